@@ -11,6 +11,7 @@
 
 use ncomm_core::Executor;
 use ncomm_executors::SimpleExecutor;
+use ncomm_publishers_and_subscribers::local::LocalTopics;
 
 use crossbeam::channel::unbounded;
 use ctrlc;
@@ -31,8 +32,7 @@ pub enum NodeIdentifier {
 }
 
 fn main() {
-    let mut publisher_node = MinimalPublisher::new();
-    let subscriber_node = MinimalSubscriber::new(publisher_node.create_subscriber());
+    let mut topics = LocalTopics::new();
 
     let (tx, rx) = unbounded();
     ctrlc::set_handler(move || tx.send(true).expect("Could not send interrupt"))
@@ -40,7 +40,10 @@ fn main() {
 
     let mut executor = SimpleExecutor::new_with(
         rx,
-        vec![Box::new(publisher_node), Box::new(subscriber_node)],
+        vec![
+            Box::new(topics.build::<MinimalPublisher>()),
+            Box::new(topics.build::<MinimalSubscriber>()),
+        ],
     );
 
     executor.update_loop();
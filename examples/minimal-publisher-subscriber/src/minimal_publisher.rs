@@ -6,7 +6,7 @@
 use super::NodeIdentifier;
 
 use ncomm_core::{Node, Publisher};
-use ncomm_publishers_and_subscribers::local::{LocalPublisher, LocalSubscriber};
+use ncomm_publishers_and_subscribers::local::{FromTopics, LocalPublisher, LocalTopics};
 
 /// A minimal publisher node that publishes the string "Hello World! {count}"
 pub struct MinimalPublisher {
@@ -14,20 +14,16 @@ pub struct MinimalPublisher {
     publisher: LocalPublisher<String>,
 }
 
-impl MinimalPublisher {
-    /// Create a new minimal publisher
-    pub fn new() -> Self {
+impl FromTopics for MinimalPublisher {
+    fn from_topics(topics: &mut LocalTopics) -> Self {
         Self {
             count: 0,
-            publisher: LocalPublisher::new(),
+            publisher: topics.publisher("topic"),
         }
     }
+}
 
-    /// Creates a new subscriber for this node's publisher
-    pub fn create_subscriber(&mut self) -> LocalSubscriber<String> {
-        self.publisher.subscribe()
-    }
-
+impl MinimalPublisher {
     /// Publish the string "Hello World! {count}" and increment
     /// the current count.
     fn publish_message(&mut self) {
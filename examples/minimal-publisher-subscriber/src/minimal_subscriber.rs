@@ -6,7 +6,7 @@
 use super::NodeIdentifier;
 
 use ncomm_core::{Node, Subscriber};
-use ncomm_publishers_and_subscribers::local::LocalSubscriber;
+use ncomm_publishers_and_subscribers::local::{FromTopics, LocalSubscriber, LocalTopics};
 
 /// Minimal Subscriber that receives the message "Hello World! {count}" and prints
 /// the message to the console.
@@ -14,12 +14,15 @@ pub struct MinimalSubscriber {
     subscriber: LocalSubscriber<String>,
 }
 
-impl MinimalSubscriber {
-    /// Creates a new MinimalSubscriber Node with a given subscriber
-    pub fn new(subscriber: LocalSubscriber<String>) -> Self {
-        Self { subscriber }
+impl FromTopics for MinimalSubscriber {
+    fn from_topics(topics: &mut LocalTopics) -> Self {
+        Self {
+            subscriber: topics.subscribe("topic"),
+        }
     }
+}
 
+impl MinimalSubscriber {
     /// Print "I heard: {data}" with the data published to it by the minimal
     /// publisher
     fn print_subscriber_data(&mut self) {
@@ -6,9 +6,14 @@
 //! from servers to clients.
 //!
 
-use std::{collections::HashMap, convert::Infallible, hash::Hash};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    hash::Hash,
+    time::{Duration, Instant},
+};
 
-use crossbeam::channel::{self, Receiver, Sender};
+use crossbeam::channel::{self, Receiver, RecvTimeoutError, Sender};
 
 use ncomm_core::{Client, Server};
 
@@ -21,13 +26,60 @@ pub struct LocalClient<Req, Res> {
     tx: Sender<Req>,
 }
 
+/// An Error when a blocking call over a local client doesn't receive a response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalCallError {
+    /// No response to the request was received within the timeout
+    Timeout,
+    /// The server was dropped (or removed the client) so no response can be received
+    Disconnected,
+}
+
+impl std::fmt::Display for LocalCallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "timed out waiting for a response"),
+            Self::Disconnected => write!(f, "the server disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for LocalCallError {}
+
+impl<Req: PartialEq + Clone, Res> LocalClient<Req, Res> {
+    /// Send a request and block until the server responds to it (or the timeout
+    /// elapses), returning the response.
+    ///
+    /// This makes one-shot requests convenient outside of a node's update (i.e. in
+    /// a script), where polling for the response isn't practical.
+    ///
+    /// Note: Responses to other requests received while waiting are dropped.
+    pub fn call(&self, request: Req, timeout: Duration) -> Result<Res, LocalCallError> {
+        let deadline = Instant::now() + timeout;
+        self.tx
+            .send(request.clone())
+            .map_err(|_| LocalCallError::Disconnected)?;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.rx.recv_timeout(remaining) {
+                Ok((req, res)) if req == request => return Ok(res),
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => return Err(LocalCallError::Timeout),
+                Err(RecvTimeoutError::Disconnected) => return Err(LocalCallError::Disconnected),
+            }
+        }
+    }
+}
+
 impl<Req, Res> Client for LocalClient<Req, Res> {
     type Request = Req;
     type Response = Res;
     type Error = Infallible;
 
     fn send_request(&mut self, request: Self::Request) -> Result<(), Self::Error> {
-        self.tx.send(request).unwrap();
+        // The request is dropped if the server has removed this client
+        let _ = self.tx.send(request);
         Ok(())
     }
 
@@ -51,7 +103,9 @@ impl<Req, Res> Client for LocalClient<Req, Res> {
 
 /// A local server that receives data via a crossbeam channel and sends
 /// data back via another crossbeam channel.
-pub struct LocalServer<Req, Res, K: Hash + Eq + Clone> {
+///
+/// Clients are identified by a key of type K (a String by default).
+pub struct LocalServer<Req, Res, K: Hash + Eq + Clone = String> {
     /// A map between client identifiers and their respective
     /// request receivers and response senders
     #[allow(clippy::type_complexity)]
@@ -82,6 +136,18 @@ impl<Req, Res, K: Hash + Eq + Clone> LocalServer<Req, Res, K> {
             tx: req_tx,
         }
     }
+
+    /// Remove a client from the server, returning whether the client existed.
+    ///
+    /// Requests from a removed client are dropped and it no longer receives responses.
+    pub fn remove_client(&mut self, key: &K) -> bool {
+        self.client_map.remove(key).is_some()
+    }
+
+    /// Get the keys of the clients of the server
+    pub fn get_clients(&self) -> Vec<K> {
+        self.client_map.keys().cloned().collect()
+    }
 }
 
 impl<Req, Res, K: Hash + Eq + Clone> Server for LocalServer<Req, Res, K> {
@@ -197,4 +263,120 @@ mod tests {
             assert!(false, "Expected to receive response");
         }
     }
+
+    #[test]
+    fn test_local_server_remove_client() {
+        let mut server = LocalServer::new();
+        let mut client_one = server.create_client(0u8);
+        let mut client_two = server.create_client(1u8);
+
+        let request = Request::new();
+        client_one.send_request(request).unwrap();
+        client_two.send_request(request).unwrap();
+
+        assert!(server.remove_client(&1));
+        assert!(!server.remove_client(&1));
+        assert_eq!(server.get_clients(), vec![0]);
+
+        // The removed client's requests are no longer received
+        let mut requests = server.poll_for_requests();
+        assert_eq!(requests.len(), 1);
+        let Ok((key, request)) = requests.remove(0);
+        assert_eq!(key, 0);
+
+        // and it no longer receives responses
+        server
+            .send_response(1, request, Response::new(request))
+            .unwrap();
+        server
+            .send_response(0, request, Response::new(request))
+            .unwrap();
+        assert!(client_two.poll_for_response().unwrap().is_none());
+        assert!(client_one.poll_for_response().unwrap().is_some());
+
+        client_two.send_request(request).unwrap();
+        assert!(server.poll_for_requests().is_empty());
+    }
+
+    #[test]
+    fn test_send_many_requests_from_same_client() {
+        let mut server: LocalServer<Request, Response, u8> = LocalServer::new();
+        let mut client = server.create_client(0u8);
+
+        let original_requests = [Request::new(), Request::new(), Request::new()];
+        for request in original_requests {
+            client.send_request(request).unwrap();
+        }
+
+        let requests: Vec<Request> = server
+            .poll_for_requests()
+            .into_iter()
+            .map(|request| {
+                let Ok((_, request)) = request;
+                request
+            })
+            .collect();
+        assert_eq!(requests, original_requests);
+    }
+
+    #[test]
+    fn test_local_server_keys() {
+        let mut server: LocalServer<Request, Response> = LocalServer::new();
+        let mut named_client = server.create_client(String::from("client"));
+        named_client.send_request(Request::new()).unwrap();
+        let Ok(Some((key, _))) = server.poll_for_request() else {
+            panic!("Expected a request");
+        };
+        assert_eq!(key, "client");
+
+        let mut server: LocalServer<Request, Response, u32> = LocalServer::new();
+        let mut numbered_client = server.create_client(42);
+        let request = Request::new();
+        numbered_client.send_request(request).unwrap();
+        let Ok(Some((key, request))) = server.poll_for_request() else {
+            panic!("Expected a request");
+        };
+        assert_eq!(key, 42);
+        server
+            .send_response(key, request, Response::new(request))
+            .unwrap();
+        assert_eq!(
+            numbered_client.poll_for_response().unwrap(),
+            Some((request, Response::new(request)))
+        );
+    }
+
+    #[test]
+    fn test_local_client_call() {
+        let mut server = LocalServer::new();
+        let client = server.create_client(0u8);
+
+        let handle = std::thread::spawn(move || loop {
+            if let Ok(Some((client, request))) = server.poll_for_request() {
+                server
+                    .send_response(client, request, Response::new(request))
+                    .unwrap();
+                return server;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        });
+
+        let request = Request::new();
+        assert_eq!(
+            client.call(request, Duration::from_secs(1)),
+            Ok(Response::new(request))
+        );
+
+        // The server is still alive but no longer answering requests
+        let server = handle.join().unwrap();
+        assert_eq!(
+            client.call(Request::new(), Duration::from_millis(10)),
+            Err(LocalCallError::Timeout)
+        );
+        drop(server);
+        assert_eq!(
+            client.call(Request::new(), Duration::from_millis(10)),
+            Err(LocalCallError::Disconnected)
+        );
+    }
 }
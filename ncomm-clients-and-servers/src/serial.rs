@@ -8,7 +8,10 @@ use core::marker::PhantomData;
 use embedded_io::{Error, Read, ReadReady, Write};
 
 use ncomm_core::client_server::{Client, Server};
-use ncomm_utils::packing::{Packable, PackingError};
+use ncomm_utils::{
+    cobs,
+    packing::{Packable, PackingError},
+};
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
@@ -258,3 +261,498 @@ where
         Ok(())
     }
 }
+
+/// Decoder reassembling COBS-encoded frames from the bytes read from a serial
+/// device (which may split a frame across multiple reads).
+struct FrameDecoder<const BUFFER_SIZE: usize> {
+    /// The encoded bytes of the current frame
+    frame: [u8; BUFFER_SIZE],
+    /// The number of bytes of the current frame in the buffer
+    frame_len: usize,
+    /// Whether the current frame overflowed the buffer (and should be discarded)
+    overflowed: bool,
+}
+
+impl<const BUFFER_SIZE: usize> FrameDecoder<BUFFER_SIZE> {
+    /// Create a new FrameDecoder without any received bytes
+    fn new() -> Self {
+        Self {
+            frame: [0u8; BUFFER_SIZE],
+            frame_len: 0,
+            overflowed: false,
+        }
+    }
+
+    /// Read bytes from the serial device until a frame is completed (or there is
+    /// no more data to read), decoding the frame into `decoded` and returning the
+    /// length of the decoded frame.
+    ///
+    /// Note: Bytes are read one at a time so bytes following a completed frame are
+    /// left on the serial device for the next call.
+    fn read_frame<Serial: ReadReady + Read>(
+        &mut self,
+        serial_device: &mut Serial,
+        decoded: &mut [u8],
+    ) -> Option<usize> {
+        let mut byte = [0u8; 1];
+        while let Ok(true) = serial_device.read_ready() {
+            match serial_device.read(&mut byte) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => (),
+            }
+
+            if byte[0] != cobs::DELIMITER {
+                if self.frame_len < BUFFER_SIZE {
+                    self.frame[self.frame_len] = byte[0];
+                    self.frame_len += 1;
+                } else {
+                    self.overflowed = true;
+                }
+                continue;
+            }
+
+            let frame = if self.overflowed || self.frame_len == 0 {
+                None
+            } else {
+                cobs::decode(&self.frame[..self.frame_len], decoded).ok()
+            };
+            self.frame_len = 0;
+            self.overflowed = false;
+            if frame.is_some() {
+                return frame;
+            }
+        }
+        None
+    }
+}
+
+/// Client that sends COBS-framed requests and receives COBS-framed responses via
+/// a serial device.
+///
+/// Framing each message (instead of sending the raw packed bytes) lets the client
+/// find the boundaries between responses even if bytes are lost or a response is
+/// split across multiple reads.
+///
+/// Note: To make this client no_std compatible the client has internal buffers that
+/// are statically allocated, hence the reason for the const BUFFER_SIZE: usize
+/// generic.  The buffer must be large enough to fit an encoded request and response
+/// (see `ncomm_utils::cobs::max_encoded_len`).
+pub struct SerialFramedClient<
+    Req: Packable,
+    Res: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err> + Write<Error = Err>,
+    Err: Error,
+    const BUFFER_SIZE: usize,
+> {
+    /// The serial peripheral device
+    serial_device: Serial,
+    /// The internal buffer for packing and unpacking data
+    buffer: [u8; BUFFER_SIZE],
+    /// The internal buffer requests are encoded into
+    encoded: [u8; BUFFER_SIZE],
+    /// The decoder for frames received from the server
+    decoder: FrameDecoder<BUFFER_SIZE>,
+    /// A marker to bind the type of data
+    _phantom: PhantomData<(Req, Res)>,
+}
+
+impl<Req, Res, Serial, Err, const BUFFER_SIZE: usize>
+    SerialFramedClient<Req, Res, Serial, Err, BUFFER_SIZE>
+where
+    Req: Packable,
+    Res: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err> + Write<Error = Err>,
+    Err: Error,
+{
+    /// Construct a new SerialFramedClient from a serial peripheral
+    pub fn new(serial_device: Serial, buffer: [u8; BUFFER_SIZE]) -> Self {
+        assert!(
+            BUFFER_SIZE >= cobs::max_encoded_len(Req::len() + Res::len()),
+            "The buffer must be large enough to fit an encoded request and response"
+        );
+        Self {
+            serial_device,
+            buffer,
+            encoded: [0u8; BUFFER_SIZE],
+            decoder: FrameDecoder::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Destroy the SerialFramedClient, returning the serial peripheral
+    pub fn destroy(self) -> Serial {
+        self.serial_device
+    }
+
+    /// Read the next complete response frame from the server
+    fn read_response(&mut self) -> Option<(Req, Res)> {
+        while let Some(len) = self
+            .decoder
+            .read_frame(&mut self.serial_device, &mut self.buffer)
+        {
+            if len != Req::len() + Res::len() {
+                continue;
+            }
+
+            if let (Ok(request), Ok(response)) = (
+                Req::unpack(&self.buffer[..Req::len()]),
+                Res::unpack(&self.buffer[Req::len()..len]),
+            ) {
+                return Some((request, response));
+            }
+        }
+        None
+    }
+}
+
+impl<Req, Res, Serial, Err, const BUFFER_SIZE: usize> Client
+    for SerialFramedClient<Req, Res, Serial, Err, BUFFER_SIZE>
+where
+    Req: Packable,
+    Res: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err> + Write<Error = Err>,
+    Err: Error,
+{
+    type Request = Req;
+    type Response = Res;
+    type Error = SerialClientServerError<Err>;
+
+    fn send_request(&mut self, request: Self::Request) -> Result<(), Self::Error> {
+        let buffer = &mut self.buffer[..Req::len()];
+        buffer.iter_mut().for_each(|v| *v = 0);
+        request
+            .pack(buffer)
+            .map_err(SerialClientServerError::PackingError)?;
+        let encoded_len = cobs::encode(buffer, &mut self.encoded)
+            .map_err(SerialClientServerError::PackingError)?;
+
+        self.serial_device
+            .write_all(&self.encoded[..encoded_len])
+            .map_err(SerialClientServerError::IOError)?;
+
+        Ok(())
+    }
+
+    fn poll_for_response(
+        &mut self,
+    ) -> Result<Option<(Self::Request, Self::Response)>, Self::Error> {
+        Ok(self.read_response())
+    }
+
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    fn poll_for_responses(&mut self) -> Vec<Result<(Self::Request, Self::Response), Self::Error>> {
+        let mut responses = Vec::new();
+        while let Some(response) = self.read_response() {
+            responses.push(Ok(response));
+        }
+        responses
+    }
+}
+
+/// A serial server capable of receiving COBS-framed requests and sending
+/// COBS-framed responses.
+///
+/// Note: To make this server no_std compatible the server has internal buffers that
+/// are statically allocated, hence the reason for the const BUFFER_SIZE: usize
+/// generic.  The buffer must be large enough to fit an encoded request and response
+/// (see `ncomm_utils::cobs::max_encoded_len`).
+pub struct SerialFramedServer<
+    Req: Packable,
+    Res: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err> + Write<Error = Err>,
+    Err: Error,
+    const BUFFER_SIZE: usize,
+> {
+    /// The serial peripheral device
+    serial_device: Serial,
+    /// The internal buffer for packing and unpacking data
+    buffer: [u8; BUFFER_SIZE],
+    /// The internal buffer responses are encoded into
+    encoded: [u8; BUFFER_SIZE],
+    /// The decoder for frames received from the client
+    decoder: FrameDecoder<BUFFER_SIZE>,
+    /// A holder for the request and response data type
+    _phantom: PhantomData<(Req, Res)>,
+}
+
+impl<Req, Res, Serial, Err, const BUFFER_SIZE: usize>
+    SerialFramedServer<Req, Res, Serial, Err, BUFFER_SIZE>
+where
+    Req: Packable,
+    Res: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err> + Write<Error = Err>,
+    Err: Error,
+{
+    /// Create a new SerialFramedServer from a serial device peripheral
+    pub fn new(serial_device: Serial, buffer: [u8; BUFFER_SIZE]) -> Self {
+        assert!(
+            BUFFER_SIZE >= cobs::max_encoded_len(Req::len() + Res::len()),
+            "The buffer must be large enough to fit an encoded request and response"
+        );
+        Self {
+            serial_device,
+            buffer,
+            encoded: [0u8; BUFFER_SIZE],
+            decoder: FrameDecoder::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Destroy the SerialFramedServer returning the serial peripheral
+    pub fn destroy(self) -> Serial {
+        self.serial_device
+    }
+
+    /// Read the next complete request frame from the client
+    fn read_request(&mut self) -> Option<Req> {
+        while let Some(len) = self
+            .decoder
+            .read_frame(&mut self.serial_device, &mut self.buffer)
+        {
+            if len != Req::len() {
+                continue;
+            }
+
+            if let Ok(request) = Req::unpack(&self.buffer[..len]) {
+                return Some(request);
+            }
+        }
+        None
+    }
+}
+
+impl<Req, Res, Serial, Err, const BUFFER_SIZE: usize> Server
+    for SerialFramedServer<Req, Res, Serial, Err, BUFFER_SIZE>
+where
+    Req: Packable,
+    Res: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err> + Write<Error = Err>,
+    Err: Error,
+{
+    type Request = Req;
+    type Response = Res;
+    type Key = bool;
+    type Error = SerialClientServerError<Err>;
+
+    fn poll_for_request(&mut self) -> Result<Option<(Self::Key, Self::Request)>, Self::Error> {
+        Ok(self.read_request().map(|request| (true, request)))
+    }
+
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    fn poll_for_requests(&mut self) -> Vec<Result<(Self::Key, Self::Request), Self::Error>> {
+        let mut requests = Vec::new();
+        while let Some(request) = self.read_request() {
+            requests.push(Ok((true, request)));
+        }
+        requests
+    }
+
+    fn send_response(
+        &mut self,
+        _client_key: Self::Key,
+        request: Self::Request,
+        response: Self::Response,
+    ) -> Result<(), Self::Error> {
+        let buffer = &mut self.buffer[..(Req::len() + Res::len())];
+        buffer.iter_mut().for_each(|v| *v = 0);
+        request
+            .pack(&mut buffer[..Req::len()])
+            .map_err(SerialClientServerError::PackingError)?;
+        response
+            .pack(&mut buffer[Req::len()..])
+            .map_err(SerialClientServerError::PackingError)?;
+        let encoded_len = cobs::encode(buffer, &mut self.encoded)
+            .map_err(SerialClientServerError::PackingError)?;
+
+        self.serial_device
+            .write_all(&self.encoded[..encoded_len])
+            .map_err(SerialClientServerError::IOError)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{cell::RefCell, collections::VecDeque, convert::Infallible, rc::Rc};
+
+    use embedded_io::ErrorType;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Data {
+        num: u64,
+    }
+
+    impl Packable for Data {
+        fn len() -> usize {
+            8
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            if buffer.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
+            } else {
+                buffer[..8].copy_from_slice(&self.num.to_le_bytes());
+                Ok(())
+            }
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            if data.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
+            } else {
+                Ok(Self {
+                    num: u64::from_le_bytes(data[..8].try_into().unwrap()),
+                })
+            }
+        }
+    }
+
+    /// One end of a mock serial line that reads the bytes written by the other end
+    /// of the line, at most `chunk_size` bytes at a time.
+    struct SerialEnd {
+        rx: Rc<RefCell<VecDeque<u8>>>,
+        tx: Rc<RefCell<VecDeque<u8>>>,
+        chunk_size: usize,
+    }
+
+    /// Create both ends of a mock serial line
+    fn serial_line(chunk_size: usize) -> (SerialEnd, SerialEnd) {
+        let a = Rc::new(RefCell::new(VecDeque::new()));
+        let b = Rc::new(RefCell::new(VecDeque::new()));
+        (
+            SerialEnd {
+                rx: a.clone(),
+                tx: b.clone(),
+                chunk_size,
+            },
+            SerialEnd {
+                rx: b,
+                tx: a,
+                chunk_size,
+            },
+        )
+    }
+
+    impl ErrorType for SerialEnd {
+        type Error = Infallible;
+    }
+
+    impl Write for SerialEnd {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.tx.borrow_mut().extend(buf.iter());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl Read for SerialEnd {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let mut bytes = self.rx.borrow_mut();
+            let read = buf.len().min(bytes.len()).min(self.chunk_size);
+            for (idx, byte) in bytes.drain(..read).enumerate() {
+                buf[idx] = byte;
+            }
+            Ok(read)
+        }
+    }
+
+    impl ReadReady for SerialEnd {
+        fn read_ready(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.rx.borrow().is_empty())
+        }
+    }
+
+    #[test]
+    fn test_framed_client_server() {
+        let (client_end, server_end) = serial_line(64);
+        let mut client: SerialFramedClient<Data, Data, _, _, 32> =
+            SerialFramedClient::new(client_end, [0u8; 32]);
+        let mut server: SerialFramedServer<Data, Data, _, _, 32> =
+            SerialFramedServer::new(server_end, [0u8; 32]);
+
+        assert!(client.poll_for_response().unwrap().is_none());
+        assert!(server.poll_for_request().unwrap().is_none());
+
+        // The packed requests contain zero bytes that have to be stuffed
+        let requests = [
+            Data { num: 0 },
+            Data {
+                num: 0x0100_0000_0000_0002,
+            },
+        ];
+        for request in requests {
+            client.send_request(request).unwrap();
+        }
+
+        // Noise on the line before a delimiter is discarded
+        server.serial_device.rx.borrow_mut().push_front(0x00);
+        server.serial_device.rx.borrow_mut().push_front(0x42);
+
+        for request in server.poll_for_requests() {
+            let (key, request) = request.unwrap();
+            server
+                .send_response(
+                    key,
+                    request,
+                    Data {
+                        num: request.num * 3,
+                    },
+                )
+                .unwrap();
+        }
+
+        let responses: Vec<(Data, Data)> = client
+            .poll_for_responses()
+            .into_iter()
+            .map(|response| response.unwrap())
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                (Data { num: 0 }, Data { num: 0 }),
+                (
+                    Data {
+                        num: 0x0100_0000_0000_0002
+                    },
+                    Data {
+                        num: 0x0300_0000_0000_0006
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_framed_client_server_single_request() {
+        let (client_end, server_end) = serial_line(3);
+        let mut client: SerialFramedClient<Data, Data, _, _, 32> =
+            SerialFramedClient::new(client_end, [0u8; 32]);
+        let mut server: SerialFramedServer<Data, Data, _, _, 32> =
+            SerialFramedServer::new(server_end, [0u8; 32]);
+
+        client.send_request(Data { num: 7 }).unwrap();
+        let (key, request) = server.poll_for_request().unwrap().unwrap();
+        assert_eq!(request, Data { num: 7 });
+        server
+            .send_response(key, request, Data { num: 14 })
+            .unwrap();
+
+        assert_eq!(
+            client.poll_for_response().unwrap(),
+            Some((Data { num: 7 }, Data { num: 14 }))
+        );
+        assert!(client.poll_for_response().unwrap().is_none());
+    }
+}
@@ -7,9 +7,11 @@
 //!
 
 use std::{
+    collections::HashMap,
     io::Error,
     marker::PhantomData,
     net::{SocketAddr, UdpSocket},
+    time::{Duration, Instant},
 };
 
 use ncomm_core::{Client, Server};
@@ -26,6 +28,8 @@ pub enum UdpClientServerError<Data: Packable> {
     UnknownRequester((Data, SocketAddr)),
     /// The client you are sending data to is unknown
     UnknownClient,
+    /// No response was received for the request before its deadline passed
+    TimedOut(Data),
 }
 
 /// A udp client that sends requests via a UdpSocket to a specific
@@ -35,6 +39,11 @@ pub struct UdpClient<Req: Packable, Res: Packable> {
     socket: UdpSocket,
     /// The address to send data to
     address: SocketAddr,
+    /// The sequence number of the next request sent with a timeout
+    next_sequence: u64,
+    /// The packed requests (and their deadlines) sent with a timeout that have not
+    /// yet been responded to, keyed by their sequence number
+    outstanding: HashMap<u64, (Vec<u8>, Instant)>,
     /// A PhantomData to bind the specific request and response type to the
     /// client
     phantom: PhantomData<(Req, Res)>,
@@ -49,9 +58,68 @@ impl<Req: Packable, Res: Packable> UdpClient<Req, Res> {
         Ok(Self {
             socket,
             address: server_address,
+            next_sequence: 0,
+            outstanding: HashMap::new(),
             phantom: PhantomData,
         })
     }
+
+    /// Send a request to the server that should be responded to within a given
+    /// timeout.
+    ///
+    /// If no response to the request is received before the timeout elapses, polling
+    /// for responses will return a `UdpClientServerError::TimedOut` error containing
+    /// the request.
+    pub fn send_request_with_timeout(
+        &mut self,
+        request: Req,
+        timeout: Duration,
+    ) -> Result<(), UdpClientServerError<Req>> {
+        let mut buffer = vec![0u8; Req::len()];
+        request
+            .pack(&mut buffer)
+            .map_err(UdpClientServerError::PackingError)?;
+
+        self.socket
+            .send_to(&buffer, self.address)
+            .map_err(UdpClientServerError::IOError)?;
+
+        self.outstanding
+            .insert(self.next_sequence, (buffer, Instant::now() + timeout));
+        self.next_sequence = self.next_sequence.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Stop tracking the outstanding request (if any) the packed request belongs to
+    fn complete_request(&mut self, packed_request: &[u8]) {
+        let sequence = self
+            .outstanding
+            .iter()
+            .filter(|(_, (request, _))| request == packed_request)
+            .map(|(sequence, _)| *sequence)
+            .min();
+        if let Some(sequence) = sequence {
+            self.outstanding.remove(&sequence);
+        }
+    }
+
+    /// Remove the outstanding request whose deadline passed first (if any deadline
+    /// has passed), returning a timed out error for the request.
+    fn pop_timed_out(&mut self) -> Option<UdpClientServerError<Req>> {
+        let now = Instant::now();
+        let sequence = self
+            .outstanding
+            .iter()
+            .filter(|(_, (_, deadline))| *deadline <= now)
+            .min_by_key(|(sequence, (_, deadline))| (*deadline, **sequence))
+            .map(|(sequence, _)| *sequence)?;
+
+        let (request, _) = self.outstanding.remove(&sequence)?;
+        Some(match Req::unpack(&request) {
+            Ok(request) => UdpClientServerError::TimedOut(request),
+            Err(err) => UdpClientServerError::PackingError(err),
+        })
+    }
 }
 
 impl<Req: Packable, Res: Packable> Client for UdpClient<Req, Res> {
@@ -71,16 +139,28 @@ impl<Req: Packable, Res: Packable> Client for UdpClient<Req, Res> {
         Ok(())
     }
 
+    /// Check the UDP socket for an incoming response.
+    ///
+    /// Note: If no response is available and a request sent with a timeout has passed
+    /// its deadline, a `UdpClientServerError::TimedOut` error is returned instead.
     fn poll_for_response(
         &mut self,
     ) -> Result<Option<(Self::Request, Self::Response)>, Self::Error> {
         let mut buffer = vec![0u8; Req::len() + Res::len()];
         let (req, res) = match self.socket.recv(&mut buffer) {
-            Ok(_received) => (
-                Req::unpack(&buffer[..Req::len()]),
-                Res::unpack(&buffer[Req::len()..]),
-            ),
-            Err(_) => return Ok(None),
+            Ok(_received) => {
+                self.complete_request(&buffer[..Req::len()]);
+                (
+                    Req::unpack(&buffer[..Req::len()]),
+                    Res::unpack(&buffer[Req::len()..]),
+                )
+            }
+            Err(_) => {
+                return match self.pop_timed_out() {
+                    Some(err) => Err(err),
+                    None => Ok(None),
+                }
+            }
         };
 
         if let (Ok(req), Ok(res)) = (req, res) {
@@ -94,16 +174,22 @@ impl<Req: Packable, Res: Packable> Client for UdpClient<Req, Res> {
     ///
     /// Note: Incoming data will be in the form:
     /// \[request\[0\], request\[1\], ..., request\[-1\], response\[0\], response\[1\], ...\]
+    ///
+    /// Additionally, a `UdpClientServerError::TimedOut` error is returned for every
+    /// request sent with a timeout that has passed its deadline without a response.
     fn poll_for_responses(&mut self) -> Vec<Result<(Self::Request, Self::Response), Self::Error>> {
         let mut responses = Vec::new();
 
         let mut buffer = vec![0u8; Req::len() + Res::len()];
         loop {
             let (req, res) = match self.socket.recv(&mut buffer) {
-                Ok(_received) => (
-                    Req::unpack(&buffer[..Req::len()]),
-                    Res::unpack(&buffer[Req::len()..]),
-                ),
+                Ok(_received) => {
+                    self.complete_request(&buffer[..Req::len()]);
+                    (
+                        Req::unpack(&buffer[..Req::len()]),
+                        Res::unpack(&buffer[Req::len()..]),
+                    )
+                }
                 Err(_) => break,
             };
             buffer.iter_mut().for_each(|v| *v = 0);
@@ -113,6 +199,10 @@ impl<Req: Packable, Res: Packable> Client for UdpClient<Req, Res> {
             }
         }
 
+        while let Some(err) = self.pop_timed_out() {
+            responses.push(Err(err));
+        }
+
         responses
     }
 }
@@ -163,6 +253,23 @@ impl<Req: Packable, Res: Packable, K: Eq + Clone> UdpServer<Req, Res, K> {
     pub fn add_clients(&mut self, mut clients: Vec<(K, SocketAddr)>) {
         self.client_addresses.append(&mut clients);
     }
+
+    /// Remove a client from the server, returning whether the client existed.
+    ///
+    /// Requests from a removed client are reported as coming from an unknown requester.
+    pub fn remove_client(&mut self, key: &K) -> bool {
+        let clients = self.client_addresses.len();
+        self.client_addresses.retain(|(k, _)| k != key);
+        self.client_addresses.len() != clients
+    }
+
+    /// Get the keys of the clients of the server
+    pub fn get_clients(&self) -> Vec<K> {
+        self.client_addresses
+            .iter()
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
 }
 
 impl<Req: Packable, Res: Packable, K: Eq + Clone> Server for UdpServer<Req, Res, K> {
@@ -272,7 +379,10 @@ mod tests {
 
         fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
             if buffer.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
             } else {
                 Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
             }
@@ -280,7 +390,10 @@ mod tests {
 
         fn unpack(data: &[u8]) -> Result<Self, PackingError> {
             if data.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
             } else {
                 Ok(Self {
                     num: u64::from_le_bytes(data[..8].try_into().unwrap()),
@@ -309,7 +422,10 @@ mod tests {
 
         fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
             if buffer.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
             } else {
                 Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
             }
@@ -317,7 +433,10 @@ mod tests {
 
         fn unpack(data: &[u8]) -> Result<Self, PackingError> {
             if data.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
             } else {
                 Ok(Self {
                     num: u64::from_le_bytes(data[..8].try_into().unwrap()),
@@ -438,4 +557,79 @@ mod tests {
             assert!(false, "Expected to receive response");
         }
     }
+
+    #[test]
+    fn test_udp_client_request_timeout() {
+        // The server is bound but never responds
+        let _server: UdpServer<Request, Response, i32> = UdpServer::new_with(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7010)),
+            vec![(
+                0,
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7011)),
+            )],
+        )
+        .unwrap();
+
+        let mut client: UdpClient<Request, Response> = UdpClient::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7011)),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7010)),
+        )
+        .unwrap();
+
+        let request = Request::new();
+        client
+            .send_request_with_timeout(request, Duration::from_millis(50))
+            .unwrap();
+
+        sleep(Duration::from_millis(25));
+        assert!(matches!(client.poll_for_response(), Ok(None)));
+
+        sleep(Duration::from_millis(35));
+        match client.poll_for_response() {
+            Err(UdpClientServerError::TimedOut(timed_out)) => assert_eq!(timed_out, request),
+            _ => assert!(false, "Expected the request to time out"),
+        }
+
+        // The timeout should only be reported once
+        assert!(matches!(client.poll_for_response(), Ok(None)));
+        assert!(client.poll_for_responses().is_empty());
+    }
+
+    #[test]
+    fn test_udp_client_request_timeout_responded() {
+        let mut server: UdpServer<Request, Response, i32> = UdpServer::new_with(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7012)),
+            vec![(
+                0,
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7013)),
+            )],
+        )
+        .unwrap();
+
+        let mut client: UdpClient<Request, Response> = UdpClient::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7013)),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7012)),
+        )
+        .unwrap();
+
+        let request = Request::new();
+        client
+            .send_request_with_timeout(request, Duration::from_millis(50))
+            .unwrap();
+
+        sleep(Duration::from_millis(10));
+        let (k, request) = server.poll_for_request().unwrap().unwrap();
+        server
+            .send_response(k, request, Response::new(request))
+            .unwrap();
+
+        sleep(Duration::from_millis(60));
+        let responses = client.poll_for_responses();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(
+            responses[0].as_ref().unwrap(),
+            &(request, Response::new(request))
+        );
+        assert!(matches!(client.poll_for_response(), Ok(None)));
+    }
 }
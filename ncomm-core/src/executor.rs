@@ -10,9 +10,9 @@
 use crate::node::Node;
 
 #[cfg(feature = "alloc")]
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec::Vec};
 #[cfg(feature = "std")]
-use std::boxed::Box;
+use std::{boxed::Box, vec::Vec};
 
 /// The current state an executor is in.
 ///
@@ -29,6 +29,9 @@ pub enum ExecutorState {
     Started,
     /// The nodes in the executor are current being updated
     Running,
+    /// The nodes in the executor have been started but are temporarily not
+    /// being updated
+    Paused,
 }
 
 /// An executor handles the scheduling and execution of nodes
@@ -48,6 +51,21 @@ pub trait Executor<ID: PartialEq> {
     /// Run the update loop until the executor's interrupt is called
     fn update_loop(&mut self);
 
+    /// Pause the executor, temporarily stopping node updates without shutting
+    /// the nodes down.
+    ///
+    /// Note: Executors that do not support pausing will ignore this call.
+    fn pause(&mut self) {}
+
+    /// Resume a paused executor, continuing node updates from where they
+    /// were paused.
+    ///
+    /// Note: Executors that do not support pausing will ignore this call.
+    fn resume(&mut self) {}
+
+    /// Get the current state of the executor
+    fn state(&self) -> ExecutorState;
+
     /// Check whether the program has been interrupted
     ///
     /// Note: This should be called between each Node execution
@@ -66,4 +84,108 @@ pub trait Executor<ID: PartialEq> {
 
     /// Remove a node from the executor.
     fn remove_node(&mut self, id: &ID) -> Option<Box<dyn Node<ID>>>;
+
+    /// Get the ids of all of the nodes contained by the executor.
+    ///
+    /// Note: Node ids are returned by value because nodes report their ids
+    /// via `Node::get_id`, which returns an owned id.
+    fn node_ids(&self) -> Vec<ID>;
+
+    /// Check whether the executor contains a node with a given id.
+    fn contains_node(&self, id: &ID) -> bool {
+        self.node_ids().iter().any(|node_id| node_id.eq(id))
+    }
+}
+
+/// An object-safe view of an executor without the executor's context so
+/// executors of different types can be stored together (i.e. in a
+/// `Vec<Box<dyn DynExecutor<ID>>>`) and managed by supervisory code.
+///
+/// DynExecutor is implemented for every executor.
+///
+/// Note: Because every executor implements both traits, calling these methods on
+/// a concrete executor with both `Executor` and `DynExecutor` in scope is
+/// ambiguous, so DynExecutor isn't re-exported from the crate root (or the ncomm
+/// prelude) and should only be imported where executors are used as trait objects.
+pub trait DynExecutor<ID: PartialEq> {
+    /// Starts the nodes contained by the executor
+    fn start(&mut self);
+
+    /// Run the update loop for a set amount of time (in milliseconds)
+    fn update_for_ms(&mut self, ms: u128);
+
+    /// Run the update loop until the executor's interrupt is called
+    fn update_loop(&mut self);
+
+    /// Pause the executor, temporarily stopping node updates without shutting
+    /// the nodes down.
+    fn pause(&mut self);
+
+    /// Resume a paused executor, continuing node updates from where they
+    /// were paused.
+    fn resume(&mut self);
+
+    /// Get the current state of the executor
+    fn state(&self) -> ExecutorState;
+
+    /// Check whether the program has been interrupted
+    fn check_interrupt(&mut self) -> bool;
+
+    /// Add a node to the executor.
+    fn add_node(&mut self, node: Box<dyn Node<ID>>);
+
+    /// Remove a node from the executor.
+    fn remove_node(&mut self, id: &ID) -> Option<Box<dyn Node<ID>>>;
+
+    /// Get the ids of all of the nodes contained by the executor.
+    fn node_ids(&self) -> Vec<ID>;
+
+    /// Check whether the executor contains a node with a given id.
+    fn contains_node(&self, id: &ID) -> bool;
+}
+
+impl<ID: PartialEq, E: Executor<ID>> DynExecutor<ID> for E {
+    fn start(&mut self) {
+        Executor::start(self)
+    }
+
+    fn update_for_ms(&mut self, ms: u128) {
+        Executor::update_for_ms(self, ms)
+    }
+
+    fn update_loop(&mut self) {
+        Executor::update_loop(self)
+    }
+
+    fn pause(&mut self) {
+        Executor::pause(self)
+    }
+
+    fn resume(&mut self) {
+        Executor::resume(self)
+    }
+
+    fn state(&self) -> ExecutorState {
+        Executor::state(self)
+    }
+
+    fn check_interrupt(&mut self) -> bool {
+        Executor::check_interrupt(self)
+    }
+
+    fn add_node(&mut self, node: Box<dyn Node<ID>>) {
+        Executor::add_node(self, node)
+    }
+
+    fn remove_node(&mut self, id: &ID) -> Option<Box<dyn Node<ID>>> {
+        Executor::remove_node(self, id)
+    }
+
+    fn node_ids(&self) -> Vec<ID> {
+        Executor::node_ids(self)
+    }
+
+    fn contains_node(&self, id: &ID) -> bool {
+        Executor::contains_node(self, id)
+    }
 }
@@ -10,7 +10,9 @@
 extern crate alloc;
 
 pub mod node;
-pub use node::Node;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use node::NodeError;
+pub use node::{Node, ShutdownReason};
 
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub mod executor;
@@ -18,7 +20,10 @@ pub mod executor;
 pub use executor::{Executor, ExecutorState};
 
 pub mod publisher_subscriber;
-pub use publisher_subscriber::{Publisher, Subscriber};
+pub use publisher_subscriber::{
+    AddressedPublisher, ConsumingSubscriber, DedupSubscriber, FilterSubscriber, MapSubscriber,
+    Publisher, Subscriber,
+};
 
 pub mod client_server;
 pub use client_server::{Client, Server};
@@ -9,6 +9,28 @@
 //! information.
 //!
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use std::boxed::Box;
+
+/// The error a Node can return from a fallible update
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub type NodeError = Box<dyn core::error::Error + Send>;
+
+/// The reason an executor is shutting a Node down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShutdownReason {
+    /// The executor finished executing normally (i.e. `update_for_ms` elapsed)
+    Completed,
+    /// The executor was interrupted (i.e. by Ctrl-C)
+    Interrupted,
+    /// The node is being removed from the executor after an error
+    Error,
+    /// The node is being removed from the executor after panicking
+    Panic,
+}
+
 /// A Node represents a singular process that performs some singular
 /// purpose
 ///
@@ -18,9 +40,51 @@ pub trait Node<ID: PartialEq>: Send {
     /// Return the node's ID
     fn get_id(&self) -> ID;
 
+    /// Return a human-readable name for the node (i.e. for tooling and monitoring
+    /// dashboards to display instead of the node's id).
+    ///
+    /// By default, the name is the node's type name.
+    fn name(&self) -> &str {
+        core::any::type_name::<Self>()
+    }
+
+    /// Return an optional human-readable description of what the node does.
+    ///
+    /// By default, nodes have no description.
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Return the names of the topics the node publishes to (i.e. for tooling that
+    /// visualizes the structure of a system).
+    ///
+    /// By default, nodes don't report any published topics.
+    fn published_topics(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Return the names of the topics the node subscribes to.
+    ///
+    /// By default, nodes don't report any subscribed topics.
+    fn subscribed_topics(&self) -> &[&str] {
+        &[]
+    }
+
     /// Return the node's update rate (in us)
+    ///
+    /// Note: executors re-read the update delay after every update so a node can
+    /// change its update rate at runtime by returning a different value (i.e. via
+    /// `set_update_delay_us`), which takes effect from the node's next update.
     fn get_update_delay_us(&self) -> u128;
 
+    /// Set the node's update rate (in us).
+    ///
+    /// By convention, nodes whose update rate can change at runtime (i.e. a sensor
+    /// that slows down when idle) should store their update delay, return it from
+    /// `get_update_delay_us`, and overwrite it here.  By default, the update rate
+    /// of a node is fixed so this method does nothing.
+    fn set_update_delay_us(&mut self, _delay_us: u128) {}
+
     /// Complete the necessary setup functionalities for a Node.
     ///
     /// Note: this method is called on Start for the executor or
@@ -28,14 +92,117 @@ pub trait Node<ID: PartialEq>: Send {
     /// begins updating nodes.
     fn start(&mut self) {}
 
+    /// Reset the node so it can continue to be updated after a failure (i.e. after
+    /// panicking during its update when the executor's policy is to restart nodes).
+    ///
+    /// Note: The node may have been left in an inconsistent state by the failure,
+    /// so this should restore any state the node relies on.  By default, this
+    /// method calls start.
+    fn reset(&mut self) {
+        self.start();
+    }
+
     /// Update is called by the executor every get_update_delay microseconds.
     ///
     /// This can be compared to Arduino's `void loop` and should include the
     /// work completed by this node every "tick".
     fn update(&mut self) {}
 
+    /// A fallible version of update that allows a node to report a (recoverable)
+    /// error to the executor.
+    ///
+    /// Executors call this method instead of update so, by default, this method
+    /// calls update and reports that the update succeeded.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn try_update(&mut self) -> Result<(), NodeError> {
+        self.update();
+        Ok(())
+    }
+
+    /// A version of try_update that is given the current time of the executor's
+    /// clock (in microseconds since the executor was started).
+    ///
+    /// Nodes that need the time (i.e. to timestamp their output consistently with
+    /// the executor's schedule) should override this method instead of creating
+    /// their own clock.  Executors call this method instead of try_update so, by
+    /// default, this method ignores the time and calls try_update.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn try_update_at(&mut self, _now_us: u128) -> Result<(), NodeError> {
+        self.try_update()
+    }
+
+    /// Called by the executor before an update when the node has fallen behind
+    /// its schedule by at least one full update period.
+    ///
+    /// `missed` is the number of full update periods that were missed, which
+    /// lets nodes (i.e. control loops) react to scheduling jitter.
+    fn on_deadline_missed(&mut self, _missed: u32) {}
+
     /// When an executor is stopped or has finished executing nodes, it will call
     /// this method on all of its nodes so this should clean up any work
     /// the node needs to do.
     fn shutdown(&mut self) {}
+
+    /// Called by executors (instead of shutdown) with the reason the node is being
+    /// shut down so nodes can react differently to an emergency interrupt than to
+    /// a normal stop (i.e. by zeroing motor outputs).
+    ///
+    /// By default, this method ignores the reason and calls shutdown.
+    fn shutdown_with_reason(&mut self, _reason: ShutdownReason) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DefaultNode;
+
+    impl Node<u8> for DefaultNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            1_000
+        }
+    }
+
+    struct DescribedNode;
+
+    impl Node<u8> for DescribedNode {
+        fn get_id(&self) -> u8 {
+            1
+        }
+
+        fn name(&self) -> &str {
+            "imu"
+        }
+
+        fn description(&self) -> Option<&str> {
+            Some("Publishes orientation from the IMU")
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            1_000
+        }
+    }
+
+    #[test]
+    fn test_node_name() {
+        let node = DefaultNode;
+        assert_eq!(node.name(), core::any::type_name::<DefaultNode>());
+        assert!(node.name().ends_with("DefaultNode"));
+        assert_eq!(node.description(), None);
+
+        // Trait objects report the name of the underlying node
+        let nodes: [&dyn Node<u8>; 2] = [&DefaultNode, &DescribedNode];
+        assert!(nodes[0].name().ends_with("DefaultNode"));
+        assert_eq!(nodes[1].name(), "imu");
+        assert_eq!(
+            nodes[1].description(),
+            Some("Publishes orientation from the IMU")
+        );
+    }
 }
@@ -18,6 +18,19 @@ pub trait Publisher {
     fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error>;
 }
 
+/// A publisher that can publish data to a subset of the addresses it
+/// publishes to.
+///
+/// Note: Publishing to specific addresses isn't supported by every publisher,
+/// so it is a separate trait implemented only by the publishers that support it.
+pub trait AddressedPublisher<Address>: Publisher {
+    /// Publish a piece of data only to the given addresses.
+    ///
+    /// Each of the addresses should be one of the addresses the publisher
+    /// publishes to.
+    fn publish_to(&mut self, data: Self::Data, addresses: &[Address]) -> Result<(), Self::Error>;
+}
+
 /// The basic subscriber trait that enables for the reading of data
 /// from some endpoint.
 ///
@@ -30,4 +43,322 @@ pub trait Subscriber {
     /// Update the current data in the subscriber and return a reference to the
     /// current data
     fn get(&mut self) -> &Self::Target;
+
+    /// Check whether there is new data waiting to be received by the subscriber
+    /// without consuming it, so `get` can be skipped when nothing has changed.
+    ///
+    /// Note: By default, subscribers report that there may always be new data.
+    fn has_update(&mut self) -> bool {
+        true
+    }
+
+    /// Create a subscriber that yields the result of applying `map` to the data in
+    /// this subscriber.
+    fn map<T, F: Fn(&Self::Target) -> T>(self, map: F) -> MapSubscriber<Self, T, F>
+    where
+        Self: Sized,
+    {
+        MapSubscriber::new(self, map)
+    }
+
+    /// Create a subscriber that tracks whether the data in this subscriber changed
+    /// between calls to `get`.
+    fn dedup(self) -> DedupSubscriber<Self>
+    where
+        Self: Sized,
+        Self::Target: PartialEq + Clone,
+    {
+        DedupSubscriber::new(self)
+    }
+
+    /// Create a subscriber that only yields the data in this subscriber that
+    /// satisfies `predicate`.
+    fn filter<Data: Clone, F: Fn(&Data) -> bool>(
+        self,
+        predicate: F,
+    ) -> FilterSubscriber<Self, Data, F>
+    where
+        Self: Sized + Subscriber<Target = Option<Data>>,
+    {
+        FilterSubscriber::new(self, predicate)
+    }
+}
+
+/// A subscriber whose current data can be taken by ownership instead of being
+/// borrowed, avoiding a clone for data that is consumed exactly once.
+///
+/// Note: Taking data isn't supported by every subscriber, so it is a separate
+/// trait implemented only by the subscribers that support it.
+pub trait ConsumingSubscriber: Subscriber {
+    /// The type of data taken from the subscriber
+    type Data;
+
+    /// Update the current data in the subscriber and take it, leaving the
+    /// subscriber without data until new data is received.
+    fn take(&mut self) -> Option<Self::Data>;
+}
+
+/// A subscriber adapter that transforms the data of an inner subscriber
+/// through a closure.
+///
+/// Note: Because `get` returns a reference, the MapSubscriber owns the most recently
+/// transformed value.  The closure is applied on every call to `get`.
+pub struct MapSubscriber<S: Subscriber, T, F: Fn(&S::Target) -> T> {
+    /// The subscriber whose data is transformed
+    subscriber: S,
+    /// The closure transforming the data
+    map: F,
+    /// The most recently transformed data
+    data: Option<T>,
+}
+
+impl<S: Subscriber, T, F: Fn(&S::Target) -> T> MapSubscriber<S, T, F> {
+    /// Create a new MapSubscriber transforming the data of `subscriber` with `map`
+    pub fn new(subscriber: S, map: F) -> Self {
+        Self {
+            subscriber,
+            map,
+            data: None,
+        }
+    }
+
+    /// Destroy the MapSubscriber, returning the inner subscriber
+    pub fn into_inner(self) -> S {
+        self.subscriber
+    }
+}
+
+impl<S: Subscriber, T, F: Fn(&S::Target) -> T> Subscriber for MapSubscriber<S, T, F> {
+    type Target = T;
+
+    fn get(&mut self) -> &Self::Target {
+        let data = (self.map)(self.subscriber.get());
+        self.data.insert(data)
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.subscriber.has_update()
+    }
+}
+
+/// A subscriber adapter that reports whether the data of an inner subscriber
+/// differs from the data returned by the previous call to `get`.
+///
+/// Note: The DedupSubscriber keeps a clone of the previous data to compare against,
+/// so downstream work can be skipped when the same value is received repeatedly.
+pub struct DedupSubscriber<S: Subscriber>
+where
+    S::Target: PartialEq + Clone,
+{
+    /// The subscriber whose data is compared
+    subscriber: S,
+    /// The data returned by the previous call to `get`
+    previous: Option<S::Target>,
+    /// Whether the data returned by the latest call to `get` differed from the
+    /// previous data
+    changed: bool,
+}
+
+impl<S: Subscriber> DedupSubscriber<S>
+where
+    S::Target: PartialEq + Clone,
+{
+    /// Create a new DedupSubscriber comparing the data of `subscriber`
+    pub fn new(subscriber: S) -> Self {
+        Self {
+            subscriber,
+            previous: None,
+            changed: false,
+        }
+    }
+
+    /// Whether the data returned by the latest call to `get` differs from the data
+    /// returned by the call before it.
+    ///
+    /// Note: The first data returned by `get` is always considered changed.
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Destroy the DedupSubscriber, returning the inner subscriber
+    pub fn into_inner(self) -> S {
+        self.subscriber
+    }
+}
+
+impl<S: Subscriber> Subscriber for DedupSubscriber<S>
+where
+    S::Target: PartialEq + Clone,
+{
+    type Target = S::Target;
+
+    fn get(&mut self) -> &Self::Target {
+        let data = self.subscriber.get();
+        self.changed = self.previous.as_ref() != Some(data);
+        if self.changed {
+            self.previous = Some(data.clone());
+        }
+        data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.subscriber.has_update()
+    }
+}
+
+/// A subscriber adapter that only updates its data with the data of an inner
+/// subscriber that satisfies a predicate, keeping the most recent data that passed
+/// (or None if no data has passed yet).
+///
+/// Note: The predicate is applied to the data returned by the inner subscriber on
+/// each call to `get`, so only the data the inner subscriber yields is filtered
+/// (i.e. the most recent data for last-value subscribers).
+pub struct FilterSubscriber<S, Data, F>
+where
+    S: Subscriber<Target = Option<Data>>,
+    Data: Clone,
+    F: Fn(&Data) -> bool,
+{
+    /// The subscriber whose data is filtered
+    subscriber: S,
+    /// The predicate data must satisfy
+    predicate: F,
+    /// The most recent data that satisfied the predicate
+    data: Option<Data>,
+}
+
+impl<S, Data, F> FilterSubscriber<S, Data, F>
+where
+    S: Subscriber<Target = Option<Data>>,
+    Data: Clone,
+    F: Fn(&Data) -> bool,
+{
+    /// Create a new FilterSubscriber filtering the data of `subscriber` with `predicate`
+    pub fn new(subscriber: S, predicate: F) -> Self {
+        Self {
+            subscriber,
+            predicate,
+            data: None,
+        }
+    }
+
+    /// Destroy the FilterSubscriber, returning the inner subscriber
+    pub fn into_inner(self) -> S {
+        self.subscriber
+    }
+}
+
+impl<S, Data, F> Subscriber for FilterSubscriber<S, Data, F>
+where
+    S: Subscriber<Target = Option<Data>>,
+    Data: Clone,
+    F: Fn(&Data) -> bool,
+{
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        if let Some(data) = self.subscriber.get() {
+            if (self.predicate)(data) {
+                self.data = Some(data.clone());
+            }
+        }
+
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.subscriber.has_update()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CountingSubscriber {
+        data: u64,
+    }
+
+    impl Subscriber for CountingSubscriber {
+        type Target = u64;
+
+        fn get(&mut self) -> &Self::Target {
+            self.data += 0x0101;
+            &self.data
+        }
+    }
+
+    #[test]
+    fn test_map_subscriber() {
+        let mut subscriber = CountingSubscriber { data: 0xFF }.map(|data| *data as u8);
+        assert_eq!(*subscriber.get(), 0x00);
+        assert_eq!(*subscriber.get(), 0x01);
+        assert!(subscriber.has_update());
+        assert_eq!(subscriber.into_inner().data, 0x0301);
+    }
+
+    struct SequenceSubscriber {
+        sequence: [u8; 4],
+        idx: usize,
+    }
+
+    impl Subscriber for SequenceSubscriber {
+        type Target = u8;
+
+        fn get(&mut self) -> &Self::Target {
+            let data = &self.sequence[self.idx.min(self.sequence.len() - 1)];
+            self.idx += 1;
+            data
+        }
+    }
+
+    #[test]
+    fn test_dedup_subscriber() {
+        let mut subscriber = SequenceSubscriber {
+            sequence: [1, 1, 2, 2],
+            idx: 0,
+        }
+        .dedup();
+
+        let mut changes = [false; 4];
+        for changed in changes.iter_mut() {
+            subscriber.get();
+            *changed = subscriber.changed();
+        }
+        assert_eq!(changes, [true, false, true, false]);
+        assert_eq!(*subscriber.get(), 2);
+    }
+
+    struct OptionSequenceSubscriber {
+        sequence: [Option<u8>; 5],
+        idx: usize,
+        data: Option<u8>,
+    }
+
+    impl Subscriber for OptionSequenceSubscriber {
+        type Target = Option<u8>;
+
+        fn get(&mut self) -> &Self::Target {
+            self.data = self.sequence[self.idx.min(self.sequence.len() - 1)];
+            self.idx += 1;
+            &self.data
+        }
+    }
+
+    #[test]
+    fn test_filter_subscriber() {
+        let mut subscriber = OptionSequenceSubscriber {
+            sequence: [Some(3), Some(12), None, Some(7), Some(20)],
+            idx: 0,
+            data: None,
+        }
+        .filter(|data| *data > 10);
+
+        let mut received = [None; 5];
+        for data in received.iter_mut() {
+            *data = *subscriber.get();
+        }
+        // Data below the threshold (and missing data) keeps the previous passing data
+        assert_eq!(received, [None, Some(12), Some(12), Some(12), Some(20)]);
+    }
 }
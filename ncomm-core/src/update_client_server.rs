@@ -70,6 +70,10 @@ pub trait UpdateServer {
     #[allow(clippy::type_complexity)]
     fn poll_for_requests(&mut self) -> Vec<Result<(Self::Key, Self::Request), Self::Error>>;
 
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    /// Get the keys of every client known to the server
+    fn get_clients(&self) -> Vec<Self::Key>;
+
     /// Send an update to a specific client
     fn send_update(
         &mut self,
@@ -91,6 +95,22 @@ pub trait UpdateServer {
             .collect()
     }
 
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    /// Send the same update for a request to every client known to the server
+    fn broadcast_update(
+        &mut self,
+        request: &Self::Request,
+        update: Self::Update,
+    ) -> Vec<Result<(), Self::Error>>
+    where
+        Self::Update: Clone,
+    {
+        self.get_clients()
+            .into_iter()
+            .map(|client_key| self.send_update(client_key, request, update.clone()))
+            .collect()
+    }
+
     /// Send a response to a specific client
     fn send_response(
         &mut self,
@@ -111,4 +131,20 @@ pub trait UpdateServer {
             .map(|response| self.send_response(response.0, response.1, response.2))
             .collect()
     }
+
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    /// Send the same response to a request to every client known to the server
+    fn broadcast_response(
+        &mut self,
+        request: Self::Request,
+        response: Self::Response,
+    ) -> Vec<Result<(), Self::Error>>
+    where
+        Self::Response: Clone,
+    {
+        self.get_clients()
+            .into_iter()
+            .map(|client_key| self.send_response(client_key, request.clone(), response.clone()))
+            .collect()
+    }
 }
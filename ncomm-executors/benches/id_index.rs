@@ -0,0 +1,80 @@
+//!
+//! Benchmarks adding, looking up, and removing nodes from a SimpleExecutor with and
+//! without its id index.
+//!
+//! Note: Removing a node still shifts the nodes after it in the executor's backing
+//! vector so the index only speeds up finding the node being removed.
+//!
+//! Run with `cargo bench -p ncomm-executors --bench id_index`.
+//!
+
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::unbounded;
+
+use ncomm_core::{Executor, Node};
+use ncomm_executors::SimpleExecutor;
+
+/// The number of nodes added to (and removed from) the executor
+const NODES: u32 = 10_000;
+
+/// A node that does nothing
+struct IdleNode {
+    id: u32,
+}
+
+impl Node<u32> for IdleNode {
+    fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        1_000
+    }
+}
+
+/// Time adding every node to, looking up every node in, and then removing every
+/// node from an executor.
+fn add_lookup_remove(indexed: bool) -> (Duration, Duration, Duration) {
+    let (_, rx) = unbounded();
+    let mut executor = SimpleExecutor::new(rx);
+    executor.set_id_index(indexed);
+
+    let start = Instant::now();
+    for id in 0..NODES {
+        executor.add_node(Box::new(IdleNode { id }));
+    }
+    let added = start.elapsed();
+
+    // The executor resets every node's priority when it starts and shuts down, so
+    // the nodes of a stopped executor all share one priority and the index finds
+    // them by their insertion sequence
+    let start = Instant::now();
+    for id in 0..NODES {
+        assert!(executor.node_health(&id).is_some());
+    }
+    let looked_up = start.elapsed();
+
+    let start = Instant::now();
+    for id in (0..NODES).rev() {
+        assert!(executor.remove_node(&id).is_some());
+    }
+    let removed = start.elapsed();
+    assert!(executor.node_ids().is_empty());
+
+    (added, looked_up, removed)
+}
+
+fn main() {
+    for indexed in [false, true] {
+        let (added, looked_up, removed) = add_lookup_remove(indexed);
+        println!(
+            "{} nodes ({}): add {:?}/node, lookup {:?}/node, remove {:?}/node",
+            NODES,
+            if indexed { "indexed" } else { "unindexed" },
+            added / NODES,
+            looked_up / NODES,
+            removed / NODES,
+        );
+    }
+}
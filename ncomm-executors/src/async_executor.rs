@@ -0,0 +1,494 @@
+//!
+//! The Async Executor
+//!
+//! The async executor drives each node from its own Tokio task that waits on
+//! a `tokio::time::interval` between updates.  Unlike the other executors in
+//! NComm, the async executor does not busy wait so the CPU is yielded to other
+//! processes between node updates.
+//!
+//! Note: Node updates are synchronous so they are run directly on the interval
+//! tick of a multi-threaded Tokio runtime.  Nodes that block for long periods of
+//! time will occupy one of the runtime's worker threads while they update.
+//!
+
+use std::{any::Any, panic, thread, time::Duration};
+
+use crossbeam::channel::Receiver;
+
+use quanta::{Clock, Instant};
+
+use tokio::{
+    runtime::{Builder, Runtime},
+    sync::watch,
+    task::JoinHandle,
+    time,
+};
+
+use ncomm_core::{Executor, ExecutorState, Node};
+
+use crate::{
+    log_interrupt, log_nodes_started, log_update_error, shutdown_nodes, shutdown_reason,
+    NodeGroups, NodeWrapper, StateCallback,
+};
+
+/// The period the main thread sleeps for between checks of the interrupt and
+/// the elapsed time
+const CHECK_PERIOD: Duration = Duration::from_millis(1);
+
+/// Async Executor
+///
+/// The Async Executor spawns a Tokio task for each of its Nodes that updates
+/// the Node every time the Node's update interval elapses.
+///
+/// Note: The Async Executor can be interrupted by sending a true value over
+/// the mpsc channel whose receiving end is owned by the AsyncExecutor
+pub struct AsyncExecutor<ID: PartialEq> {
+    /// The nodes contained by the executor
+    backing: Vec<NodeWrapper<ID>>,
+    /// The Tokio runtime the nodes are executed on
+    runtime: Runtime,
+    /// The quanta high-precision clock backing the AsyncExecutor
+    clock: Clock,
+    /// The current state of the executor
+    state: ExecutorState,
+    /// The Instant the executor was started
+    start_instant: Instant,
+    /// The Interrupt receiver channel
+    interrupt: Receiver<bool>,
+    /// Whether or not the executor has been interrupted
+    interrupted: bool,
+    /// How long a node's shutdown may take before the node is abandoned
+    shutdown_timeout: Option<Duration>,
+    /// The callback invoked whenever the executor changes state
+    on_state_change: Option<StateCallback>,
+    /// The ids of the nodes in each group of nodes
+    groups: NodeGroups<ID>,
+}
+
+impl<ID: PartialEq + Send + 'static> AsyncExecutor<ID> {
+    /// Create a new Async Executor without any Nodes
+    pub fn new(interrupt: Receiver<bool>) -> Self {
+        Self::new_with(interrupt, Vec::new())
+    }
+
+    /// Creates a new Async Executor with a number of Nodes
+    pub fn new_with(interrupt: Receiver<bool>, mut nodes: Vec<Box<dyn Node<ID>>>) -> Self {
+        let mut backing = Vec::new();
+        for node in nodes.drain(..) {
+            backing.push(NodeWrapper::new(0, node));
+        }
+
+        let runtime = Builder::new_multi_thread().enable_time().build().unwrap();
+
+        let clock = Clock::new();
+        let now = clock.now();
+
+        Self {
+            backing,
+            runtime,
+            clock,
+            state: ExecutorState::Stopped,
+            start_instant: now,
+            interrupt,
+            interrupted: false,
+            shutdown_timeout: None,
+            on_state_change: None,
+            groups: NodeGroups::default(),
+        }
+    }
+
+    /// Set how long shutting down the nodes may take when the executor stops.
+    ///
+    /// With a timeout, nodes are shut down on a watchdog thread against a single
+    /// deadline and any node whose shutdown hasn't finished by the deadline is
+    /// abandoned (and removed from the executor) so the executor can still return.
+    /// By default, there is no timeout.
+    pub fn set_shutdown_timeout(&mut self, shutdown_timeout: Option<Duration>) {
+        self.shutdown_timeout = shutdown_timeout;
+    }
+
+    /// Register a callback that is invoked with the new state of the executor
+    /// every time the executor changes state.
+    pub fn on_state_change(&mut self, callback: impl FnMut(ExecutorState) + Send + 'static) {
+        self.on_state_change = Some(Box::new(callback));
+    }
+
+    /// Transition the executor into a given state, notifying the state change
+    /// callback if the state changed.
+    fn set_state(&mut self, state: ExecutorState) {
+        if self.state != state {
+            self.state = state;
+            if let Some(callback) = self.on_state_change.as_mut() {
+                callback(state);
+            }
+        }
+    }
+
+    /// Spawn a task for each of the nodes in the executor that updates the node
+    /// every update delay until the stop signal is received.
+    fn spawn_nodes(&mut self, stop: &watch::Receiver<bool>) -> Vec<JoinHandle<NodeWrapper<ID>>> {
+        let mut handles = Vec::new();
+        for mut node_wrapper in self.backing.drain(..) {
+            let mut stop = stop.clone();
+            let clock = self.clock.clone();
+            let start_instant = self.start_instant;
+            handles.push(self.runtime.spawn(async move {
+                let mut delay = node_wrapper.node.get_update_delay_us();
+                let mut interval = time::interval(Duration::from_micros(delay as u64));
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {
+                            let now = clock.now().duration_since(start_instant).as_micros();
+                            if let Err(error) = node_wrapper.node.try_update_at(now) {
+                                log_update_error(&error);
+                            }
+
+                            // Nodes may change their update rate at runtime
+                            let next_delay = node_wrapper.node.get_update_delay_us();
+                            if next_delay != delay {
+                                delay = next_delay;
+                                let period = Duration::from_micros(delay as u64);
+                                interval = time::interval_at(time::Instant::now() + period, period);
+                            }
+                            node_wrapper.priority += delay;
+                        },
+                        _ = stop.changed() => break,
+                    }
+                }
+                node_wrapper
+            }));
+        }
+        handles
+    }
+
+    /// Stop each of the node tasks, returning the nodes to the backing vector
+    /// and shutting them down.
+    fn stop_nodes(&mut self, stop: watch::Sender<bool>, handles: Vec<JoinHandle<NodeWrapper<ID>>>) {
+        let _ = stop.send(true);
+        for handle in handles {
+            match self.runtime.block_on(handle) {
+                Ok(node_wrapper) => self.backing.push(node_wrapper),
+                Err(err) if err.is_panic() => panic::resume_unwind(err.into_panic()),
+                Err(_) => (),
+            }
+        }
+
+        let reason = shutdown_reason(self.interrupted);
+        shutdown_nodes(&mut self.backing, reason, self.shutdown_timeout);
+        self.set_state(ExecutorState::Stopped);
+    }
+}
+
+impl<ID: PartialEq + Send + 'static> AsyncExecutor<ID> {
+    /// Add a group of nodes to the Async Executor that can be removed together via
+    /// `remove_group`.
+    ///
+    /// Note: Adding nodes to an existing group adds the nodes to the group.
+    pub fn add_node_group(&mut self, group_id: impl Into<String>, nodes: Vec<Box<dyn Node<ID>>>) {
+        let ids: Vec<ID> = nodes.iter().map(|node| node.get_id()).collect();
+        for node in nodes {
+            self.add_node(node);
+        }
+        self.groups.insert(group_id.into(), ids);
+    }
+
+    /// Remove every node in a group from the Async Executor, returning the removed nodes.
+    ///
+    /// Note: Nodes can only be removed from the executor when it is not running.
+    pub fn remove_group(&mut self, group_id: &str) -> Vec<Box<dyn Node<ID>>> {
+        self.groups
+            .remove(group_id)
+            .iter()
+            .filter_map(|id| self.remove_node(id))
+            .collect()
+    }
+
+    /// Get the ids of the nodes in a group
+    pub fn group_node_ids(&self, group_id: &str) -> Option<&[ID]> {
+        self.groups.get(group_id)
+    }
+}
+
+impl<ID: PartialEq + Send + 'static> Executor<ID> for AsyncExecutor<ID> {
+    /// Context doesn't really apply to AsyncExecutors
+    type Context = Box<dyn Any>;
+
+    /// For each node in the async executor we should reset their priority to 0
+    /// and start the node.  We should also set the start_instant to the current time.
+    ///
+    /// Note: this method should not be called individually as it will always be
+    /// called during the `update_for_ms` and `update_loop` methods.
+    fn start(&mut self) {
+        for node_wrapper in self.backing.iter_mut() {
+            node_wrapper.priority = 0;
+            node_wrapper.node.start();
+        }
+        log_nodes_started(self.backing.len());
+
+        self.interrupted = false;
+        self.set_state(ExecutorState::Started);
+        self.start_instant = self.clock.now();
+    }
+
+    /// Start the executor and run the executor for a given number of milliseconds before
+    /// stopping the executor.  An interrupt will also stop the executor early.
+    fn update_for_ms(&mut self, ms: u128) {
+        // Start the Executor
+        self.start();
+
+        // Run the Executor
+        self.set_state(ExecutorState::Running);
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let handles = self.spawn_nodes(&stop_rx);
+        while self
+            .clock
+            .now()
+            .duration_since(self.start_instant)
+            .as_millis()
+            < ms
+            && !self.check_interrupt()
+        {
+            thread::sleep(CHECK_PERIOD);
+        }
+
+        // Stop the Executor
+        self.stop_nodes(stop_tx, handles);
+    }
+
+    /// Start the executor and run until an interrupt is received.
+    fn update_loop(&mut self) {
+        // Start the Executor
+        self.start();
+
+        // Run the Executor
+        self.set_state(ExecutorState::Running);
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let handles = self.spawn_nodes(&stop_rx);
+        while !self.check_interrupt() {
+            thread::sleep(CHECK_PERIOD);
+        }
+
+        // Stop the Executor
+        self.stop_nodes(stop_tx, handles);
+    }
+
+    /// Get the current state of the executor
+    fn state(&self) -> ExecutorState {
+        self.state
+    }
+
+    /// Check the interrupt receiver for an interrupt.  If an interrupt
+    /// signal was sent over the channel then this node should report that
+    /// it was interrupted.
+    fn check_interrupt(&mut self) -> bool {
+        if let Ok(interrupt) = self.interrupt.try_recv() {
+            self.interrupted = interrupt;
+            if interrupt {
+                log_interrupt();
+            }
+        }
+        self.interrupted
+    }
+
+    /// Add a node to the Async Executor.
+    ///
+    /// Note: Nodes can only be added to the executor when it is not running.
+    ///
+    /// Additionally, only 1 node can exist per id so additional nodes added with
+    /// the same id will replace the previous node of a given id.
+    fn add_node(&mut self, node: Box<dyn Node<ID>>) {
+        if let Some(idx) = self
+            .backing
+            .iter()
+            .position(|node_wrapper| node_wrapper.node.get_id().eq(&node.get_id()))
+        {
+            self.backing.remove(idx);
+        }
+
+        if self.state == ExecutorState::Stopped || self.state == ExecutorState::Started {
+            self.backing.push(NodeWrapper::new(0, node));
+        }
+    }
+
+    /// Remove a node from the Async Executor.
+    ///
+    /// Note: Nodes can only be removed from the executor when it is not running.
+    fn remove_node(&mut self, id: &ID) -> Option<Box<dyn Node<ID>>> {
+        if self.state != ExecutorState::Running {
+            let idx = self
+                .backing
+                .iter()
+                .position(|node_wrapper| node_wrapper.node.get_id().eq(id));
+            idx.map(|idx| self.backing.remove(idx).destroy())
+        } else {
+            None
+        }
+    }
+
+    /// Get the ids of the nodes in the Async Executor.
+    fn node_ids(&self) -> Vec<ID> {
+        self.backing
+            .iter()
+            .map(|node_wrapper| node_wrapper.node.get_id())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossbeam::channel::unbounded;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum State {
+        Stopped,
+        Started,
+        Updating,
+    }
+
+    pub struct SimpleNode {
+        id: u8,
+        pub update_delay: u128,
+        pub num: u8,
+        state: State,
+    }
+
+    impl SimpleNode {
+        pub fn new(id: u8, update_delay: u128) -> Self {
+            Self {
+                id,
+                update_delay,
+                num: 0,
+                state: State::Stopped,
+            }
+        }
+    }
+
+    impl Node<u8> for SimpleNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn start(&mut self) {
+            self.state = State::Started;
+        }
+
+        fn update(&mut self) {
+            self.state = State::Updating;
+            self.num = self.num.wrapping_add(1);
+        }
+
+        fn shutdown(&mut self) {
+            self.state = State::Stopped;
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            self.update_delay
+        }
+    }
+
+    #[test]
+    fn test_update_for_ms() {
+        let (_, rx) = unbounded();
+
+        let mut executor = AsyncExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(SimpleNode::new(1, 25_000)),
+            ],
+        );
+
+        let start = executor.clock.now();
+        executor.update_for_ms(100);
+        let end = executor.clock.now();
+
+        assert_eq!(executor.backing.len(), 2);
+        for node_wrapper in executor.backing.iter() {
+            assert_eq!(node_wrapper.priority, 0);
+            let simple_node: &dyn Any = &node_wrapper.node;
+            let simple_node: &Box<SimpleNode> = unsafe { simple_node.downcast_ref_unchecked() };
+            assert_eq!(simple_node.state, State::Stopped);
+            if simple_node.id == 0 {
+                assert!([9, 10, 11].contains(&simple_node.num));
+            } else {
+                assert!([3, 4, 5].contains(&simple_node.num));
+            }
+        }
+        assert_eq!(executor.state, ExecutorState::Stopped);
+
+        assert!(Duration::from_millis(95) < end - start);
+        assert!(end - start < Duration::from_millis(110));
+    }
+
+    #[test]
+    fn test_update_loop() {
+        let (tx, rx) = unbounded();
+
+        let mut executor = AsyncExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(SimpleNode::new(1, 25_000)),
+            ],
+        );
+
+        let handle = thread::spawn(move || {
+            executor.update_loop();
+            executor
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        tx.send(true).unwrap();
+
+        let executor = handle.join().unwrap();
+        assert_eq!(executor.backing.len(), 2);
+        for node_wrapper in executor.backing.iter() {
+            let simple_node: &dyn Any = &node_wrapper.node;
+            let simple_node: &Box<SimpleNode> = unsafe { simple_node.downcast_ref_unchecked() };
+            assert_eq!(simple_node.state, State::Stopped);
+            assert!([3, 4, 5, 9, 10, 11].contains(&simple_node.num));
+        }
+        assert!(executor.interrupted);
+        assert_eq!(executor.state, ExecutorState::Stopped);
+    }
+
+    #[test]
+    fn test_add_and_remove_node() {
+        let (_, rx) = unbounded();
+
+        let mut executor = AsyncExecutor::new(rx);
+        executor.add_node(Box::new(SimpleNode::new(0, 10_000)));
+        executor.add_node(Box::new(SimpleNode::new(1, 10_000)));
+        executor.add_node(Box::new(SimpleNode::new(0, 1_000)));
+        assert_eq!(executor.backing.len(), 2);
+
+        let node = executor.remove_node(&0).unwrap();
+        assert_eq!(node.get_update_delay_us(), 1_000);
+        assert_eq!(executor.backing.len(), 1);
+    }
+
+    #[test]
+    fn test_node_ids() {
+        let (_, rx) = unbounded();
+
+        let mut executor = AsyncExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(SimpleNode::new(1, 25_000)),
+            ],
+        );
+        executor.add_node(Box::new(SimpleNode::new(2, 1_000)));
+
+        let mut ids = executor.node_ids();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 2]);
+        assert!(executor.contains_node(&2));
+        assert!(!executor.contains_node(&3));
+
+        executor.remove_node(&1);
+        assert!(!executor.contains_node(&1));
+    }
+}
@@ -0,0 +1,206 @@
+//!
+//! A Benchmark Harness for Executors
+//!
+//! When the `bench` feature is enabled, executors can be compared empirically by
+//! running a set of synthetic nodes (each with a configurable update cost) on an
+//! executor for a fixed duration and recording the throughput of the executor and
+//! how often each node missed its update period.
+//!
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use ncomm_core::{executor::DynExecutor, Node};
+
+use crate::clock::{Clock, SystemClock};
+
+/// The configuration of a synthetic node run by the benchmark harness
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyntheticNodeConfig {
+    /// The delay (in microseconds) between updates of the node
+    pub update_delay_us: u128,
+    /// How long each update of the node busy-waits for (simulating CPU work)
+    pub cost: Duration,
+}
+
+impl SyntheticNodeConfig {
+    /// Create the configuration of a synthetic node
+    pub fn new(update_delay_us: u128, cost: Duration) -> Self {
+        Self {
+            update_delay_us,
+            cost,
+        }
+    }
+}
+
+/// The statistics of a single synthetic node recorded during a benchmark
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NodeBenchmark {
+    /// The number of times the node was updated
+    pub updates: u64,
+    /// The number of updates that started a full update period (or more) after
+    /// they were due
+    pub overruns: u64,
+    /// The longest an update started after it was due
+    pub max_lateness: Duration,
+}
+
+/// The results of running a benchmark on an executor
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkReport {
+    /// How long the executor actually ran for (according to the executor's default
+    /// `SystemClock`)
+    pub elapsed: Duration,
+    /// The statistics of each synthetic node (in the order the nodes were configured)
+    pub nodes: Vec<NodeBenchmark>,
+}
+
+impl BenchmarkReport {
+    /// Get the total number of node updates performed by the executor
+    pub fn total_updates(&self) -> u64 {
+        self.nodes.iter().map(|node| node.updates).sum()
+    }
+
+    /// Get the total number of overruns across every node
+    pub fn total_overruns(&self) -> u64 {
+        self.nodes.iter().map(|node| node.overruns).sum()
+    }
+
+    /// Get the number of node updates the executor performed per second
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            0.0
+        } else {
+            self.total_updates() as f64 / self.elapsed.as_secs_f64()
+        }
+    }
+}
+
+/// A node that busy-waits for a configured duration every update, recording
+/// its statistics for the benchmark harness
+struct SyntheticNode {
+    /// The identifier for the node (its index in the benchmark's configuration)
+    id: usize,
+    /// The configuration of the node
+    config: SyntheticNodeConfig,
+    /// The instant the previous update of the node started
+    previous_update: Option<Instant>,
+    /// The statistics shared with the benchmark harness
+    stats: Arc<Mutex<NodeBenchmark>>,
+}
+
+impl Node<usize> for SyntheticNode {
+    fn get_id(&self) -> usize {
+        self.id
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.config.update_delay_us
+    }
+
+    fn start(&mut self) {
+        self.previous_update = None;
+    }
+
+    fn update(&mut self) {
+        let now = Instant::now();
+        let mut stats = self.stats.lock().unwrap();
+        stats.updates += 1;
+        if let Some(previous_update) = self.previous_update {
+            let delay = Duration::from_micros(self.config.update_delay_us as u64);
+            let lateness = now.saturating_duration_since(previous_update + delay);
+            if !delay.is_zero() && lateness >= delay {
+                stats.overruns += 1;
+            }
+            stats.max_lateness = stats.max_lateness.max(lateness);
+        }
+        drop(stats);
+        self.previous_update = Some(now);
+
+        while now.elapsed() < self.config.cost {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Run a set of synthetic nodes on an executor for a given duration, returning
+/// the throughput of the executor and the statistics of each node.
+///
+/// The synthetic nodes are given the ids `0..nodes.len()` and are removed from the
+/// executor once the benchmark has finished.
+///
+/// Note: The executor should not contain any other nodes (with overlapping ids) as
+/// the other nodes would also be removed and their updates would skew the results.
+pub fn benchmark(
+    executor: &mut dyn DynExecutor<usize>,
+    nodes: &[SyntheticNodeConfig],
+    duration: Duration,
+) -> BenchmarkReport {
+    let stats: Vec<Arc<Mutex<NodeBenchmark>>> = nodes
+        .iter()
+        .map(|_| Arc::new(Mutex::new(NodeBenchmark::default())))
+        .collect();
+    for (id, (config, stats)) in nodes.iter().zip(stats.iter()).enumerate() {
+        executor.add_node(Box::new(SyntheticNode {
+            id,
+            config: *config,
+            previous_update: None,
+            stats: stats.clone(),
+        }));
+    }
+
+    // The run is timed with the same (quanta) clock the executors measure time with
+    // so the elapsed time agrees with the executor's own notion of the duration
+    let clock = SystemClock::new();
+    executor.update_for_ms(duration.as_millis());
+    let elapsed = clock.now();
+
+    for id in 0..nodes.len() {
+        executor.remove_node(&id);
+    }
+
+    BenchmarkReport {
+        elapsed,
+        nodes: stats.iter().map(|stats| *stats.lock().unwrap()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossbeam::channel::unbounded;
+
+    use crate::SimpleExecutor;
+
+    #[test]
+    fn test_benchmark_simple_executor() {
+        let (_, rx) = unbounded();
+        let mut executor = SimpleExecutor::new(rx);
+
+        let report = benchmark(
+            &mut executor,
+            &[
+                SyntheticNodeConfig::new(10_000, Duration::ZERO),
+                SyntheticNodeConfig::new(20_000, Duration::from_micros(100)),
+            ],
+            Duration::from_millis(100),
+        );
+
+        assert!(report.elapsed >= Duration::from_millis(100));
+        assert_eq!(report.nodes.len(), 2);
+        // Each node updates once at the start and then once every update period
+        assert!(report.nodes[0].updates >= 9);
+        assert!(report.nodes[1].updates >= 4);
+        assert!(report.nodes[0].updates > report.nodes[1].updates);
+        assert_eq!(
+            report.total_updates(),
+            report.nodes[0].updates + report.nodes[1].updates
+        );
+        assert!(report.throughput() > 0.0);
+        // The synthetic nodes are removed once the benchmark finishes
+        assert!(executor.node_ids().is_empty());
+    }
+}
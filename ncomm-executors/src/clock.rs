@@ -0,0 +1,121 @@
+//!
+//! Clocks for Executors
+//!
+//! Executors measure time through the Clock trait so that the wall clock can
+//! be swapped out for a simulated clock.  This is mostly useful for testing
+//! time-dependent nodes, where the ManualClock lets an executor run through
+//! seconds of simulated time without actually waiting.
+//!
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A source of time for an executor.
+pub trait Clock: Send {
+    /// Get the current time as the duration since some fixed (but arbitrary)
+    /// epoch of the clock.
+    fn now(&self) -> Duration;
+
+    /// Skip ahead to the given time instead of waiting for it to pass, returning
+    /// whether the clock skipped ahead.
+    ///
+    /// Real clocks can't skip ahead so, by default, this returns false and the
+    /// executor waits for the time to pass.
+    fn skip_to(&self, _time: Duration) -> bool {
+        false
+    }
+}
+
+/// The default executor clock backed by the quanta high-precision clock.
+#[derive(Clone)]
+pub struct SystemClock {
+    /// The quanta high-precision clock
+    clock: quanta::Clock,
+    /// The instant the clock was created
+    epoch: quanta::Instant,
+}
+
+impl SystemClock {
+    /// Create a new system clock whose epoch is the current instant
+    pub fn new() -> Self {
+        let clock = quanta::Clock::new();
+        let epoch = clock.now();
+        Self { clock, epoch }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.clock.now().duration_since(self.epoch)
+    }
+}
+
+/// A simulated clock that only advances when `advance` is called or when an
+/// executor skips ahead to the next node update.
+///
+/// Clones of a ManualClock share the same time, so a test can keep a clone of the
+/// clock given to an executor to inspect (or advance) the simulated time.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    /// The current simulated time (in nanoseconds)
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Create a new manual clock starting at time zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance the simulated time by a given duration
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+
+    /// Advance the simulated time to the given time (if it is in the future).
+    fn skip_to(&self, time: Duration) -> bool {
+        self.nanos
+            .fetch_max(time.as_nanos() as u64, Ordering::SeqCst);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new();
+        let handle = clock.clone();
+        assert_eq!(clock.now(), Duration::ZERO);
+
+        handle.advance(Duration::from_millis(5));
+        assert_eq!(clock.now(), Duration::from_millis(5));
+
+        assert!(clock.skip_to(Duration::from_millis(12)));
+        assert_eq!(handle.now(), Duration::from_millis(12));
+
+        // Skipping never moves the clock backwards
+        clock.skip_to(Duration::from_millis(3));
+        assert_eq!(clock.now(), Duration::from_millis(12));
+    }
+}
@@ -0,0 +1,332 @@
+//!
+//! The Cooperative Executor is a `no_std` executor for embedded targets.
+//!
+//! The Cooperative Executor doesn't spawn threads or read a clock.  Instead, the
+//! current time is given to the executor (as a monotonic tick count in microseconds)
+//! every time it is updated, so the executor can be driven from a hardware timer,
+//! an RTOS task, or the main loop of a bare-metal program.
+//!
+//! Because the Cooperative Executor has no time source or interrupt channel of its
+//! own, it doesn't implement the Executor trait.
+//!
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "std")]
+use std::{boxed::Box, vec::Vec};
+
+use ncomm_core::{ExecutorState, Node, ShutdownReason};
+
+use crate::{insert_into, missed_deadlines, NodeWrapper};
+
+/// Cooperative Executor
+///
+/// The Cooperative Executor stores Nodes in a sorted vector and updates every
+/// Node that is due whenever `update` is called with the current tick count.
+///
+/// Note: Ticks should be in microseconds (the unit of node update delays) and
+/// must never decrease.
+pub struct CooperativeExecutor<ID: PartialEq> {
+    /// The sorted backing vector for the executor
+    backing: Vec<NodeWrapper<ID>>,
+    /// The current state of the executor
+    state: ExecutorState,
+    /// The tick the executor was started at
+    start_tick: u64,
+    /// The most recent tick the executor was given
+    now: u64,
+}
+
+impl<ID: PartialEq> CooperativeExecutor<ID> {
+    /// Create a new Cooperative Executor without any Nodes
+    pub fn new() -> Self {
+        Self::new_with(Vec::new())
+    }
+
+    /// Creates a new Cooperative Executor with a number of Nodes
+    pub fn new_with(mut nodes: Vec<Box<dyn Node<ID>>>) -> Self {
+        let mut backing = Vec::new();
+        for node in nodes.drain(..) {
+            backing.push(NodeWrapper::new(0, node));
+        }
+
+        Self {
+            backing,
+            state: ExecutorState::Stopped,
+            start_tick: 0,
+            now: 0,
+        }
+    }
+
+    /// Get the current state of the executor
+    pub fn state(&self) -> ExecutorState {
+        self.state
+    }
+
+    /// Start each of the nodes in the executor at a given tick.
+    ///
+    /// Note: `update` starts the executor if it hasn't been started so this
+    /// only needs to be called to start the nodes before the first update.
+    pub fn start(&mut self, now: u64) {
+        for node_wrapper in self.backing.iter_mut() {
+            node_wrapper.priority = 0;
+            node_wrapper.node.start();
+        }
+
+        self.start_tick = now;
+        self.now = now;
+        self.state = ExecutorState::Started;
+    }
+
+    /// Update every node that is due at a given tick, returning the number of
+    /// nodes that were updated.
+    ///
+    /// Each node is updated at most once per call, so a node that has fallen
+    /// behind catches up over subsequent calls.
+    pub fn update(&mut self, now: u64) -> usize {
+        if self.state == ExecutorState::Stopped {
+            self.start(now);
+        }
+        self.state = ExecutorState::Running;
+        self.now = now;
+
+        let elapsed = now.saturating_sub(self.start_tick) as u128;
+        let mut due = Vec::new();
+        while self
+            .backing
+            .last()
+            .is_some_and(|node_wrapper| node_wrapper.priority <= elapsed)
+        {
+            due.push(self.backing.pop().unwrap());
+        }
+
+        let updated = due.len();
+        for mut node_wrapper in due {
+            let missed = missed_deadlines(
+                elapsed,
+                node_wrapper.priority,
+                node_wrapper.node.get_update_delay_us(),
+            );
+            if missed > 0 {
+                node_wrapper.node.on_deadline_missed(missed);
+            }
+            // There is nowhere to log errors without std so they are left for
+            // the node to handle
+            let _ = node_wrapper.node.try_update_at(elapsed);
+            node_wrapper.priority += node_wrapper.node.get_update_delay_us();
+            insert_into(&mut self.backing, node_wrapper);
+        }
+        updated
+    }
+
+    /// Get the tick the next node is due to be updated at (if there are any nodes).
+    ///
+    /// This can be used to sleep (or wait for an interrupt) until the next update.
+    pub fn next_update(&self) -> Option<u64> {
+        self.backing.last().map(|node_wrapper| {
+            self.start_tick
+                .saturating_add(u64::try_from(node_wrapper.priority).unwrap_or(u64::MAX))
+        })
+    }
+
+    /// Shut down each of the nodes in the executor
+    pub fn shutdown(&mut self) {
+        for node_wrapper in self.backing.iter_mut() {
+            node_wrapper.priority = 0;
+            node_wrapper
+                .node
+                .shutdown_with_reason(ShutdownReason::Completed);
+        }
+        self.state = ExecutorState::Stopped;
+    }
+
+    /// Add a node to the executor.
+    ///
+    /// Nodes added to a running executor are first updated on the next call
+    /// to `update`.
+    pub fn add_node(&mut self, node: Box<dyn Node<ID>>) {
+        if let Some(idx) = self
+            .backing
+            .iter()
+            .position(|node_wrapper| node_wrapper.node.get_id().eq(&node.get_id()))
+        {
+            self.backing.remove(idx);
+        }
+
+        if self.state == ExecutorState::Stopped {
+            self.backing.push(NodeWrapper::new(0, node));
+        } else {
+            let elapsed = self.now.saturating_sub(self.start_tick) as u128;
+            insert_into(&mut self.backing, NodeWrapper::new(elapsed, node));
+        }
+    }
+
+    /// Remove a node from the executor
+    pub fn remove_node(&mut self, id: &ID) -> Option<Box<dyn Node<ID>>> {
+        let idx = self
+            .backing
+            .iter()
+            .position(|node_wrapper| node_wrapper.node.get_id().eq(id))?;
+        Some(self.backing.remove(idx).destroy())
+    }
+
+    /// Get the ids of the nodes in the executor
+    pub fn node_ids(&self) -> Vec<ID> {
+        self.backing
+            .iter()
+            .map(|node_wrapper| node_wrapper.node.get_id())
+            .collect()
+    }
+}
+
+impl<ID: PartialEq> Default for CooperativeExecutor<ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    use core::{
+        any::Any,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    /// A mock monotonic tick source (in microseconds)
+    struct MockTicks {
+        now: u64,
+    }
+
+    impl MockTicks {
+        fn advance(&mut self, us: u64) -> u64 {
+            self.now += us;
+            self.now
+        }
+    }
+
+    struct CountingNode {
+        id: u8,
+        update_delay: u128,
+        updates: u32,
+        missed: u32,
+    }
+
+    impl CountingNode {
+        fn new(id: u8, update_delay: u128) -> Self {
+            Self {
+                id,
+                update_delay,
+                updates: 0,
+                missed: 0,
+            }
+        }
+    }
+
+    impl Node<u8> for CountingNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            self.update_delay
+        }
+
+        fn update(&mut self) {
+            self.updates += 1;
+        }
+
+        fn on_deadline_missed(&mut self, missed: u32) {
+            self.missed += missed;
+        }
+    }
+
+    static SHUTDOWNS: AtomicU32 = AtomicU32::new(0);
+
+    struct ShutdownNode;
+
+    impl Node<u8> for ShutdownNode {
+        fn get_id(&self) -> u8 {
+            9
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            1_000
+        }
+
+        fn shutdown(&mut self) {
+            SHUTDOWNS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn counting_node(executor: &CooperativeExecutor<u8>, id: u8) -> &CountingNode {
+        let node_wrapper = executor
+            .backing
+            .iter()
+            .find(|node_wrapper| node_wrapper.node.get_id() == id)
+            .unwrap();
+        let node: &dyn Any = &node_wrapper.node;
+        let node: &Box<CountingNode> = unsafe { node.downcast_ref_unchecked() };
+        node
+    }
+
+    #[test]
+    fn test_update() {
+        let mut ticks = MockTicks { now: 5_000 };
+        let mut executor = CooperativeExecutor::new_with(vec![
+            Box::new(CountingNode::new(0, 1_000)),
+            Box::new(CountingNode::new(1, 4_000)),
+        ]);
+        assert_eq!(executor.state(), ExecutorState::Stopped);
+
+        // Both nodes are due on the first update
+        assert_eq!(executor.update(ticks.now), 2);
+        assert_eq!(executor.state(), ExecutorState::Running);
+        assert_eq!(executor.next_update(), Some(6_000));
+
+        for _ in 0..8 {
+            executor.update(ticks.advance(500));
+        }
+        assert_eq!(counting_node(&executor, 0).updates, 5);
+        assert_eq!(counting_node(&executor, 1).updates, 2);
+
+        executor.shutdown();
+        assert_eq!(executor.state(), ExecutorState::Stopped);
+    }
+
+    #[test]
+    fn test_update_catches_up_after_missed_deadlines() {
+        let mut ticks = MockTicks { now: 0 };
+        let mut executor =
+            CooperativeExecutor::new_with(vec![Box::new(CountingNode::new(0, 1_000))]);
+
+        executor.update(ticks.now);
+        // The node is only updated once per update even after falling behind
+        assert_eq!(executor.update(ticks.advance(3_500)), 1);
+        assert_eq!(counting_node(&executor, 0).missed, 2);
+        assert_eq!(executor.update(ticks.advance(1)), 1);
+        assert_eq!(executor.update(ticks.advance(1)), 1);
+        assert_eq!(executor.update(ticks.advance(1)), 0);
+        assert_eq!(counting_node(&executor, 0).updates, 4);
+    }
+
+    #[test]
+    fn test_add_remove_node() {
+        let mut ticks = MockTicks { now: 0 };
+        let mut executor = CooperativeExecutor::new();
+        executor.add_node(Box::new(CountingNode::new(0, 1_000)));
+        executor.update(ticks.now);
+
+        executor.add_node(Box::new(ShutdownNode));
+        assert_eq!(executor.node_ids().len(), 2);
+        assert_eq!(executor.update(ticks.advance(10)), 1);
+
+        executor.shutdown();
+        assert_eq!(SHUTDOWNS.load(Ordering::SeqCst), 1);
+        assert!(executor.remove_node(&9).is_some());
+        assert_eq!(executor.node_ids(), vec![0]);
+    }
+}
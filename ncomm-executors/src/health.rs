@@ -0,0 +1,75 @@
+//!
+//! Health of Nodes
+//!
+//! The executors keep a short history of the errors and missed deadlines of each
+//! node so a supervisor can make health-based decisions (i.e. demoting a node that
+//! keeps failing) without the executor removing the node.
+//!
+
+/// The error and overrun history of a single Node
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NodeHealth {
+    /// The number of updates in a row that failed (reset by a successful update)
+    pub consecutive_errors: u32,
+    /// The total number of updates that failed
+    pub total_errors: u64,
+    /// The time (in microseconds since the executor was started) of the most
+    /// recent failed update
+    pub last_error_time: Option<u128>,
+    /// The total number of update periods the node has missed
+    pub overruns: u64,
+}
+
+impl NodeHealth {
+    /// Check whether the most recent update of the node succeeded
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_errors == 0
+    }
+
+    /// Record an update that succeeded
+    pub(crate) fn record_success(&mut self) {
+        self.consecutive_errors = 0;
+    }
+
+    /// Record an update that failed at `now` (in microseconds since the executor
+    /// was started)
+    pub(crate) fn record_error(&mut self, now: u128) {
+        self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+        self.total_errors += 1;
+        self.last_error_time = Some(now);
+    }
+
+    /// Record that the node missed `missed` update periods
+    pub(crate) fn record_overruns(&mut self, missed: u32) {
+        self.overruns += missed as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut health = NodeHealth::default();
+        assert!(health.is_healthy());
+
+        health.record_error(100);
+        health.record_error(200);
+        health.record_overruns(2);
+        assert_eq!(health.consecutive_errors, 2);
+        assert!(!health.is_healthy());
+
+        health.record_success();
+        health.record_error(400);
+        assert_eq!(
+            health,
+            NodeHealth {
+                consecutive_errors: 1,
+                total_errors: 3,
+                last_error_time: Some(400),
+                overruns: 2,
+            }
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//!
+//! Indexing Nodes by Id
+//!
+//! By default, the executors find a node with a given id by scanning their sorted
+//! backing vector which is perfectly fine for a handful of nodes but becomes slow
+//! when nodes are added to or removed from an executor with thousands of nodes.
+//!
+//! When the ids of the nodes can be hashed, an executor can keep an index of the
+//! scheduled update time (i.e. priority) and insertion sequence of each node so a
+//! node can be found by binary searching the sorted backing vector.
+//!
+//! Note: For the binary search to work, the backing vector must be sorted from the
+//! latest to the earliest priority with ties sorted from the earliest to the latest
+//! sequence (i.e. the order `insert_in_sequence` inserts nodes in).
+//!
+//! Addendum: Executors don't update the index every time a node is rescheduled so
+//! the priority and sequence of a node in the index may be stale (i.e. after an
+//! executor stopped unexpectedly).  Stale entries fall back to scanning the backing
+//! vector.
+//!
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::NodeWrapper;
+
+/// An index of the priority and sequence of each node in an executor keyed by the
+/// id of the node.
+///
+/// Note: The index is a trait object so executors only need the ids of their nodes to
+/// be hashable when the index is enabled.
+pub(crate) trait IdIndex<ID>: Send {
+    /// Record the priority and sequence of the node with a given id
+    fn insert(&mut self, id: ID, priority: u128, sequence: u64);
+
+    /// Remove the node with a given id from the index
+    fn remove(&mut self, id: &ID);
+
+    /// Get the priority and sequence of the node with a given id
+    fn get(&self, id: &ID) -> Option<(u128, u64)>;
+
+    /// Remove every node from the index
+    fn clear(&mut self);
+}
+
+/// An IdIndex backed by a HashMap
+pub(crate) struct HashIdIndex<ID>(HashMap<ID, (u128, u64)>);
+
+impl<ID> HashIdIndex<ID> {
+    /// Create an empty HashIdIndex
+    pub(crate) fn new() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<ID: Eq + Hash + Send> IdIndex<ID> for HashIdIndex<ID> {
+    fn insert(&mut self, id: ID, priority: u128, sequence: u64) {
+        self.0.insert(id, (priority, sequence));
+    }
+
+    fn remove(&mut self, id: &ID) {
+        self.0.remove(id);
+    }
+
+    fn get(&self, id: &ID) -> Option<(u128, u64)> {
+        self.0.get(id).copied()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Check whether `node_wrapper` is sorted before a node with a given priority and
+/// sequence in the backing vector
+#[inline(always)]
+fn sorts_before<ID: PartialEq>(
+    node_wrapper: &NodeWrapper<ID>,
+    priority: u128,
+    sequence: u64,
+) -> bool {
+    node_wrapper.priority > priority
+        || (node_wrapper.priority == priority && node_wrapper.sequence < sequence)
+}
+
+/// Insert a node into the sorted `backing` vector, placing it after every node with
+/// the same priority and an earlier sequence.
+pub(crate) fn insert_in_sequence<ID: PartialEq>(
+    backing: &mut Vec<NodeWrapper<ID>>,
+    node_wrapper: NodeWrapper<ID>,
+) {
+    let idx = backing
+        .partition_point(|other| sorts_before(other, node_wrapper.priority, node_wrapper.sequence));
+    backing.insert(idx, node_wrapper);
+}
+
+/// Find the position of the node with a given id in the sorted `backing` vector.
+///
+/// With an index, the node is found by binary searching for its priority and
+/// sequence.  Otherwise (or if the node's entry in the index is stale), the entire
+/// backing vector is scanned.
+pub(crate) fn position_of<ID: PartialEq>(
+    backing: &[NodeWrapper<ID>],
+    index: Option<&dyn IdIndex<ID>>,
+    id: &ID,
+) -> Option<usize> {
+    if let Some(index) = index {
+        let (priority, sequence) = index.get(id)?;
+        let idx =
+            backing.partition_point(|node_wrapper| sorts_before(node_wrapper, priority, sequence));
+        if backing
+            .get(idx)
+            .is_some_and(|node_wrapper| node_wrapper.node.get_id().eq(id))
+        {
+            return Some(idx);
+        }
+    }
+
+    backing
+        .iter()
+        .position(|node_wrapper| node_wrapper.node.get_id().eq(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ncomm_core::Node;
+
+    struct IdNode(u32);
+
+    impl Node<u32> for IdNode {
+        fn get_id(&self) -> u32 {
+            self.0
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10
+        }
+    }
+
+    #[test]
+    fn test_position_of() {
+        let mut backing = Vec::new();
+        let mut index = HashIdIndex::new();
+        for id in 0..1_000u32 {
+            let priority = u128::from(id % 10);
+            let mut node_wrapper = NodeWrapper::new(priority, Box::new(IdNode(id)));
+            node_wrapper.sequence = u64::from(id);
+            insert_in_sequence(&mut backing, node_wrapper);
+            index.insert(id, priority, u64::from(id));
+        }
+        assert!(backing.windows(2).all(|pair| sorts_before(
+            &pair[0],
+            pair[1].priority,
+            pair[1].sequence
+        )));
+
+        for id in 0..1_000u32 {
+            let idx = position_of(&backing, Some(&index), &id).unwrap();
+            assert_eq!(backing[idx].node.get_id(), id);
+            assert_eq!(position_of(&backing, None, &id), Some(idx));
+        }
+        assert_eq!(position_of(&backing, Some(&index), &1_000), None);
+
+        index.remove(&3);
+        assert_eq!(position_of(&backing, Some(&index), &3), None);
+
+        // A stale entry falls back to scanning the backing vector
+        index.insert(4, 9, 0);
+        assert_eq!(
+            position_of(&backing, Some(&index), &4),
+            position_of(&backing, None, &4)
+        );
+    }
+}
@@ -19,10 +19,20 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(any(feature = "alloc", feature = "std"))]
+pub mod cooperative_executor;
+#[cfg(any(feature = "alloc", feature = "std"))]
+pub use cooperative_executor::CooperativeExecutor;
+
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub use clock::{Clock, ManualClock, SystemClock};
+
 #[cfg(feature = "std")]
 pub mod simple_executor;
 #[cfg(feature = "std")]
-pub use simple_executor::SimpleExecutor;
+pub use simple_executor::{LoopStats, SimpleExecutor};
 
 #[cfg(feature = "std")]
 pub mod threadpool_executor;
@@ -34,6 +44,28 @@ pub mod threaded_executor;
 #[cfg(feature = "std")]
 pub use threaded_executor::ThreadedExecutor;
 
+#[cfg(feature = "tokio")]
+pub mod async_executor;
+#[cfg(feature = "tokio")]
+pub use async_executor::AsyncExecutor;
+
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "bench")]
+pub use bench::{benchmark, BenchmarkReport, NodeBenchmark, SyntheticNodeConfig};
+
+#[cfg(feature = "std")]
+pub mod health;
+#[cfg(feature = "std")]
+mod index;
+#[cfg(feature = "std")]
+pub use health::NodeHealth;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::NodeMetrics;
+
 use core::cmp::{Ord, Ordering};
 use ncomm_core::node::Node;
 
@@ -42,20 +74,303 @@ use alloc::{boxed::Box, vec::Vec};
 #[cfg(feature = "std")]
 use std::{boxed::Box, vec::Vec};
 
+/// The PanicPolicy determines how an executor reacts to a node panicking
+/// during its update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PanicPolicy {
+    /// The panic is propagated, unwinding through (and stopping) the executor.
+    #[default]
+    Propagate,
+    /// The panicking node is shut down (with `ShutdownReason::Panic`) and removed
+    /// from the executor and the remaining nodes continue to be updated.
+    ///
+    /// Note: As the node has already panicked, its shutdown may panic as well so
+    /// a panic during the shutdown is logged rather than propagated.
+    RemoveNode,
+    /// The panicking node is restarted (via its reset method) and continues to
+    /// be updated.
+    Restart,
+}
+
+/// The ErrorPolicy determines how an executor reacts to a node returning an
+/// error from its update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// The error is logged and the node continues to be updated.
+    #[default]
+    Log,
+    /// The error is logged and the node is removed from the executor.
+    RemoveNode,
+    /// The error is logged and the node is restarted (via its reset method).
+    Restart,
+}
+
+/// The SchedulingMode determines when an executor schedules a node's next update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SchedulingMode {
+    /// The next update is scheduled one update period after the time the previous
+    /// update was scheduled for, so a node that falls behind is updated back to back
+    /// until it catches up (keeping its long-term update rate).
+    #[default]
+    FixedRate,
+    /// The next update is scheduled one update period after the previous update
+    /// finished, so updates are always at least one update period apart.
+    FixedDelay,
+}
+
+/// The error returned when the nodes of an executor can't be changed because
+/// of the state the executor is in.
+#[cfg(any(feature = "alloc", feature = "std"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidStateError(pub ncomm_core::ExecutorState);
+
+#[cfg(any(feature = "alloc", feature = "std"))]
+impl core::fmt::Display for InvalidStateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "nodes can't be changed while the executor is {:?}",
+            self.0
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidStateError {}
+
+/// A callback invoked with the new state of an executor whenever the executor
+/// changes state.
+#[cfg(feature = "std")]
+pub(crate) type StateCallback = Box<dyn FnMut(ncomm_core::ExecutorState) + Send>;
+
+/// The ids of the nodes in each group of nodes added to an executor (keyed by
+/// the id of the group)
+#[cfg(feature = "std")]
+pub(crate) struct NodeGroups<ID> {
+    /// The ids of the nodes in each group
+    groups: std::collections::HashMap<String, Vec<ID>>,
+}
+
+#[cfg(feature = "std")]
+impl<ID> Default for NodeGroups<ID> {
+    fn default() -> Self {
+        Self {
+            groups: std::collections::HashMap::new(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<ID> NodeGroups<ID> {
+    /// Add the ids of a number of nodes to a group (creating the group if it
+    /// doesn't exist)
+    pub(crate) fn insert(&mut self, group_id: String, ids: impl IntoIterator<Item = ID>) {
+        self.groups.entry(group_id).or_default().extend(ids);
+    }
+
+    /// Remove a group, returning the ids of the nodes in the group
+    pub(crate) fn remove(&mut self, group_id: &str) -> Vec<ID> {
+        self.groups.remove(group_id).unwrap_or_default()
+    }
+
+    /// Get the ids of the nodes in a group
+    pub(crate) fn get(&self, group_id: &str) -> Option<&[ID]> {
+        self.groups.get(group_id).map(Vec::as_slice)
+    }
+}
+
+/// Build a Graphviz (DOT) description of the nodes and topics in an executor.
+///
+/// Nodes are drawn as boxes labelled with their name and id and topics are drawn
+/// as ellipses, with edges from a node to the topics it publishes and from a topic
+/// to the nodes that subscribe to it.
+#[cfg(feature = "std")]
+pub(crate) fn export_graph<'a, ID: PartialEq + core::fmt::Debug + 'a>(
+    nodes: impl Iterator<Item = &'a dyn Node<ID>>,
+) -> std::string::String {
+    use std::{collections::BTreeSet, fmt::Write};
+
+    let escape = |label: &str| label.replace('\\', "\\\\").replace('"', "\\\"");
+
+    let mut graph = std::string::String::from("digraph ncomm {\n");
+    let mut topics = BTreeSet::new();
+    let mut edges = std::string::String::new();
+    for (index, node) in nodes.enumerate() {
+        let _ = writeln!(
+            graph,
+            "    node_{index} [shape=box, label=\"{} ({:?})\"];",
+            escape(node.name()),
+            node.get_id()
+        );
+        for topic in node.published_topics() {
+            topics.insert(*topic);
+            let _ = writeln!(edges, "    node_{index} -> \"topic:{}\";", escape(topic));
+        }
+        for topic in node.subscribed_topics() {
+            topics.insert(*topic);
+            let _ = writeln!(edges, "    \"topic:{}\" -> node_{index};", escape(topic));
+        }
+    }
+    for topic in topics {
+        let topic = escape(topic);
+        let _ = writeln!(
+            graph,
+            "    \"topic:{topic}\" [shape=ellipse, label=\"{topic}\"];"
+        );
+    }
+    graph.push_str(&edges);
+    graph.push_str("}\n");
+    graph
+}
+
+/// Get the reason an executor is shutting its nodes down given whether or not
+/// the executor was interrupted.
+#[cfg(feature = "std")]
+pub(crate) fn shutdown_reason(interrupted: bool) -> ncomm_core::ShutdownReason {
+    if interrupted {
+        ncomm_core::ShutdownReason::Interrupted
+    } else {
+        ncomm_core::ShutdownReason::Completed
+    }
+}
+
+/// Log an error returned from a node's update.
+///
+/// Note: Like every other log helper, nothing is logged without the `log` feature
+/// so the executors never write to the application's stderr.
+#[cfg(feature = "std")]
+pub(crate) fn log_update_error(error: &ncomm_core::NodeError) {
+    #[cfg(feature = "log")]
+    log::error!("node update failed: {error}");
+    #[cfg(not(feature = "log"))]
+    let _ = error;
+}
+
+/// Log that a node couldn't be added to or removed from an executor.
+#[cfg(feature = "std")]
+pub(crate) fn log_node_rejected(error: &InvalidStateError) {
+    #[cfg(feature = "log")]
+    log::warn!("{error}");
+    #[cfg(not(feature = "log"))]
+    let _ = error;
+}
+
+/// Log that an executor's thread couldn't be given a real-time priority.
+#[cfg(all(feature = "realtime", target_os = "linux"))]
+pub(crate) fn log_realtime_priority_failed(error: &std::io::Error) {
+    #[cfg(feature = "log")]
+    log::warn!("failed to set real-time priority, running with the default policy: {error}");
+    #[cfg(not(feature = "log"))]
+    let _ = error;
+}
+
+/// Log that an executor's thread couldn't be pinned to a core.
+#[cfg(feature = "affinity")]
+pub(crate) fn log_thread_affinity_failed(core: usize) {
+    #[cfg(feature = "log")]
+    log::warn!("failed to pin thread to core {core}, running without affinity");
+    #[cfg(not(feature = "log"))]
+    let _ = core;
+}
+
+/// Log that an executor has started its nodes.
+#[cfg(feature = "std")]
+pub(crate) fn log_nodes_started(count: usize) {
+    #[cfg(feature = "log")]
+    log::info!("started {count} node(s)");
+    #[cfg(not(feature = "log"))]
+    let _ = count;
+}
+
+/// Log that a node has fallen `missed` full update periods behind.
+#[cfg(feature = "std")]
+pub(crate) fn log_deadline_missed(missed: u32) {
+    #[cfg(feature = "log")]
+    log::warn!("node overran its update period, missing {missed} deadline(s)");
+    #[cfg(not(feature = "log"))]
+    let _ = missed;
+}
+
+/// Log that an executor received an interrupt.
+#[cfg(feature = "std")]
+pub(crate) fn log_interrupt() {
+    #[cfg(feature = "log")]
+    log::info!("interrupt received, stopping executor");
+}
+
+/// Log that a node's shutdown exceeded the executor's shutdown timeout and the
+/// node was abandoned.
+#[cfg(feature = "std")]
+pub(crate) fn log_shutdown_timeout() {
+    #[cfg(feature = "log")]
+    log::error!("node shutdown timed out, abandoning node");
+}
+
+/// Log that a node panicked during its update.
+#[cfg(feature = "std")]
+pub(crate) fn log_node_panicked(payload: &(dyn core::any::Any + Send)) {
+    #[cfg(feature = "log")]
+    {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        log::error!("node panicked during its update: {message}");
+    }
+    #[cfg(not(feature = "log"))]
+    let _ = payload;
+}
+
 #[cfg(any(feature = "alloc", feature = "std"))]
 /// The NodeWrapper wraps nodes giving them a priority based on the timestamp
 /// of their next update.
 ///
-/// This ensures that nodes are updated at the correct time
-pub(crate) struct NodeWrapper<ID: PartialEq> {
-    /// The timestamp of the nodes next update
+/// This ensures that nodes are updated at the correct time.  NodeWrappers are
+/// ordered so that a vector of NodeWrappers kept sorted via `insert_into` has the
+/// node that should be updated next at its end, which makes NodeWrappers usable
+/// for executors implemented outside of this crate.
+///
+/// Note: The priority of a NodeWrapper must always be the timestamp (in
+/// microseconds since the executor was started) of the node's next update.
+pub struct NodeWrapper<ID: PartialEq> {
+    /// The timestamp (in microseconds) of the node's next update
     pub priority: u128,
-    /// The nde this NodeWrapper is wrapping around
+    /// The node this NodeWrapper is wrapping around
     pub node: Box<dyn Node<ID>>,
+    /// The priority class of the node (higher classes are dispatched first
+    /// when multiple nodes are due at the same time)
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) priority_class: u8,
+    /// The execution metrics of the node
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: NodeMetrics,
+    /// The error and overrun history of the node
+    #[cfg(feature = "std")]
+    pub(crate) health: NodeHealth,
+    /// The order the node was added to the executor in (used by executors to
+    /// break ties between nodes due at the same time deterministically)
+    #[cfg(feature = "std")]
+    pub(crate) sequence: u64,
 }
 
 #[cfg(any(feature = "alloc", feature = "std"))]
 impl<ID: PartialEq> NodeWrapper<ID> {
+    /// Wrap a node that should next be updated at the timestamp `priority`
+    pub fn new(priority: u128, node: Box<dyn Node<ID>>) -> Self {
+        Self {
+            priority,
+            node,
+            priority_class: 0,
+            #[cfg(feature = "metrics")]
+            metrics: NodeMetrics::default(),
+            #[cfg(feature = "std")]
+            health: NodeHealth::default(),
+            #[cfg(feature = "std")]
+            sequence: 0,
+        }
+    }
+
     /// Destroy the node wrapper returning the node it was wrapping.
     pub fn destroy(self) -> Box<dyn Node<ID>> {
         self.node
@@ -93,7 +408,7 @@ impl<ID: PartialEq> Eq for NodeWrapper<ID> {}
 /// This is just a convenience method I found myself using a ton so I decided
 /// to make it its own method.
 #[inline(always)]
-pub(crate) fn insert_into<ID: PartialEq>(vec: &mut Vec<NodeWrapper<ID>>, node: NodeWrapper<ID>) {
+pub fn insert_into<ID: PartialEq>(vec: &mut Vec<NodeWrapper<ID>>, node: NodeWrapper<ID>) {
     // If another node is found with the same priority, insert the node after that
     // node.  Otherwise, insert the node into the position it should be in in the
     // sorted vector
@@ -102,3 +417,161 @@ pub(crate) fn insert_into<ID: PartialEq>(vec: &mut Vec<NodeWrapper<ID>>, node: N
         Err(idx) => vec.insert(idx, node),
     }
 }
+
+/// Shut down the nodes in `backing` for a given reason.
+///
+/// If a `timeout` is given, each node is shut down on a watchdog thread against a
+/// single deadline for all of the nodes, so any node whose shutdown doesn't finish
+/// before the deadline is abandoned (and removed from the backing) and a stuck node
+/// can't keep the executor from stopping for more than the timeout.
+#[cfg(feature = "std")]
+pub(crate) fn shutdown_nodes<ID: PartialEq + 'static>(
+    backing: &mut Vec<NodeWrapper<ID>>,
+    reason: ncomm_core::ShutdownReason,
+    timeout: Option<std::time::Duration>,
+) {
+    let Some(timeout) = timeout else {
+        for node_wrapper in backing.iter_mut() {
+            node_wrapper.priority = 0;
+            node_wrapper.node.shutdown_with_reason(reason);
+        }
+        return;
+    };
+
+    let deadline = std::time::Instant::now() + timeout;
+    for mut node_wrapper in core::mem::take(backing) {
+        node_wrapper.priority = 0;
+        let (tx, rx) = crossbeam::channel::bounded(1);
+        std::thread::spawn(move || {
+            node_wrapper.node.shutdown_with_reason(reason);
+            let _ = tx.send(node_wrapper);
+        });
+
+        match rx.recv_deadline(deadline) {
+            Ok(node_wrapper) => backing.push(node_wrapper),
+            Err(_) => log_shutdown_timeout(),
+        }
+    }
+}
+
+/// Compute the number of full update periods a node has missed given the
+/// current time (`now`), the node's scheduled update time (`priority`), and
+/// the node's update delay (all in microseconds).
+///
+/// A node is only considered to have missed its deadline once
+/// `now >= priority + delay`.
+#[cfg(any(feature = "alloc", feature = "std"))]
+#[inline(always)]
+pub(crate) fn missed_deadlines(now: u128, priority: u128, delay: u128) -> u32 {
+    if delay == 0 || now < priority.saturating_add(delay) {
+        0
+    } else {
+        u32::try_from((now - priority) / delay).unwrap_or(u32::MAX)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    /// A minimal executor built only from the public scheduling primitives (like an
+    /// executor implemented outside of this crate would be)
+    struct StepExecutor {
+        backing: Vec<NodeWrapper<u8>>,
+    }
+
+    impl StepExecutor {
+        /// Update the next node as if the current time were its scheduled update time
+        fn step(&mut self) {
+            let mut node_wrapper = self.backing.pop().unwrap();
+            node_wrapper.node.update();
+            node_wrapper.priority += node_wrapper.node.get_update_delay_us();
+            insert_into(&mut self.backing, node_wrapper);
+        }
+    }
+
+    struct OrderNode {
+        id: u8,
+        update_delay: u128,
+        order: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Node<u8> for OrderNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            self.order.lock().unwrap().push(self.id);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            self.update_delay
+        }
+    }
+
+    #[test]
+    fn test_external_executor() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut executor = StepExecutor {
+            backing: Vec::new(),
+        };
+        for (id, update_delay) in [(0, 10), (1, 25)] {
+            let node = Box::new(OrderNode {
+                id,
+                update_delay,
+                order: order.clone(),
+            });
+            insert_into(&mut executor.backing, NodeWrapper::new(update_delay, node));
+        }
+
+        for _ in 0..5 {
+            executor.step();
+        }
+
+        // Node 0 is due at 10, 20, 30, 40, 50 and node 1 at 25, 50
+        assert_eq!(*order.lock().unwrap(), vec![0, 0, 1, 0, 0]);
+        assert_eq!(executor.backing.last().unwrap().priority, 50);
+    }
+
+    #[test]
+    fn test_dyn_executors() {
+        use crossbeam::channel::unbounded;
+        use ncomm_core::executor::DynExecutor;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let node = |id| {
+            Box::new(OrderNode {
+                id,
+                update_delay: 10_000,
+                order: order.clone(),
+            })
+        };
+
+        let (_, simple_rx) = unbounded();
+        let (_, threadpool_rx) = unbounded();
+        let mut executors: Vec<Box<dyn DynExecutor<u8>>> = vec![
+            Box::new(SimpleExecutor::new_with(simple_rx, vec![node(0)])),
+            Box::new(ThreadPoolExecutor::new_with(
+                2,
+                threadpool_rx,
+                vec![node(1)],
+            )),
+        ];
+
+        for executor in executors.iter_mut() {
+            executor.start();
+            assert_eq!(executor.state(), ncomm_core::ExecutorState::Started);
+        }
+        for executor in executors.iter_mut() {
+            executor.update_for_ms(5);
+        }
+
+        let mut order = order.lock().unwrap().clone();
+        order.sort();
+        assert_eq!(order, vec![0, 1]);
+        assert!(executors[1].contains_node(&1));
+    }
+}
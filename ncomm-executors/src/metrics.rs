@@ -0,0 +1,91 @@
+//!
+//! Execution Metrics for Nodes
+//!
+//! When the `metrics` feature is enabled, the executors record how long each
+//! node's update takes so that nodes blowing through their update budget can
+//! be identified.
+//!
+
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+use crate::NodeWrapper;
+
+/// Wall-clock timing metrics for the updates of a single Node
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NodeMetrics {
+    /// The duration of the most recent update
+    pub last: Duration,
+    /// The shortest update duration
+    pub min: Duration,
+    /// The longest update duration
+    pub max: Duration,
+    /// The mean update duration
+    pub mean: Duration,
+    /// The number of updates that have been recorded
+    pub count: u64,
+    /// The number of updates that took longer than the node's update delay
+    pub overruns: u64,
+    /// The total duration of every recorded update
+    total: Duration,
+}
+
+impl NodeMetrics {
+    /// Record the duration of a node's update where the node expects to be
+    /// updated every `update_delay_us` microseconds.
+    pub(crate) fn record(&mut self, duration: Duration, update_delay_us: u128) {
+        if self.count == 0 || duration < self.min {
+            self.min = duration;
+        }
+        if duration > self.max {
+            self.max = duration;
+        }
+        if duration.as_micros() > update_delay_us {
+            self.overruns += 1;
+        }
+
+        self.last = duration;
+        self.count += 1;
+        self.total += duration;
+        self.mean = Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64);
+    }
+}
+
+/// Refresh the execution metrics of every node in `backing` in place, removing
+/// the metrics of nodes that are no longer in `backing`.
+pub(crate) fn refresh_metrics<ID: PartialEq + Eq + Hash>(
+    metrics: &mut HashMap<ID, NodeMetrics>,
+    backing: &[NodeWrapper<ID>],
+) {
+    for node_wrapper in backing.iter() {
+        metrics.insert(node_wrapper.node.get_id(), node_wrapper.metrics);
+    }
+
+    if metrics.len() != backing.len() {
+        metrics.retain(|id, _| {
+            backing
+                .iter()
+                .any(|node_wrapper| node_wrapper.node.get_id().eq(id))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut metrics = NodeMetrics::default();
+
+        metrics.record(Duration::from_micros(200), 500);
+        metrics.record(Duration::from_micros(100), 500);
+        metrics.record(Duration::from_micros(600), 500);
+
+        assert_eq!(metrics.last, Duration::from_micros(600));
+        assert_eq!(metrics.min, Duration::from_micros(100));
+        assert_eq!(metrics.max, Duration::from_micros(600));
+        assert_eq!(metrics.mean, Duration::from_micros(300));
+        assert_eq!(metrics.count, 3);
+        assert_eq!(metrics.overruns, 1);
+    }
+}
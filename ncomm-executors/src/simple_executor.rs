@@ -12,15 +12,107 @@
 //! executor for single threaded execution.
 //!
 
-use std::any::Any;
-
-use crossbeam::channel::Receiver;
+use std::{
+    any::Any,
+    fmt::Debug,
+    hash::Hash,
+    panic::{self, AssertUnwindSafe},
+    thread,
+    time::Duration,
+};
+
+use crossbeam::channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+
+#[cfg(feature = "metrics")]
+use std::collections::HashMap;
+
+use ncomm_core::{Executor, ExecutorState, Node, ShutdownReason};
+
+use crate::{
+    clock::{Clock, SystemClock},
+    export_graph,
+    index::{insert_in_sequence, position_of, HashIdIndex, IdIndex},
+    log_deadline_missed, log_interrupt, log_node_panicked, log_node_rejected, log_nodes_started,
+    log_update_error, missed_deadlines, shutdown_nodes, shutdown_reason, ErrorPolicy,
+    InvalidStateError, NodeGroups, NodeHealth, NodeWrapper, PanicPolicy, SchedulingMode,
+    StateCallback,
+};
+
+#[cfg(feature = "metrics")]
+use crate::{metrics::refresh_metrics, NodeMetrics};
+
+/// A hook invoked every iteration of the Simple Executor's loop
+type LoopHook = Box<dyn FnMut() + Send>;
+
+#[cfg(all(feature = "realtime", target_os = "linux"))]
+use crate::log_realtime_priority_failed;
+
+/// The time (in microseconds) before a node's next update that a sleeping
+/// SimpleExecutor will wake up and busy wait for precision.
+const SLEEP_SPIN_MARGIN_US: u128 = 1_000;
+
+/// The longest time (in microseconds) a sleeping SimpleExecutor will sleep for
+/// when it has no nodes to update.
+const MAX_IDLE_SLEEP_US: u128 = 100_000;
+
+/// Statistics describing how closely a SimpleExecutor's loop keeps up with
+/// the nodes it is updating
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LoopStats {
+    /// The number of node updates dispatched by the executor
+    pub dispatches: u64,
+    /// The mean time between consecutive node dispatches
+    pub mean_interval: Duration,
+    /// The standard deviation of the time between consecutive node dispatches
+    pub jitter: Duration,
+    /// The number of loop cycles spent waiting for the next node update
+    pub waits: u64,
+    /// The number of waiting loop cycles where the executor slept (instead of spinning)
+    pub sleeps: u64,
+    /// The time (according to the executor's clock) of the most recent dispatch
+    last_dispatch: Option<Duration>,
+    /// The running sum of squared differences from the mean interval (in nanoseconds)
+    interval_m2: f64,
+}
 
-use quanta::{Clock, Instant};
+impl LoopStats {
+    /// Get the fraction of waiting loop cycles where the executor slept.
+    ///
+    /// A fraction near 0 means the executor spent its time spinning (i.e. it is
+    /// busy waiting or CPU-bound) while a fraction near 1 means it mostly slept.
+    pub fn sleep_fraction(&self) -> f64 {
+        if self.waits == 0 {
+            0.0
+        } else {
+            self.sleeps as f64 / self.waits as f64
+        }
+    }
 
-use ncomm_core::{Executor, ExecutorState, Node};
+    /// Record a node being dispatched at a given time
+    fn record_dispatch(&mut self, now: Duration) {
+        if let Some(last_dispatch) = self.last_dispatch {
+            // Welford's algorithm for the running mean and variance of the intervals
+            let interval = now.saturating_sub(last_dispatch).as_nanos() as f64;
+            let intervals = self.dispatches as f64;
+            let mean = self.mean_interval.as_nanos() as f64;
+            let delta = interval - mean;
+            let mean = mean + delta / intervals;
+            self.interval_m2 += delta * (interval - mean);
+            self.mean_interval = Duration::from_nanos(mean as u64);
+            self.jitter = Duration::from_nanos((self.interval_m2 / intervals).sqrt() as u64);
+        }
+        self.last_dispatch = Some(now);
+        self.dispatches += 1;
+    }
 
-use crate::{insert_into, NodeWrapper};
+    /// Record a loop cycle spent waiting for the next node update
+    fn record_wait(&mut self, slept: bool) {
+        self.waits += 1;
+        if slept {
+            self.sleeps += 1;
+        }
+    }
+}
 
 /// Simple Executor
 ///
@@ -31,62 +123,590 @@ use crate::{insert_into, NodeWrapper};
 /// Note: The Simple Executor can be interrupted by sending a true value
 /// over the mpsc channel whose receiving end is owned by the SimpleExecutor
 ///
-/// Addendum: The Simple Executor will also busy wait between node executions
+/// Addendum: By default, the Simple Executor will busy wait between node executions
 /// so do not expect the SimpleExecutor to yield CPU time to other processes while
-/// it is running.
+/// it is running.  For lower CPU usage (at the cost of some jitter), the
+/// SimpleExecutor can be configured to sleep between node executions via
+/// `set_sleep`.
+///
+/// By default, a node that panics during its update will unwind through the
+/// executor.  This can be changed by setting the executor's `PanicPolicy`.  Similarly,
+/// errors returned from a node's update are logged and handled according to the
+/// executor's `ErrorPolicy`.
+///
+/// A running Simple Executor can be paused (and resumed) by sending true (or false)
+/// over the Sender returned by `pause_handle`.
 pub struct SimpleExecutor<ID: PartialEq> {
     /// The sorted backing vector for the executor
     pub(crate) backing: Vec<NodeWrapper<ID>>,
-    /// The quanta high-precision clock backing the SimplExecutor
-    clock: Clock,
+    /// The clock backing the SimpleExecutor (the quanta high-precision clock by default)
+    clock: Box<dyn Clock>,
     /// The current state of the executor
     state: ExecutorState,
-    /// The Instant the executor was started
-    start_instant: Instant,
+    /// The time (according to the clock) the executor was started
+    start_instant: Duration,
     /// The Interrupt receiver channel
     interrupt: Receiver<bool>,
     /// Whether or not the executor has been interrupted
     interrupted: bool,
+    /// How the executor should handle a node panicking during its update
+    panic_policy: PanicPolicy,
+    /// How the executor should handle a node returning an error from its update
+    error_policy: ErrorPolicy,
+    /// When the executor should schedule a node's next update
+    scheduling_mode: SchedulingMode,
+    /// Whether the executor should sleep (instead of busy waiting) until
+    /// shortly before the next node update
+    sleep: bool,
+    /// The sending end of the pause channel handed out to users of the executor
+    pause_sender: Sender<bool>,
+    /// The receiving end of the pause channel
+    pause_receiver: Receiver<bool>,
+    /// The time (according to the clock) the executor was paused
+    paused_instant: Duration,
+    /// How long a node's shutdown may take before the node is abandoned
+    shutdown_timeout: Option<Duration>,
+    /// The callback invoked whenever the executor changes state
+    on_state_change: Option<StateCallback>,
+    /// The hook invoked every iteration of the executor's loop
+    loop_hook: Option<LoopHook>,
+    /// The ids of the nodes in each group of nodes
+    groups: NodeGroups<ID>,
+    /// The index of the priority and sequence of each node by id (if enabled).  The
+    /// index is updated when nodes are added or removed and rebuilt whenever the
+    /// executor starts or stops
+    index: Option<Box<dyn IdIndex<ID>>>,
+    /// The sequence given to the next node inserted into the backing vector
+    next_sequence: u64,
+    /// Statistics about the executor's loop since it was last started
+    loop_stats: LoopStats,
+    /// The execution metrics of every node keyed by id (refreshed by `metrics`)
+    #[cfg(feature = "metrics")]
+    metrics: HashMap<ID, NodeMetrics>,
+    /// The SCHED_FIFO priority the executing thread is given when `update_loop` starts
+    #[cfg(all(feature = "realtime", target_os = "linux"))]
+    realtime_priority: Option<u8>,
 }
 
 impl<ID: PartialEq> SimpleExecutor<ID> {
     /// Create a new Simple Executor without any Nodes
     pub fn new(interrupt: Receiver<bool>) -> Self {
-        let clock = Clock::new();
-        let now = clock.now();
-
-        Self {
-            backing: Vec::new(),
-            clock,
-            start_instant: now,
-            state: ExecutorState::Stopped,
-            interrupt,
-            interrupted: false,
-        }
+        Self::new_with(interrupt, Vec::new())
     }
 
     /// Creates a new Simple Executor with a number of Nodes
     pub fn new_with(interrupt: Receiver<bool>, mut nodes: Vec<Box<dyn Node<ID>>>) -> Self {
         let mut backing = Vec::new();
         for node in nodes.drain(..) {
-            backing.push(NodeWrapper { priority: 0, node });
+            backing.push(NodeWrapper::new(0, node));
         }
 
-        let clock = Clock::new();
+        let clock: Box<dyn Clock> = Box::new(SystemClock::new());
         let now = clock.now();
 
-        Self {
+        let (pause_sender, pause_receiver) = unbounded();
+
+        let mut executor = Self {
             backing,
             clock,
             start_instant: now,
             state: ExecutorState::Stopped,
             interrupt,
             interrupted: false,
+            panic_policy: PanicPolicy::default(),
+            error_policy: ErrorPolicy::default(),
+            scheduling_mode: SchedulingMode::default(),
+            sleep: false,
+            pause_sender,
+            pause_receiver,
+            paused_instant: now,
+            shutdown_timeout: None,
+            on_state_change: None,
+            loop_hook: None,
+            groups: NodeGroups::default(),
+            index: None,
+            next_sequence: 0,
+            loop_stats: LoopStats::default(),
+            #[cfg(feature = "metrics")]
+            metrics: HashMap::new(),
+            #[cfg(all(feature = "realtime", target_os = "linux"))]
+            realtime_priority: None,
+        };
+        executor.resequence();
+        executor
+    }
+
+    /// Set the policy for handling nodes that panic during their update
+    pub fn set_panic_policy(&mut self, panic_policy: PanicPolicy) {
+        self.panic_policy = panic_policy;
+    }
+
+    /// Set the policy for handling nodes that return an error from their update
+    pub fn set_error_policy(&mut self, error_policy: ErrorPolicy) {
+        self.error_policy = error_policy;
+    }
+
+    /// Set when the executor schedules a node's next update (by default, nodes are
+    /// updated at a fixed rate)
+    pub fn set_scheduling_mode(&mut self, scheduling_mode: SchedulingMode) {
+        self.scheduling_mode = scheduling_mode;
+    }
+
+    /// Set how long shutting down the nodes may take when the executor stops.
+    ///
+    /// With a timeout, nodes are shut down on a watchdog thread against a single
+    /// deadline and any node whose shutdown hasn't finished by the deadline is
+    /// abandoned (and removed from the executor) so the executor can still return.
+    /// By default, there is no timeout.
+    pub fn set_shutdown_timeout(&mut self, shutdown_timeout: Option<Duration>) {
+        self.shutdown_timeout = shutdown_timeout;
+    }
+
+    /// Register a callback that is invoked with the new state of the executor
+    /// every time the executor changes state.
+    pub fn on_state_change(&mut self, callback: impl FnMut(ExecutorState) + Send + 'static) {
+        self.on_state_change = Some(Box::new(callback));
+    }
+
+    /// Set a hook that is invoked once every iteration of the executor's loop (in
+    /// `update_for_ms` and `update_loop`), right after checking for an interrupt
+    /// (i.e. to kick a watchdog or drain an external queue).
+    ///
+    /// Note: The hook is invoked on the executor's thread between node updates so
+    /// it should return quickly.
+    pub fn set_loop_hook(&mut self, hook: impl FnMut() + Send + 'static) {
+        self.loop_hook = Some(Box::new(hook));
+    }
+
+    /// Transition the executor into a given state, notifying the state change
+    /// callback if the state changed.
+    fn set_state(&mut self, state: ExecutorState) {
+        if self.state != state {
+            self.state = state;
+            if let Some(callback) = self.on_state_change.as_mut() {
+                callback(state);
+            }
+        }
+    }
+
+    /// Get statistics about the executor's loop since it was last started.
+    ///
+    /// Comparing the mean interval between dispatches with the update delays of
+    /// the nodes shows whether the executor is achieving its target frequency.
+    pub fn loop_stats(&self) -> LoopStats {
+        self.loop_stats
+    }
+
+    /// Run the executor's thread with the SCHED_FIFO real-time policy at a given
+    /// priority (1 to 99) once `update_loop` starts.
+    ///
+    /// Note: Changing the scheduling policy requires the CAP_SYS_NICE capability
+    /// (or a sufficient RLIMIT_RTPRIO).  Without it, a warning is logged and the
+    /// executor runs with the thread's existing policy.
+    ///
+    /// Additionally, the policy is kept by the thread after the executor stops.
+    #[cfg(all(feature = "realtime", target_os = "linux"))]
+    pub fn set_realtime_priority(&mut self, priority: u8) {
+        self.realtime_priority = Some(priority);
+    }
+
+    /// Give the executing thread the SCHED_FIFO policy (if a real-time priority was set)
+    #[cfg(all(feature = "realtime", target_os = "linux"))]
+    fn apply_realtime_priority(&self) {
+        let Some(priority) = self.realtime_priority else {
+            return;
+        };
+
+        let param = libc::sched_param {
+            sched_priority: i32::from(priority),
+        };
+        // SAFETY: pthread_self always returns a valid handle to the calling thread
+        // and param is a valid sched_param for the duration of the call.
+        let result =
+            unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+        if result != 0 {
+            log_realtime_priority_failed(&std::io::Error::from_raw_os_error(result));
+        }
+    }
+
+    /// Set whether the executor should sleep between node updates.
+    ///
+    /// When enabled, the executor sleeps until just before the next node
+    /// update and then busy waits for the remaining time.  When disabled
+    /// (the default), the executor busy waits for the lowest possible jitter.
+    pub fn set_sleep(&mut self, sleep: bool) {
+        self.sleep = sleep;
+    }
+
+    /// Get a handle that can be used to pause (by sending true) and resume (by
+    /// sending false) the executor while it is running.
+    pub fn pause_handle(&self) -> Sender<bool> {
+        self.pause_sender.clone()
+    }
+
+    /// Set the clock the executor measures time with.
+    ///
+    /// By default, the executor uses the wall clock but it can be given a simulated
+    /// clock (i.e. a `ManualClock`) to run time-dependent nodes deterministically.
+    /// Simulated clocks skip ahead to the next node update instead of waiting for it.
+    ///
+    /// Note: The clock should only be changed while the executor is stopped.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+        self.start_instant = self.clock.now();
+        self.paused_instant = self.start_instant;
+    }
+
+    /// Get the time (in microseconds) since the executor was started.
+    fn now_us(&self) -> u128 {
+        self.clock
+            .now()
+            .saturating_sub(self.start_instant)
+            .as_micros()
+    }
+
+    /// Get the time (in microseconds) the executor has spent running.
+    ///
+    /// Note: While the executor is paused the elapsed time does not advance.
+    fn elapsed_us(&self) -> u128 {
+        if self.state == ExecutorState::Paused {
+            self.paused_instant
+                .saturating_sub(self.start_instant)
+                .as_micros()
+        } else {
+            self.now_us()
+        }
+    }
+
+    /// Wait for the next node update at the given timestamp (in microseconds since
+    /// the executor was started).
+    ///
+    /// Simulated clocks skip straight to the timestamp, otherwise the executor sleeps
+    /// (if configured to) or returns immediately to busy wait.
+    fn wait_until(&mut self, timestamp: u128) {
+        let time = self.start_instant + Duration::from_micros(timestamp as u64);
+        let slept = !self.clock.skip_to(time) && self.sleep && self.sleep_until(timestamp);
+        self.loop_stats.record_wait(slept);
+    }
+
+    /// Sleep until shortly before the given timestamp (in microseconds since the
+    /// executor was started), waking early if an interrupt is received.
+    ///
+    /// Returns whether the executor slept (it doesn't sleep when the timestamp is
+    /// within the spin margin).
+    fn sleep_until(&mut self, timestamp: u128) -> bool {
+        let now = self.now_us();
+        if timestamp > now + SLEEP_SPIN_MARGIN_US {
+            let duration = Duration::from_micros((timestamp - now - SLEEP_SPIN_MARGIN_US) as u64);
+            match self.interrupt.recv_timeout(duration) {
+                Ok(interrupt) => self.interrupted = interrupt,
+                Err(RecvTimeoutError::Disconnected) => thread::sleep(duration),
+                Err(RecvTimeoutError::Timeout) => (),
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Pop the highest priority node from the backing vector, update it, and
+    /// insert it back into the backing vector with its next priority (according to
+    /// the executor's scheduling mode).
+    ///
+    /// If the node has fallen at least one full update period behind, it is
+    /// notified via `on_deadline_missed` before being updated.
+    ///
+    /// If the node panics during its update, the executor's panic policy decides
+    /// whether the panic is propagated, the node is removed (after being shut down
+    /// with `ShutdownReason::Panic`), or the node is restarted.
+    /// Likewise, if the node returns an error from its update, the error is logged and
+    /// the executor's error policy decides whether the node is removed (after being
+    /// shut down with `ShutdownReason::Error`) or restarted.
+    fn update_next_node(&mut self) {
+        self.loop_stats.record_dispatch(self.clock.now());
+        let mut node_wrapper = self.backing.pop().unwrap();
+        let now = self.now_us();
+        let missed = missed_deadlines(
+            now,
+            node_wrapper.priority,
+            node_wrapper.node.get_update_delay_us(),
+        );
+        if missed > 0 {
+            node_wrapper.node.on_deadline_missed(missed);
+            log_deadline_missed(missed);
+            node_wrapper.health.record_overruns(missed);
+        }
+        #[cfg(feature = "metrics")]
+        let update_start = self.clock.now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| node_wrapper.node.try_update_at(now)));
+        #[cfg(feature = "metrics")]
+        node_wrapper.metrics.record(
+            self.clock.now().saturating_sub(update_start),
+            node_wrapper.node.get_update_delay_us(),
+        );
+        match result {
+            Ok(Ok(())) => node_wrapper.health.record_success(),
+            Ok(Err(error)) => {
+                log_update_error(&error);
+                node_wrapper.health.record_error(now);
+                match self.error_policy {
+                    ErrorPolicy::Log => (),
+                    ErrorPolicy::RemoveNode => {
+                        node_wrapper
+                            .node
+                            .shutdown_with_reason(ShutdownReason::Error);
+                        self.unindex(&node_wrapper.node.get_id());
+                        return;
+                    }
+                    ErrorPolicy::Restart => node_wrapper.node.reset(),
+                }
+            }
+            Err(payload) => {
+                log_node_panicked(payload.as_ref());
+                node_wrapper.health.record_error(now);
+                match self.panic_policy {
+                    PanicPolicy::Propagate => panic::resume_unwind(payload),
+                    PanicPolicy::RemoveNode => {
+                        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| {
+                            node_wrapper
+                                .node
+                                .shutdown_with_reason(ShutdownReason::Panic)
+                        })) {
+                            log_node_panicked(payload.as_ref());
+                        }
+                        self.unindex(&node_wrapper.node.get_id());
+                        return;
+                    }
+                    PanicPolicy::Restart => node_wrapper.node.reset(),
+                }
+            }
+        }
+        node_wrapper.priority = match self.scheduling_mode {
+            SchedulingMode::FixedRate => node_wrapper.priority,
+            SchedulingMode::FixedDelay => self.now_us(),
+        } + node_wrapper.node.get_update_delay_us();
+        self.schedule(node_wrapper);
+    }
+
+    /// Insert a node into the backing vector after the other nodes with the same
+    /// priority.
+    ///
+    /// Note: The index isn't updated here as nodes are rescheduled after every update.
+    /// Instead, the index is rebuilt whenever the executor starts or stops.
+    fn schedule(&mut self, mut node_wrapper: NodeWrapper<ID>) {
+        node_wrapper.sequence = self.next_sequence;
+        self.next_sequence += 1;
+        insert_in_sequence(&mut self.backing, node_wrapper);
+    }
+
+    /// Find the position of the node with a given id in the backing vector
+    fn position_of(&self, id: &ID) -> Option<usize> {
+        position_of(&self.backing, self.index.as_deref(), id)
+    }
+
+    /// Remove the node with a given id from the index (if enabled)
+    fn unindex(&mut self, id: &ID) {
+        if let Some(index) = self.index.as_mut() {
+            index.remove(id);
+        }
+    }
+
+    /// Renumber the sequences of the nodes in the order of the backing vector,
+    /// rebuilding the index (if enabled).
+    ///
+    /// Note: This must be called whenever the priorities of the nodes are reset.
+    fn resequence(&mut self) {
+        if let Some(index) = self.index.as_mut() {
+            index.clear();
+        }
+        for (sequence, node_wrapper) in (0..).zip(self.backing.iter_mut()) {
+            node_wrapper.sequence = sequence;
+            if let Some(index) = self.index.as_mut() {
+                index.insert(node_wrapper.node.get_id(), node_wrapper.priority, sequence);
+            }
+        }
+        self.next_sequence = self.backing.len() as u64;
+    }
+
+    /// Get the error and overrun history of the node with a given id.
+    ///
+    /// Note: Panics are recorded as errors.  The history of a node is kept when the
+    /// node is restarted but is lost once the node is removed from the executor.
+    pub fn node_health(&self, id: &ID) -> Option<&NodeHealth> {
+        self.position_of(id).map(|idx| &self.backing[idx].health)
+    }
+
+    /// Get the execution metrics of the node with a given id
+    #[cfg(feature = "metrics")]
+    pub fn node_metrics(&self, id: &ID) -> Option<&NodeMetrics> {
+        self.position_of(id).map(|idx| &self.backing[idx].metrics)
+    }
+}
+
+impl<ID: PartialEq + 'static> SimpleExecutor<ID> {
+    /// Shut down the executor's nodes for a given reason
+    fn shutdown(&mut self, reason: ShutdownReason) {
+        shutdown_nodes(&mut self.backing, reason, self.shutdown_timeout);
+        self.resequence();
+    }
+
+    /// Check the pause channel for requests to pause or resume the executor.
+    fn check_pause(&mut self) {
+        while let Ok(pause) = self.pause_receiver.try_recv() {
+            if pause {
+                self.pause();
+            } else {
+                self.resume();
+            }
+        }
+    }
+
+    /// Add a node to the Simple Executor, returning an error if the executor is
+    /// Running or Paused.
+    ///
+    /// Note: Only 1 node can exist per id so a node added with the same id as an
+    /// existing node replaces the existing node.
+    pub fn try_add_node(&mut self, node: Box<dyn Node<ID>>) -> Result<(), InvalidStateError> {
+        if !matches!(self.state, ExecutorState::Stopped | ExecutorState::Started) {
+            return Err(InvalidStateError(self.state));
+        }
+
+        if let Some(idx) = self.position_of(&node.get_id()) {
+            self.backing.remove(idx);
+        }
+
+        let priority = if self.state == ExecutorState::Stopped {
+            0
+        } else {
+            self.now_us()
+        };
+        if let Some(index) = self.index.as_mut() {
+            index.insert(node.get_id(), priority, self.next_sequence);
+        }
+        self.schedule(NodeWrapper::new(priority, node));
+        Ok(())
+    }
+
+    /// Remove a node from the Simple Executor, returning an error if the executor
+    /// is Running.
+    pub fn try_remove_node(
+        &mut self,
+        id: &ID,
+    ) -> Result<Option<Box<dyn Node<ID>>>, InvalidStateError> {
+        if self.state == ExecutorState::Running {
+            return Err(InvalidStateError(self.state));
+        }
+
+        let idx = self.position_of(id);
+        self.unindex(id);
+        Ok(idx.map(|idx| self.backing.remove(idx).destroy()))
+    }
+
+    /// Start the executor and dispatch exactly `cycles` node updates (in the order
+    /// the nodes are scheduled) before shutting the nodes down.
+    ///
+    /// Unlike `update_for_ms`, the executor never waits for a node to be due, so the
+    /// number of updates is deterministic regardless of timing, which is mostly useful
+    /// for testing.
+    ///
+    /// Note: Fewer updates are dispatched if the executor is interrupted or runs out
+    /// of nodes (i.e. because a node was removed after an error).
+    pub fn update_for_cycles(&mut self, cycles: u128) {
+        // Start the Executor
+        self.start();
+
+        // Run the Executor
+        self.set_state(ExecutorState::Running);
+        for _ in 0..cycles {
+            if self.backing.is_empty() || self.check_interrupt() {
+                break;
+            }
+            self.update_next_node();
+        }
+
+        // Stop the Executor
+        let reason = shutdown_reason(self.interrupted);
+        self.shutdown(reason);
+        self.set_state(ExecutorState::Stopped);
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<ID: PartialEq + Eq + Hash> SimpleExecutor<ID> {
+    /// Get the execution metrics of every node in the executor keyed by the
+    /// id of the node.
+    ///
+    /// Note: The map is kept in the executor and refreshed in place, so polling
+    /// the metrics doesn't build a new map every call.
+    pub fn metrics(&mut self) -> &HashMap<ID, NodeMetrics> {
+        refresh_metrics(&mut self.metrics, &self.backing);
+        &self.metrics
+    }
+}
+
+impl<ID: PartialEq + 'static> SimpleExecutor<ID> {
+    /// Add a group of nodes to the Simple Executor that can be removed together via
+    /// `remove_group`.
+    ///
+    /// Note: Adding nodes to an existing group adds the nodes to the group.
+    pub fn add_node_group(&mut self, group_id: impl Into<String>, nodes: Vec<Box<dyn Node<ID>>>) {
+        let ids: Vec<ID> = nodes.iter().map(|node| node.get_id()).collect();
+        for node in nodes {
+            self.add_node(node);
         }
+        self.groups.insert(group_id.into(), ids);
+    }
+
+    /// Remove every node in a group from the Simple Executor, returning the removed nodes.
+    ///
+    /// Note: Nodes can only be removed from the executor when it is not running.
+    pub fn remove_group(&mut self, group_id: &str) -> Vec<Box<dyn Node<ID>>> {
+        self.groups
+            .remove(group_id)
+            .iter()
+            .filter_map(|id| self.remove_node(id))
+            .collect()
+    }
+
+    /// Get the ids of the nodes in a group
+    pub fn group_node_ids(&self, group_id: &str) -> Option<&[ID]> {
+        self.groups.get(group_id)
+    }
+}
+
+impl<ID: PartialEq + Eq + Hash + Send + 'static> SimpleExecutor<ID> {
+    /// Set whether the executor should keep an index of its nodes by id.
+    ///
+    /// Without the index, finding a node by id (i.e. when adding or removing a
+    /// node) scans every node in the executor.  With the index, the executor finds
+    /// the node by binary searching for its scheduled update so adding and removing
+    /// nodes stays fast for executors with many nodes.
+    pub fn set_id_index(&mut self, index: bool) {
+        self.index = if index {
+            Some(Box::new(HashIdIndex::new()))
+        } else {
+            None
+        };
+        self.resequence();
+    }
+}
+
+impl<ID: PartialEq + Debug> SimpleExecutor<ID> {
+    /// Export a Graphviz (DOT) description of the executor's nodes and the topics
+    /// they publish and subscribe to (as reported by `Node::published_topics` and
+    /// `Node::subscribed_topics`).
+    ///
+    /// Note: The graph can be rendered with, for example, `dot -Tsvg graph.dot`.
+    pub fn export_graph(&self) -> String {
+        export_graph(
+            self.backing
+                .iter()
+                .map(|node_wrapper| node_wrapper.node.as_ref()),
+        )
     }
 }
 
-impl<ID: PartialEq> Executor<ID> for SimpleExecutor<ID> {
+impl<ID: PartialEq + 'static> Executor<ID> for SimpleExecutor<ID> {
     /// Context doesn't really apply to SimpleExecutors
     type Context = Box<dyn Any>;
 
@@ -101,86 +721,130 @@ impl<ID: PartialEq> Executor<ID> for SimpleExecutor<ID> {
             node_wrapper.priority = 0;
             node_wrapper.node.start();
         }
+        self.resequence();
+        log_nodes_started(self.backing.len());
 
         self.interrupted = false;
-        self.state = ExecutorState::Started;
+        self.loop_stats = LoopStats::default();
+        self.set_state(ExecutorState::Started);
         self.start_instant = self.clock.now();
     }
 
     /// Start the executor and run the executor for a given number of milliseconds before
     /// stopping the executor.  An interrupt will also stop the executor early.
     ///
-    /// Note: if there are no Nodes currently in the executor it will busy wait until the
+    /// Note: if there are no Nodes currently in the executor it will wait until the
     /// time has passed or an interrupt occurs
+    ///
+    /// Additionally, time spent paused does not count towards the given number of
+    /// milliseconds.
     fn update_for_ms(&mut self, ms: u128) {
         // Start the Executor
         self.start();
 
         // Run the Executor
-        self.state = ExecutorState::Running;
-        while self
-            .clock
-            .now()
-            .duration_since(self.start_instant)
-            .as_millis()
-            < ms
-            && !self.check_interrupt()
-        {
+        self.set_state(ExecutorState::Running);
+        while self.elapsed_us() < ms * 1_000 && !self.check_interrupt() {
+            if let Some(hook) = self.loop_hook.as_mut() {
+                hook();
+            }
+            self.check_pause();
+            if self.state == ExecutorState::Paused {
+                if self.sleep {
+                    thread::sleep(Duration::from_micros(SLEEP_SPIN_MARGIN_US as u64));
+                }
+                continue;
+            }
+
             if self.backing.last().is_some()
-                && self
-                    .clock
-                    .now()
-                    .duration_since(self.start_instant)
-                    .as_micros()
-                    >= self.backing.last().unwrap().priority
+                && self.now_us() >= self.backing.last().unwrap().priority
             {
-                let mut node_wrapper = self.backing.pop().unwrap();
-                node_wrapper.node.update();
-                node_wrapper.priority += node_wrapper.node.get_update_delay_us();
-                insert_into(&mut self.backing, node_wrapper);
+                self.update_next_node();
+            } else {
+                let end = ms * 1_000;
+                let next_update = self
+                    .backing
+                    .last()
+                    .map_or(end, |node_wrapper| node_wrapper.priority.min(end));
+                self.wait_until(next_update);
             }
         }
 
         // Stop the Executor
-        for node_wrapper in self.backing.iter_mut() {
-            node_wrapper.priority = 0;
-            node_wrapper.node.shutdown();
-        }
-        self.state = ExecutorState::Stopped;
+        let reason = shutdown_reason(self.interrupted);
+        self.shutdown(reason);
+        self.set_state(ExecutorState::Stopped);
     }
 
     /// Start the executor and run until an interrupt is received.
     ///
-    /// Note: if there are no Nodes currently in the executor it will busy wait until it
+    /// Note: if there are no Nodes currently in the executor it will wait until it
     /// receives an interrupt
     fn update_loop(&mut self) {
+        #[cfg(all(feature = "realtime", target_os = "linux"))]
+        self.apply_realtime_priority();
+
         // Start the Executor
         self.start();
 
         // Run the Executor
-        self.state = ExecutorState::Running;
+        self.set_state(ExecutorState::Running);
         while !self.check_interrupt() {
+            if let Some(hook) = self.loop_hook.as_mut() {
+                hook();
+            }
+            self.check_pause();
+            if self.state == ExecutorState::Paused {
+                if self.sleep {
+                    thread::sleep(Duration::from_micros(SLEEP_SPIN_MARGIN_US as u64));
+                }
+                continue;
+            }
+
             if self.backing.last().is_some()
-                && self
-                    .clock
-                    .now()
-                    .duration_since(self.start_instant)
-                    .as_micros()
-                    >= self.backing.last().unwrap().priority
+                && self.now_us() >= self.backing.last().unwrap().priority
             {
-                let mut node_wrapper = self.backing.pop().unwrap();
-                node_wrapper.node.update();
-                node_wrapper.priority += node_wrapper.node.get_update_delay_us();
-                insert_into(&mut self.backing, node_wrapper);
+                self.update_next_node();
+            } else {
+                let next_update = self.backing.last().map_or_else(
+                    || self.now_us() + MAX_IDLE_SLEEP_US,
+                    |node_wrapper| node_wrapper.priority,
+                );
+                self.wait_until(next_update);
             }
         }
 
         // Stop the Executor
-        for node_wrapper in self.backing.iter_mut() {
-            node_wrapper.priority = 0;
-            node_wrapper.node.shutdown();
+        let reason = shutdown_reason(self.interrupted);
+        self.shutdown(reason);
+        self.set_state(ExecutorState::Stopped);
+    }
+
+    /// Pause the executor so nodes are not updated until the executor is resumed.
+    ///
+    /// Note: Only a running executor can be paused.  Pausing a running executor is
+    /// usually done by sending true over the `pause_handle` channel.
+    fn pause(&mut self) {
+        if self.state == ExecutorState::Running {
+            self.paused_instant = self.clock.now();
+            self.set_state(ExecutorState::Paused);
+        }
+    }
+
+    /// Resume a paused executor.
+    ///
+    /// The start instant of the executor is shifted forward by the time spent paused
+    /// so nodes do not all update at once when the executor is resumed.
+    fn resume(&mut self) {
+        if self.state == ExecutorState::Paused {
+            self.start_instant += self.clock.now().saturating_sub(self.paused_instant);
+            self.set_state(ExecutorState::Running);
         }
-        self.state = ExecutorState::Stopped;
+    }
+
+    /// Get the current state of the executor
+    fn state(&self) -> ExecutorState {
+        self.state
     }
 
     /// Check the interrupt receiver for an interrupt.  If an interrupt
@@ -189,59 +853,45 @@ impl<ID: PartialEq> Executor<ID> for SimpleExecutor<ID> {
     fn check_interrupt(&mut self) -> bool {
         if let Ok(interrupt) = self.interrupt.try_recv() {
             self.interrupted = interrupt;
+            if interrupt {
+                log_interrupt();
+            }
         }
         self.interrupted
     }
 
     /// Add a node to the Simple Executor.
     ///
-    /// Note: Nodes can only be added to the executor when it is not running.
+    /// Note: Nodes can only be added to the executor when it is Stopped or Started.
+    /// Nodes added in any other state are dropped and the rejection is logged (use
+    /// `try_add_node` to handle the rejection instead).
     ///
     /// Additionally, only 1 node can exist per id so additional nodes added with
     /// the same id will replace the previous node of a given id.
     fn add_node(&mut self, node: Box<dyn Node<ID>>) {
-        if let Some(idx) = self
-            .backing
-            .iter()
-            .position(|node_wrapper| node_wrapper.node.get_id().eq(&node.get_id()))
-        {
-            self.backing.remove(idx);
-        }
-
-        if self.state == ExecutorState::Stopped {
-            self.backing.push(NodeWrapper { priority: 0, node });
-        } else if self.state == ExecutorState::Started {
-            insert_into(
-                &mut self.backing,
-                NodeWrapper {
-                    priority: self
-                        .clock
-                        .now()
-                        .duration_since(self.start_instant)
-                        .as_micros(),
-                    node,
-                },
-            );
+        if let Err(error) = self.try_add_node(node) {
+            log_node_rejected(&error);
         }
     }
 
     /// Remove a node from the Simple Executor.
     ///
-    /// Note: Nodes can only be removed from the executor when it is not running.
+    /// Note: Nodes can only be removed from the executor when it is not Running.
+    /// Removals in the Running state return None and the rejection is logged (use
+    /// `try_remove_node` to handle the rejection instead).
     fn remove_node(&mut self, id: &ID) -> Option<Box<dyn Node<ID>>> {
-        if self.state != ExecutorState::Running {
-            let idx = self
-                .backing
-                .iter()
-                .position(|node_wrapper| node_wrapper.node.get_id().eq(id));
-            if let Some(idx) = idx {
-                Some(self.backing.remove(idx).destroy())
-            } else {
-                None
-            }
-        } else {
+        self.try_remove_node(id).unwrap_or_else(|error| {
+            log_node_rejected(&error);
             None
-        }
+        })
+    }
+
+    /// Get the ids of the nodes in the Simple Executor.
+    fn node_ids(&self) -> Vec<ID> {
+        self.backing
+            .iter()
+            .map(|node_wrapper| node_wrapper.node.get_id())
+            .collect()
     }
 }
 
@@ -249,10 +899,22 @@ impl<ID: PartialEq> Executor<ID> for SimpleExecutor<ID> {
 mod tests {
     use super::*;
 
-    use std::{any::Any, thread, time::Duration};
+    use std::{
+        any::Any,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc, Mutex,
+        },
+        thread,
+        time::Duration,
+    };
 
     use crossbeam::channel::unbounded;
 
+    use ncomm_core::NodeError;
+
+    use crate::clock::ManualClock;
+
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     enum State {
         Stopped,
@@ -300,46 +962,426 @@ mod tests {
         }
     }
 
-    #[test]
-    /// Start should set the priority of all nodes to 0, start all nodes, set its
-    /// interrupted value to false, enter the ExecutorState::Started state and set its
-    /// start instant
-    fn test_simple_executor_start() {
-        let (_, rx) = unbounded();
-
-        let mut executor = SimpleExecutor::new_with(
-            rx,
-            vec![
-                Box::new(SimpleNode::new(0, 100_000)),
-                Box::new(SimpleNode::new(1, 250_000)),
-            ],
-        );
-        let original_start_instant = executor.start_instant;
-
-        executor.start();
+    pub struct PanickingNode {
+        id: u8,
+        pub num: u8,
+        pub starts: u8,
+    }
 
-        for node_wrapper in executor.backing.iter() {
-            assert_eq!(node_wrapper.priority, 0);
-            let simple_node: &dyn Any = &node_wrapper.node;
-            let simple_node: &Box<SimpleNode> = unsafe { simple_node.downcast_ref_unchecked() };
-            assert_eq!(simple_node.state, State::Started);
+    impl PanickingNode {
+        pub fn new(id: u8) -> Self {
+            Self {
+                id,
+                num: 0,
+                starts: 0,
+            }
         }
-        assert!(!executor.interrupted);
-        assert_eq!(executor.state, ExecutorState::Started);
-        assert!(executor.start_instant > original_start_instant);
     }
 
-    #[test]
-    fn test_update_for_ms() {
-        let (_, rx) = unbounded();
+    impl Node<u8> for PanickingNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
 
-        let mut executor = SimpleExecutor::new_with(
-            rx,
-            vec![
-                Box::new(SimpleNode::new(0, 10_000)),
-                Box::new(SimpleNode::new(1, 25_000)),
-            ],
-        );
+        fn start(&mut self) {
+            self.starts += 1;
+        }
+
+        fn update(&mut self) {
+            self.num += 1;
+            if self.num == 3 {
+                panic!("Panicking Node panicked on its third update");
+            }
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    pub struct FailingNode {
+        pub num: u8,
+        pub starts: u8,
+    }
+
+    impl Node<u8> for FailingNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn start(&mut self) {
+            self.starts += 1;
+        }
+
+        fn try_update(&mut self) -> Result<(), NodeError> {
+            self.num += 1;
+            if self.num == 3 {
+                Err(Box::new(std::io::Error::other("sensor fault")))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    pub struct SlowNode {
+        pub num: u8,
+        pub missed: Vec<u32>,
+    }
+
+    impl Node<u8> for SlowNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn update(&mut self) {
+            self.num += 1;
+            thread::sleep(Duration::from_millis(25));
+        }
+
+        fn on_deadline_missed(&mut self, missed: u32) {
+            self.missed.push(missed);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    pub struct AdaptiveNode {
+        pub update_delay: u128,
+        pub timestamps: Vec<std::time::Instant>,
+    }
+
+    impl Node<u8> for AdaptiveNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn update(&mut self) {
+            self.timestamps.push(std::time::Instant::now());
+            if self.timestamps.len() == 5 {
+                self.set_update_delay_us(self.update_delay / 2);
+            }
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            self.update_delay
+        }
+
+        fn set_update_delay_us(&mut self, delay_us: u128) {
+            self.update_delay = delay_us;
+        }
+    }
+
+    pub struct CountingNode {
+        id: u8,
+        update_delay: u128,
+        pub updates: u32,
+    }
+
+    impl CountingNode {
+        pub fn new(id: u8, update_delay: u128) -> Self {
+            Self {
+                id,
+                update_delay,
+                updates: 0,
+            }
+        }
+    }
+
+    impl Node<u8> for CountingNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            self.updates += 1;
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            self.update_delay
+        }
+    }
+
+    /// A node that counts its updates in a counter shared with the test
+    pub struct SharedCountingNode {
+        pub updates: Arc<AtomicU32>,
+    }
+
+    impl Node<u8> for SharedCountingNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn update(&mut self) {
+            self.updates.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            100_000
+        }
+    }
+
+    /// A node that panics on its first update and records the reason it was shut
+    /// down with in a slot shared with the test
+    pub struct PanickingReasonNode {
+        pub reason: Arc<Mutex<Option<ShutdownReason>>>,
+        pub panic_on_shutdown: bool,
+    }
+
+    impl Node<u8> for PanickingReasonNode {
+        fn get_id(&self) -> u8 {
+            1
+        }
+
+        fn update(&mut self) {
+            panic!("Panicking Reason Node panicked on its first update");
+        }
+
+        fn shutdown_with_reason(&mut self, reason: ShutdownReason) {
+            *self.reason.lock().unwrap() = Some(reason);
+            if self.panic_on_shutdown {
+                panic!("Panicking Reason Node panicked during its shutdown");
+            }
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    /// A node that panics on its second update and counts its resets and its
+    /// successful updates in counters shared with the test
+    pub struct ResettingNode {
+        pub updates: Arc<AtomicU32>,
+        pub resets: Arc<AtomicU32>,
+        pub panicked: bool,
+    }
+
+    impl Node<u8> for ResettingNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn reset(&mut self) {
+            self.resets.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn update(&mut self) {
+            if !self.panicked && self.updates.load(Ordering::Relaxed) == 1 {
+                self.panicked = true;
+                panic!("Resetting Node panicked on its second update");
+            }
+            self.updates.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    /// A node that timestamps its output with the executor's time
+    pub struct TimestampingNode {
+        pub outputs: Arc<Mutex<Vec<(u32, u128)>>>,
+    }
+
+    impl Node<u8> for TimestampingNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn try_update_at(&mut self, now_us: u128) -> Result<(), NodeError> {
+            let mut outputs = self.outputs.lock().unwrap();
+            let value = outputs.len() as u32;
+            outputs.push((value, now_us));
+            Ok(())
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    /// A node whose first update takes 15ms of (simulated) time
+    pub struct SlowStartNode {
+        pub clock: ManualClock,
+        pub outputs: Arc<Mutex<Vec<u128>>>,
+    }
+
+    impl Node<u8> for SlowStartNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn try_update_at(&mut self, now_us: u128) -> Result<(), NodeError> {
+            let mut outputs = self.outputs.lock().unwrap();
+            if outputs.is_empty() {
+                self.clock.advance(Duration::from_millis(15));
+            }
+            outputs.push(now_us);
+            Ok(())
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    /// A clock that jumps backwards (like a wall clock being corrected) once it
+    /// has been read a given number of times
+    pub struct BackwardJumpClock {
+        clock: SystemClock,
+        reads: AtomicU32,
+        jump_after: u32,
+        jump: Duration,
+    }
+
+    impl Clock for BackwardJumpClock {
+        fn now(&self) -> Duration {
+            let now = self.clock.now();
+            if self.reads.fetch_add(1, Ordering::SeqCst) >= self.jump_after {
+                now.saturating_sub(self.jump)
+            } else {
+                now
+            }
+        }
+    }
+
+    pub struct ReasonNode {
+        id: u8,
+        pub reason: Option<ShutdownReason>,
+    }
+
+    impl Node<u8> for ReasonNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn shutdown_with_reason(&mut self, reason: ShutdownReason) {
+            self.reason = Some(reason);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    pub struct StuckNode {
+        id: u8,
+    }
+
+    impl Node<u8> for StuckNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn shutdown(&mut self) {
+            thread::sleep(Duration::from_secs(5));
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    pub struct TimestampNode {
+        pub timestamps: Vec<std::time::Instant>,
+    }
+
+    impl Node<u8> for TimestampNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn update(&mut self) {
+            self.timestamps.push(std::time::Instant::now());
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            100_000
+        }
+    }
+
+    /// A logger that captures every record logged so tests can assert that
+    /// the expected events were logged
+    #[cfg(feature = "log")]
+    struct CapturingLogger {
+        records: std::sync::Mutex<Vec<(log::Level, String)>>,
+    }
+
+    #[cfg(feature = "log")]
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            self.records
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Get the CPU time (in clock ticks) consumed by the current thread
+    #[cfg(target_os = "linux")]
+    fn thread_cpu_ticks() -> u64 {
+        let stat = std::fs::read_to_string("/proc/thread-self/stat").unwrap();
+        let fields: Vec<&str> = stat
+            .rsplit_once(')')
+            .unwrap()
+            .1
+            .split_whitespace()
+            .collect();
+        fields[11].parse::<u64>().unwrap() + fields[12].parse::<u64>().unwrap()
+    }
+
+    #[test]
+    /// Start should set the priority of all nodes to 0, start all nodes, set its
+    /// interrupted value to false, enter the ExecutorState::Started state and set its
+    /// start instant
+    fn test_simple_executor_start() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 100_000)),
+                Box::new(SimpleNode::new(1, 250_000)),
+            ],
+        );
+        let original_start_instant = executor.start_instant;
+
+        executor.start();
+
+        for node_wrapper in executor.backing.iter() {
+            assert_eq!(node_wrapper.priority, 0);
+            let simple_node: &dyn Any = &node_wrapper.node;
+            let simple_node: &Box<SimpleNode> = unsafe { simple_node.downcast_ref_unchecked() };
+            assert_eq!(simple_node.state, State::Started);
+        }
+        assert!(!executor.interrupted);
+        assert_eq!(executor.state, ExecutorState::Started);
+        assert!(executor.start_instant > original_start_instant);
+    }
+
+    #[test]
+    fn test_update_for_ms() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(SimpleNode::new(1, 25_000)),
+            ],
+        );
 
         let start = executor.clock.now();
         executor.update_for_ms(100);
@@ -360,6 +1402,39 @@ mod tests {
         assert!(end - start < Duration::from_millis(105));
     }
 
+    #[test]
+    fn test_update_for_ms_dynamic_update_delay() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(AdaptiveNode {
+                update_delay: 20_000,
+                timestamps: Vec::new(),
+            })],
+        );
+
+        executor.update_for_ms(200);
+
+        let adaptive_node: &dyn Any = &executor.backing[0].node;
+        let adaptive_node: &Box<AdaptiveNode> = unsafe { adaptive_node.downcast_ref_unchecked() };
+        assert_eq!(adaptive_node.update_delay, 10_000);
+        // 5 updates over the first 80ms and an update every 10ms after that
+        assert!((15..=17).contains(&adaptive_node.timestamps.len()));
+        for (idx, window) in adaptive_node.timestamps.windows(2).enumerate() {
+            let interval = window[1] - window[0];
+            if idx < 4 {
+                assert!(
+                    Duration::from_millis(15) < interval && interval < Duration::from_millis(25)
+                );
+            } else {
+                assert!(
+                    Duration::from_millis(5) < interval && interval < Duration::from_millis(15)
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_check_interrupt() {
         let (tx, rx) = unbounded();
@@ -467,4 +1542,952 @@ mod tests {
         assert!(executor.interrupted);
         assert_eq!(executor.state, ExecutorState::Stopped);
     }
+
+    #[test]
+    fn test_update_for_ms_panic_remove_node() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(PanickingNode::new(1)),
+            ],
+        );
+        executor.set_panic_policy(PanicPolicy::RemoveNode);
+
+        executor.update_for_ms(100);
+
+        assert_eq!(executor.backing.len(), 1);
+        let simple_node: &dyn Any = &executor.backing[0].node;
+        let simple_node: &Box<SimpleNode> = unsafe { simple_node.downcast_ref_unchecked() };
+        assert!([9, 10, 11].contains(&simple_node.num));
+        assert_eq!(executor.state, ExecutorState::Stopped);
+    }
+
+    #[test]
+    fn test_update_for_ms_panic_remove_node_shutdown() {
+        for panic_on_shutdown in [false, true] {
+            let (_, rx) = unbounded();
+            let reason = Arc::new(Mutex::new(None));
+
+            let mut executor = SimpleExecutor::new_with(
+                rx,
+                vec![
+                    Box::new(SimpleNode::new(0, 10_000)),
+                    Box::new(PanickingReasonNode {
+                        reason: reason.clone(),
+                        panic_on_shutdown,
+                    }),
+                ],
+            );
+            executor.set_panic_policy(PanicPolicy::RemoveNode);
+
+            executor.update_for_ms(50);
+
+            assert_eq!(executor.node_ids(), vec![0]);
+            assert_eq!(*reason.lock().unwrap(), Some(ShutdownReason::Panic));
+        }
+    }
+
+    #[test]
+    fn test_update_for_ms_panic_restart() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(rx, vec![Box::new(PanickingNode::new(0))]);
+        executor.set_panic_policy(PanicPolicy::Restart);
+
+        executor.update_for_ms(100);
+
+        assert_eq!(executor.backing.len(), 1);
+        let panicking_node: &dyn Any = &executor.backing[0].node;
+        let panicking_node: &Box<PanickingNode> =
+            unsafe { panicking_node.downcast_ref_unchecked() };
+        assert_eq!(panicking_node.starts, 2);
+        assert!([9, 10, 11].contains(&panicking_node.num));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_update_for_ms_panic_propagate() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(PanickingNode::new(1)),
+            ],
+        );
+
+        executor.update_for_ms(100);
+    }
+
+    #[cfg(feature = "metrics")]
+    struct SleepingNode {
+        id: u8,
+    }
+
+    #[cfg(feature = "metrics")]
+    impl Node<u8> for SleepingNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            20_000
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn test_update_for_ms_metrics() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(rx, vec![Box::new(SleepingNode { id: 0 })]);
+
+        executor.update_for_ms(100);
+
+        let node_metrics = *executor.metrics().get(&0).unwrap();
+        assert_eq!(executor.node_metrics(&0), Some(&node_metrics));
+        assert!([4, 5, 6].contains(&node_metrics.count));
+        assert!(Duration::from_millis(5) <= node_metrics.mean);
+        assert!(node_metrics.mean < Duration::from_millis(7));
+        assert!(node_metrics.min <= node_metrics.mean && node_metrics.mean <= node_metrics.max);
+        assert_eq!(node_metrics.overruns, 0);
+        // The metrics of a removed node are dropped from the map
+        executor.remove_node(&0);
+        assert!(executor.metrics().is_empty());
+    }
+
+    #[test]
+    fn test_update_for_ms_deadline_missed() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(SlowNode {
+                num: 0,
+                missed: Vec::new(),
+            })],
+        );
+
+        executor.update_for_ms(100);
+
+        let slow_node: &dyn Any = &executor.backing[0].node;
+        let slow_node: &Box<SlowNode> = unsafe { slow_node.downcast_ref_unchecked() };
+        // The first update is on time, every update after that is late
+        assert_eq!(slow_node.missed.len(), slow_node.num as usize - 1);
+        assert_eq!(slow_node.missed[0], 1);
+        assert!(slow_node.missed.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_update_for_ms_sleep() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(TimestampNode {
+                timestamps: Vec::new(),
+            })],
+        );
+        executor.set_sleep(true);
+
+        let start_ticks = thread_cpu_ticks();
+        executor.update_for_ms(500);
+        let cpu_ticks = thread_cpu_ticks() - start_ticks;
+
+        // Busy waiting would consume ~500ms (50 ticks) of CPU time
+        assert!(cpu_ticks < 10);
+
+        let timestamp_node: &dyn Any = &executor.backing[0].node;
+        let timestamp_node: &Box<TimestampNode> =
+            unsafe { timestamp_node.downcast_ref_unchecked() };
+        assert!([5, 6].contains(&timestamp_node.timestamps.len()));
+        for timestamps in timestamp_node.timestamps.windows(2) {
+            let delay = timestamps[1] - timestamps[0];
+            assert!(Duration::from_millis(95) < delay);
+            assert!(delay < Duration::from_millis(105));
+        }
+    }
+
+    #[test]
+    fn test_node_ids() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(SimpleNode::new(1, 25_000)),
+            ],
+        );
+        executor.add_node(Box::new(SimpleNode::new(2, 1_000)));
+
+        let mut ids = executor.node_ids();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 2]);
+        assert!(executor.contains_node(&2));
+        assert!(!executor.contains_node(&3));
+
+        executor.remove_node(&1);
+        assert!(!executor.contains_node(&1));
+    }
+
+    #[test]
+    fn test_update_for_ms_pause() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(TimestampNode {
+                timestamps: Vec::new(),
+            })],
+        );
+
+        let pause = executor.pause_handle();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            let paused = std::time::Instant::now();
+            pause.send(true).unwrap();
+            thread::sleep(Duration::from_millis(150));
+            let resumed = std::time::Instant::now();
+            pause.send(false).unwrap();
+            (paused, resumed)
+        });
+
+        let start = std::time::Instant::now();
+        executor.update_for_ms(250);
+        let elapsed = start.elapsed();
+        let (paused, resumed) = handle.join().unwrap();
+
+        // The time spent paused does not count towards the 250ms
+        assert!(Duration::from_millis(390) < elapsed);
+        assert_eq!(executor.state, ExecutorState::Stopped);
+
+        let timestamp_node: &dyn Any = &executor.backing[0].node;
+        let timestamp_node: &Box<TimestampNode> =
+            unsafe { timestamp_node.downcast_ref_unchecked() };
+        assert_eq!(timestamp_node.timestamps.len(), 3);
+        assert!(timestamp_node
+            .timestamps
+            .iter()
+            .all(|timestamp| *timestamp < paused || *timestamp > resumed));
+
+        // The node continues 100ms (of running time) after its last update
+        let delay = timestamp_node.timestamps[1] - timestamp_node.timestamps[0];
+        assert!(Duration::from_millis(240) < delay);
+        assert!(delay < Duration::from_millis(260));
+    }
+
+    #[test]
+    fn test_update_for_ms_error_log() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            SimpleExecutor::new_with(rx, vec![Box::new(FailingNode { num: 0, starts: 0 })]);
+
+        executor.update_for_ms(100);
+
+        assert_eq!(executor.backing.len(), 1);
+        let failing_node: &dyn Any = &executor.backing[0].node;
+        let failing_node: &Box<FailingNode> = unsafe { failing_node.downcast_ref_unchecked() };
+        assert_eq!(failing_node.starts, 1);
+        assert!([9, 10, 11].contains(&failing_node.num));
+    }
+
+    #[test]
+    fn test_update_for_ms_error_remove_node() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            SimpleExecutor::new_with(rx, vec![Box::new(FailingNode { num: 0, starts: 0 })]);
+        executor.set_error_policy(ErrorPolicy::RemoveNode);
+
+        executor.update_for_ms(100);
+
+        assert!(executor.backing.is_empty());
+    }
+
+    #[test]
+    fn test_update_for_ms_error_restart() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            SimpleExecutor::new_with(rx, vec![Box::new(FailingNode { num: 0, starts: 0 })]);
+        executor.set_error_policy(ErrorPolicy::Restart);
+
+        executor.update_for_ms(100);
+
+        assert_eq!(executor.backing.len(), 1);
+        let failing_node: &dyn Any = &executor.backing[0].node;
+        let failing_node: &Box<FailingNode> = unsafe { failing_node.downcast_ref_unchecked() };
+        assert_eq!(failing_node.starts, 2);
+        assert!([9, 10, 11].contains(&failing_node.num));
+    }
+
+    #[test]
+    #[cfg(feature = "log")]
+    fn test_lifecycle_events_logged() {
+        static LOGGER: CapturingLogger = CapturingLogger {
+            records: std::sync::Mutex::new(Vec::new()),
+        };
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let (tx, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(SlowNode {
+                    num: 0,
+                    missed: Vec::new(),
+                }),
+                Box::new(PanickingNode::new(1)),
+            ],
+        );
+        executor.set_panic_policy(PanicPolicy::RemoveNode);
+
+        executor.update_for_ms(100);
+        tx.send(true).unwrap();
+        assert!(executor.check_interrupt());
+
+        let records = LOGGER.records.lock().unwrap();
+        assert!(records.contains(&(log::Level::Info, String::from("started 2 node(s)"))));
+        assert!(records
+            .iter()
+            .any(|(level, message)| *level == log::Level::Warn
+                && message.starts_with("node overran its update period")));
+        assert!(records.contains(&(
+            log::Level::Error,
+            String::from(
+                "node panicked during its update: Panicking Node panicked on its third update"
+            )
+        )));
+        assert!(records.contains(&(
+            log::Level::Info,
+            String::from("interrupt received, stopping executor")
+        )));
+    }
+
+    #[test]
+    fn test_update_for_ms_backward_clock_jump() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            SimpleExecutor::new_with(rx, vec![Box::new(CountingNode::new(0, 1_000))]);
+        // Jump behind the instant the executor was started shortly after starting
+        executor.set_clock(BackwardJumpClock {
+            clock: SystemClock::new(),
+            reads: AtomicU32::new(0),
+            jump_after: 20,
+            jump: Duration::from_millis(100),
+        });
+
+        let start = std::time::Instant::now();
+        executor.update_for_ms(20);
+        let elapsed = start.elapsed();
+        assert!(Duration::from_millis(20) <= elapsed);
+        assert!(elapsed < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_update_for_ms_manual_clock() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(CountingNode::new(0, 10_000)),
+                Box::new(CountingNode::new(1, 25_000)),
+            ],
+        );
+        let clock = ManualClock::new();
+        executor.set_clock(clock.clone());
+
+        // 10 simulated seconds should run without waiting on the wall clock
+        let start = std::time::Instant::now();
+        executor.update_for_ms(10_000);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(clock.now(), Duration::from_secs(10));
+
+        for node_wrapper in executor.backing.iter() {
+            let counting_node: &dyn Any = &node_wrapper.node;
+            let counting_node: &Box<CountingNode> =
+                unsafe { counting_node.downcast_ref_unchecked() };
+            if counting_node.id == 0 {
+                assert_eq!(counting_node.updates, 1_000);
+            } else {
+                assert_eq!(counting_node.updates, 400);
+            }
+        }
+    }
+
+    #[test]
+    fn test_loop_stats() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            SimpleExecutor::new_with(rx, vec![Box::new(CountingNode::new(0, 10_000))]);
+        executor.set_clock(ManualClock::new());
+        executor.update_for_ms(100);
+
+        let loop_stats = executor.loop_stats();
+        assert_eq!(loop_stats.dispatches, 10);
+        assert_eq!(loop_stats.mean_interval, Duration::from_millis(10));
+        assert_eq!(loop_stats.jitter, Duration::ZERO);
+        assert_eq!(loop_stats.waits, 10);
+        // Simulated clocks skip ahead instead of sleeping
+        assert_eq!(loop_stats.sleep_fraction(), 0.0);
+    }
+
+    #[test]
+    fn test_loop_stats_sleep() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            SimpleExecutor::new_with(rx, vec![Box::new(CountingNode::new(0, 10_000))]);
+        executor.set_sleep(true);
+        executor.update_for_ms(100);
+
+        let loop_stats = executor.loop_stats();
+        assert!([10, 11].contains(&loop_stats.dispatches));
+        assert!(Duration::from_millis(9) < loop_stats.mean_interval);
+        assert!(loop_stats.mean_interval < Duration::from_millis(11));
+        assert!(loop_stats.sleeps > 0);
+        assert!(loop_stats.sleep_fraction() > 0.0);
+    }
+
+    #[test]
+    fn test_update_for_ms_shutdown_reason() {
+        let (tx, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(ReasonNode {
+                id: 0,
+                reason: None,
+            })],
+        );
+
+        executor.update_for_ms(20);
+        let reason_node: &dyn Any = &executor.backing[0].node;
+        let reason_node: &Box<ReasonNode> = unsafe { reason_node.downcast_ref_unchecked() };
+        assert_eq!(reason_node.reason, Some(ShutdownReason::Completed));
+
+        let handle = thread::spawn(move || {
+            executor.update_for_ms(1_000);
+            executor
+        });
+        thread::sleep(Duration::from_millis(50));
+        tx.send(true).unwrap();
+
+        let executor = handle.join().unwrap();
+        let reason_node: &dyn Any = &executor.backing[0].node;
+        let reason_node: &Box<ReasonNode> = unsafe { reason_node.downcast_ref_unchecked() };
+        assert_eq!(reason_node.reason, Some(ShutdownReason::Interrupted));
+    }
+
+    #[test]
+    fn test_update_for_ms_shutdown_timeout() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(ReasonNode {
+                    id: 0,
+                    reason: None,
+                }),
+                Box::new(StuckNode { id: 1 }),
+            ],
+        );
+        executor.set_shutdown_timeout(Some(Duration::from_millis(100)));
+
+        let start = std::time::Instant::now();
+        executor.update_for_ms(20);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // The stuck node is abandoned while the other node is shut down normally
+        assert_eq!(executor.node_ids(), vec![0]);
+        let reason_node: &dyn Any = &executor.backing[0].node;
+        let reason_node: &Box<ReasonNode> = unsafe { reason_node.downcast_ref_unchecked() };
+        assert_eq!(reason_node.reason, Some(ShutdownReason::Completed));
+    }
+
+    #[test]
+    fn test_update_for_ms_shutdown_timeout_multiple_stuck_nodes() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(StuckNode { id: 0 }),
+                Box::new(StuckNode { id: 1 }),
+                Box::new(StuckNode { id: 2 }),
+                Box::new(StuckNode { id: 3 }),
+            ],
+        );
+        executor.set_shutdown_timeout(Some(Duration::from_millis(200)));
+
+        // All of the stuck nodes share one deadline instead of each waiting out
+        // the full timeout
+        let start = std::time::Instant::now();
+        executor.update_for_ms(20);
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(executor.node_ids().is_empty());
+    }
+
+    #[test]
+    fn test_update_for_ms_state_changes() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(rx, vec![Box::new(SimpleNode::new(0, 10_000))]);
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let recorded_states = states.clone();
+        executor.on_state_change(move |state| recorded_states.lock().unwrap().push(state));
+        assert_eq!(executor.state(), ExecutorState::Stopped);
+
+        executor.update_for_ms(20);
+        assert_eq!(executor.state(), ExecutorState::Stopped);
+        assert_eq!(
+            *states.lock().unwrap(),
+            vec![
+                ExecutorState::Started,
+                ExecutorState::Running,
+                ExecutorState::Stopped
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "realtime", target_os = "linux"))]
+    struct PolicyNode {
+        policy: Arc<Mutex<Option<i32>>>,
+    }
+
+    #[cfg(all(feature = "realtime", target_os = "linux"))]
+    impl Node<u8> for PolicyNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            1_000
+        }
+
+        fn update(&mut self) {
+            // SAFETY: sched_getscheduler(0) only queries the calling thread
+            *self.policy.lock().unwrap() = Some(unsafe { libc::sched_getscheduler(0) });
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "realtime", target_os = "linux"))]
+    fn test_update_loop_realtime_priority() {
+        // Skip the test when the process isn't allowed to use real-time policies
+        let privileged = thread::spawn(|| {
+            let param = libc::sched_param { sched_priority: 1 };
+            let result = unsafe {
+                libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param)
+            };
+            result == 0
+        })
+        .join()
+        .unwrap();
+
+        let (tx, rx) = unbounded();
+        let policy = Arc::new(Mutex::new(None));
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(PolicyNode {
+                policy: policy.clone(),
+            })],
+        );
+        executor.set_realtime_priority(10);
+        executor.set_sleep(true);
+
+        let handle = thread::spawn(move || executor.update_loop());
+        thread::sleep(Duration::from_millis(50));
+        tx.send(true).unwrap();
+        handle.join().unwrap();
+
+        // Without the privilege, the executor falls back to the default policy
+        let expected = if privileged {
+            libc::SCHED_FIFO
+        } else {
+            libc::SCHED_OTHER
+        };
+        assert_eq!(*policy.lock().unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn test_add_remove_node_group() {
+        let (_, rx) = unbounded();
+
+        let mut executor = SimpleExecutor::new_with(rx, vec![Box::new(SimpleNode::new(0, 10))]);
+        executor.add_node_group(
+            "arm",
+            vec![
+                Box::new(SimpleNode::new(1, 10)),
+                Box::new(SimpleNode::new(2, 10)),
+                Box::new(SimpleNode::new(3, 10)),
+            ],
+        );
+        assert_eq!(executor.node_ids().len(), 4);
+        assert_eq!(executor.group_node_ids("arm"), Some(&[1, 2, 3][..]));
+
+        let removed = executor.remove_group("arm");
+        assert_eq!(removed.len(), 3);
+        assert_eq!(executor.node_ids(), vec![0]);
+        assert_eq!(executor.group_node_ids("arm"), None);
+        assert!(executor.remove_group("arm").is_empty());
+    }
+
+    #[test]
+    fn test_update_for_ms_node_timestamps() {
+        let (_, rx) = unbounded();
+        let outputs = Arc::new(Mutex::new(Vec::new()));
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(TimestampingNode {
+                outputs: outputs.clone(),
+            })],
+        );
+        executor.set_clock(ManualClock::new());
+        executor.update_for_ms(50);
+
+        // The simulated clock skips ahead to each update so the timestamps match the
+        // node's schedule exactly
+        assert_eq!(
+            *outputs.lock().unwrap(),
+            vec![(0, 0), (1, 10_000), (2, 20_000), (3, 30_000), (4, 40_000)]
+        );
+    }
+
+    #[test]
+    fn test_update_for_cycles() {
+        let (_, rx) = unbounded();
+        let updates = Arc::new(AtomicU32::new(0));
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(SharedCountingNode {
+                updates: updates.clone(),
+            })],
+        );
+        // A long update delay would take seconds to reach 50 updates in real time
+        let start = std::time::Instant::now();
+        executor.update_for_cycles(50);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(updates.load(Ordering::Relaxed), 50);
+        assert_eq!(executor.state(), ExecutorState::Stopped);
+    }
+
+    #[test]
+    fn test_add_node_while_running() {
+        let (_, rx) = unbounded();
+        let updates = Arc::new(AtomicU32::new(0));
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(SharedCountingNode {
+                updates: updates.clone(),
+            })],
+        );
+        executor.start();
+        // The state the executor is in while an update loop is running
+        executor.set_state(ExecutorState::Running);
+
+        let result = executor.try_add_node(Box::new(SharedCountingNode {
+            updates: updates.clone(),
+        }));
+        assert_eq!(
+            result.err(),
+            Some(InvalidStateError(ExecutorState::Running))
+        );
+        assert!(matches!(
+            executor.try_remove_node(&0),
+            Err(InvalidStateError(ExecutorState::Running))
+        ));
+        // The rejected node must not have replaced the existing node with its id
+        assert_eq!(executor.node_ids(), vec![0]);
+
+        executor.set_state(ExecutorState::Started);
+        assert!(executor.try_remove_node(&0).unwrap().is_some());
+        assert!(executor
+            .try_add_node(Box::new(SharedCountingNode { updates }))
+            .is_ok());
+        assert_eq!(executor.node_ids(), vec![0]);
+    }
+
+    #[test]
+    fn test_update_for_ms_scheduling_modes() {
+        let run = |scheduling_mode| {
+            let (_, rx) = unbounded();
+            let clock = ManualClock::new();
+            let outputs = Arc::new(Mutex::new(Vec::new()));
+
+            let mut executor = SimpleExecutor::new_with(
+                rx,
+                vec![Box::new(SlowStartNode {
+                    clock: clock.clone(),
+                    outputs: outputs.clone(),
+                })],
+            );
+            executor.set_clock(clock);
+            executor.set_scheduling_mode(scheduling_mode);
+            executor.update_for_ms(50);
+
+            let outputs = outputs.lock().unwrap().clone();
+            outputs
+        };
+
+        // A fixed rate catches up on the late update to keep to the original schedule
+        assert_eq!(
+            run(SchedulingMode::FixedRate),
+            vec![0, 15_000, 20_000, 30_000, 40_000]
+        );
+        // A fixed delay schedules each update a full period after the previous one finished
+        assert_eq!(
+            run(SchedulingMode::FixedDelay),
+            vec![0, 25_000, 35_000, 45_000]
+        );
+    }
+
+    #[test]
+    fn test_update_for_cycles_panic_reset() {
+        let (_, rx) = unbounded();
+        let updates = Arc::new(AtomicU32::new(0));
+        let resets = Arc::new(AtomicU32::new(0));
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(ResettingNode {
+                updates: updates.clone(),
+                resets: resets.clone(),
+                panicked: false,
+            })],
+        );
+        executor.set_panic_policy(PanicPolicy::Restart);
+        executor.update_for_cycles(5);
+
+        // The node keeps its slot and every update after the reset succeeds
+        assert_eq!(resets.load(Ordering::Relaxed), 1);
+        assert_eq!(updates.load(Ordering::Relaxed), 4);
+        assert_eq!(executor.node_ids(), vec![0]);
+    }
+
+    /// A node reporting the topics it publishes and subscribes to
+    struct TopicNode {
+        id: u8,
+        name: &'static str,
+        published: &'static [&'static str],
+        subscribed: &'static [&'static str],
+    }
+
+    impl Node<u8> for TopicNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn published_topics(&self) -> &[&str] {
+            self.published
+        }
+
+        fn subscribed_topics(&self) -> &[&str] {
+            self.subscribed
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    #[test]
+    fn test_export_graph() {
+        let (_, rx) = unbounded();
+        let executor = SimpleExecutor::new_with(
+            rx,
+            vec![
+                Box::new(TopicNode {
+                    id: 0,
+                    name: "imu",
+                    published: &["orientation"],
+                    subscribed: &[],
+                }),
+                Box::new(TopicNode {
+                    id: 1,
+                    name: "controller",
+                    published: &["motor_commands"],
+                    subscribed: &["orientation"],
+                }),
+            ],
+        );
+
+        let graph = executor.export_graph();
+        assert!(graph.starts_with("digraph ncomm {"));
+        assert!(graph.trim_end().ends_with('}'));
+        assert!(graph.contains("node_0 [shape=box, label=\"imu (0)\"];"));
+        assert!(graph.contains("node_1 [shape=box, label=\"controller (1)\"];"));
+        // The shared topic is only declared once
+        assert_eq!(
+            graph
+                .matches("\"topic:orientation\" [shape=ellipse")
+                .count(),
+            1
+        );
+        assert!(graph.contains("node_0 -> \"topic:orientation\";"));
+        assert!(graph.contains("\"topic:orientation\" -> node_1;"));
+        assert!(graph.contains("node_1 -> \"topic:motor_commands\";"));
+    }
+
+    #[test]
+    fn test_update_for_ms_loop_hook() {
+        let (_, rx) = unbounded();
+        let updates = Arc::new(AtomicU32::new(0));
+        let iterations = Arc::new(AtomicU32::new(0));
+
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(SharedCountingNode {
+                updates: updates.clone(),
+            })],
+        );
+        let hook_iterations = iterations.clone();
+        executor.set_loop_hook(move || {
+            hook_iterations.fetch_add(1, Ordering::Relaxed);
+        });
+        executor.update_for_ms(250);
+
+        // Every node update happens in its own iteration of the loop
+        let updates = updates.load(Ordering::Relaxed);
+        assert_eq!(updates, 3);
+        assert!(iterations.load(Ordering::Relaxed) >= updates);
+    }
+
+    /// A node whose updates fail according to a fixed pattern
+    struct FlakyNode {
+        failures: [bool; 6],
+        updates: usize,
+    }
+
+    impl Node<u8> for FlakyNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn try_update(&mut self) -> Result<(), NodeError> {
+            let failed = self.failures[self.updates % self.failures.len()];
+            self.updates += 1;
+            if failed {
+                Err(Box::new(std::io::Error::other("intermittent fault")))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            1_000
+        }
+    }
+
+    #[test]
+    fn test_update_for_cycles_node_health() {
+        let (_, rx) = unbounded();
+        let mut executor = SimpleExecutor::new_with(
+            rx,
+            vec![Box::new(FlakyNode {
+                failures: [false, true, true, false, true, false],
+                updates: 0,
+            })],
+        );
+        assert_eq!(executor.node_health(&0), Some(&NodeHealth::default()));
+        assert_eq!(executor.node_health(&1), None);
+
+        executor.update_for_cycles(3);
+        let health = *executor.node_health(&0).unwrap();
+        assert_eq!(health.consecutive_errors, 2);
+        assert_eq!(health.total_errors, 2);
+        assert!(health.last_error_time.is_some());
+
+        // A successful update resets the consecutive errors but not the total
+        executor.update_for_cycles(1);
+        let health = *executor.node_health(&0).unwrap();
+        assert!(health.is_healthy());
+        assert_eq!(health.total_errors, 2);
+
+        executor.update_for_cycles(1);
+        let health = *executor.node_health(&0).unwrap();
+        assert_eq!(health.consecutive_errors, 1);
+        assert_eq!(health.total_errors, 3);
+        // The node is kept by the executor despite its errors
+        assert_eq!(executor.node_ids(), vec![0]);
+    }
+
+    #[test]
+    fn test_id_index() {
+        let nodes = || -> Vec<Box<dyn Node<u8>>> {
+            (0..200)
+                .map(|id| {
+                    Box::new(SimpleNode::new(id, u128::from(id % 7) * 10)) as Box<dyn Node<u8>>
+                })
+                .collect()
+        };
+        let (_, rx) = unbounded();
+        let mut executor = SimpleExecutor::new_with(rx, nodes());
+        executor.set_id_index(true);
+        let (_, rx) = unbounded();
+        let mut unindexed = SimpleExecutor::new_with(rx, nodes());
+
+        for id in 0..200 {
+            assert!(executor.node_health(&id).is_some());
+        }
+        for id in (0..200).step_by(2) {
+            assert_eq!(executor.remove_node(&id).unwrap().get_id(), id);
+            unindexed.remove_node(&id);
+        }
+        assert!(executor.remove_node(&0).is_none());
+
+        executor.update_for_cycles(1_000);
+        unindexed.update_for_cycles(1_000);
+        // The index doesn't change the order the nodes are scheduled in
+        assert_eq!(executor.node_ids(), unindexed.node_ids());
+
+        // Nodes added to a started executor are scheduled at different times
+        let clock = ManualClock::new();
+        executor.set_clock(clock.clone());
+        executor.start();
+        for id in 200..250 {
+            clock.advance(Duration::from_millis(1));
+            executor.add_node(Box::new(SimpleNode::new(id, 10)));
+        }
+        for id in (1..200).step_by(2).chain(200..250) {
+            assert!(executor.node_health(&id).is_some());
+        }
+        for id in (1..200).step_by(4).chain((200..250).step_by(2)) {
+            assert_eq!(executor.remove_node(&id).unwrap().get_id(), id);
+            assert!(executor.node_health(&id).is_none());
+        }
+        assert_eq!(executor.node_ids().len(), 75);
+
+        // The index isn't updated as nodes are rescheduled but lookups still work
+        for _ in 0..50 {
+            executor.update_next_node();
+        }
+        for id in (3..200).step_by(4).chain((201..250).step_by(2)) {
+            assert!(executor.node_health(&id).is_some());
+        }
+    }
 }
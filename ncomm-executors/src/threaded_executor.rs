@@ -8,7 +8,10 @@
 //! Threaded Executor may be the best choice.
 //!
 
-use std::thread;
+use std::{
+    thread::{self, JoinHandle},
+    time::Duration,
+};
 
 use quanta::{Clock, Instant};
 
@@ -16,7 +19,14 @@ use crossbeam::channel::{unbounded, Receiver, Sender};
 
 use ncomm_core::{Executor, ExecutorState, Node};
 
-use crate::{insert_into, NodeWrapper, SimpleExecutor};
+use crate::{
+    insert_into, log_deadline_missed, log_interrupt, log_nodes_started, log_shutdown_timeout,
+    log_update_error, missed_deadlines, shutdown_nodes, shutdown_reason, NodeGroups, NodeWrapper,
+    SimpleExecutor, StateCallback,
+};
+
+#[cfg(feature = "affinity")]
+use crate::log_thread_affinity_failed;
 
 /// Threaded Executor
 ///
@@ -39,9 +49,23 @@ pub struct ThreadedExecutor<NID: PartialEq + Send, TID: PartialEq + Send> {
     /// The interrupt receiver channel
     interrupt: Receiver<bool>,
     /// The interrupt senders used to propagate the interrupt to other threads
+    ///
+    /// Note: The propagators are kept in the same order as the executors.
     interrupt_propagators: Vec<Sender<bool>>,
+    /// The threads (and their thread ids) whose executors didn't stop within the
+    /// shutdown timeout
+    abandoned: Vec<(JoinHandle<SimpleExecutor<NID>>, TID)>,
     /// Whether or not the executor has been interrupted
     interrupted: bool,
+    /// How long a node's shutdown may take before the node is abandoned
+    shutdown_timeout: Option<Duration>,
+    /// The callback invoked whenever the executor changes state
+    on_state_change: Option<StateCallback>,
+    /// The ids of the nodes in each group of nodes
+    groups: NodeGroups<NID>,
+    /// The core each thread is pinned to (keyed by thread id)
+    #[cfg(feature = "affinity")]
+    thread_affinity: Vec<(TID, usize)>,
 }
 
 impl<NID: PartialEq + Send, TID: PartialEq + Send> ThreadedExecutor<NID, TID> {
@@ -59,7 +83,13 @@ impl<NID: PartialEq + Send, TID: PartialEq + Send> ThreadedExecutor<NID, TID> {
             start_instant: now,
             interrupt,
             interrupt_propagators: Vec::new(),
+            abandoned: Vec::new(),
             interrupted: false,
+            shutdown_timeout: None,
+            on_state_change: None,
+            groups: NodeGroups::default(),
+            #[cfg(feature = "affinity")]
+            thread_affinity: Vec::new(),
         }
     }
 
@@ -74,7 +104,7 @@ impl<NID: PartialEq + Send, TID: PartialEq + Send> ThreadedExecutor<NID, TID> {
         if let Some(idx) = nodes.iter().position(|(_, tid)| tid.eq(&main_thread_id)) {
             let (mut node_list, _) = nodes.remove(idx);
             for node in node_list.drain(..) {
-                backing.push(NodeWrapper { priority: 0, node });
+                backing.push(NodeWrapper::new(0, node));
             }
         }
 
@@ -98,7 +128,81 @@ impl<NID: PartialEq + Send, TID: PartialEq + Send> ThreadedExecutor<NID, TID> {
             state: ExecutorState::Stopped,
             interrupt,
             interrupt_propagators,
+            abandoned: Vec::new(),
             interrupted: false,
+            shutdown_timeout: None,
+            on_state_change: None,
+            groups: NodeGroups::default(),
+            #[cfg(feature = "affinity")]
+            thread_affinity: Vec::new(),
+        }
+    }
+
+    /// Set how long shutting down the nodes may take when the executor stops.
+    ///
+    /// With a timeout, nodes are shut down on a watchdog thread against a single
+    /// deadline and any node whose shutdown hasn't finished by the deadline is
+    /// abandoned (and removed from the executor) so the executor can still return.
+    /// The timeout is also given to the executors on the other threads and, once this
+    /// thread's nodes have been shut down, the other threads are waited on until a
+    /// shared deadline after which any unfinished thread is abandoned.  By default,
+    /// there is no timeout.
+    pub fn set_shutdown_timeout(&mut self, shutdown_timeout: Option<Duration>) {
+        self.shutdown_timeout = shutdown_timeout;
+    }
+
+    /// Take the threads (and their thread ids) whose executors didn't stop within
+    /// the shutdown timeout.
+    ///
+    /// Note: An abandoned executor and its nodes are no longer part of this executor
+    /// (and no longer receive interrupts).  They can be recovered by joining the
+    /// thread once the stuck node's shutdown returns.
+    pub fn take_abandoned(&mut self) -> Vec<(JoinHandle<SimpleExecutor<NID>>, TID)> {
+        core::mem::take(&mut self.abandoned)
+    }
+
+    /// Pin the thread with a given thread id to the core with a given id.
+    ///
+    /// Note: The pinning is applied when the thread is spawned (by `start`,
+    /// `update_for_ms`, or `update_loop`) so the main thread is never pinned.  If the
+    /// thread can't be pinned (i.e. the core doesn't exist or isn't available to the
+    /// process), a warning is logged and the thread runs without affinity.
+    #[cfg(feature = "affinity")]
+    pub fn set_thread_affinity(&mut self, thread_id: TID, core: usize) {
+        if let Some(entry) = self
+            .thread_affinity
+            .iter_mut()
+            .find(|(tid, _)| tid.eq(&thread_id))
+        {
+            entry.1 = core;
+        } else {
+            self.thread_affinity.push((thread_id, core));
+        }
+    }
+
+    /// Get the core the thread with a given thread id should be pinned to
+    #[cfg(feature = "affinity")]
+    fn core_for(&self, thread_id: &TID) -> Option<usize> {
+        self.thread_affinity
+            .iter()
+            .find(|(tid, _)| tid.eq(thread_id))
+            .map(|(_, core)| *core)
+    }
+
+    /// Register a callback that is invoked with the new state of the executor
+    /// every time the executor changes state.
+    pub fn on_state_change(&mut self, callback: impl FnMut(ExecutorState) + Send + 'static) {
+        self.on_state_change = Some(Box::new(callback));
+    }
+
+    /// Transition the executor into a given state, notifying the state change
+    /// callback if the state changed.
+    fn set_state(&mut self, state: ExecutorState) {
+        if self.state != state {
+            self.state = state;
+            if let Some(callback) = self.on_state_change.as_mut() {
+                callback(state);
+            }
         }
     }
 
@@ -107,13 +211,117 @@ impl<NID: PartialEq + Send, TID: PartialEq + Send> ThreadedExecutor<NID, TID> {
             node_wrapper.priority = 0;
             node_wrapper.node.start();
         }
+        log_nodes_started(self.backing.len());
 
         self.interrupted = false;
-        self.state = ExecutorState::Started;
+        self.set_state(ExecutorState::Started);
         self.start_instant = self.clock.now();
     }
 }
 
+impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> ThreadedExecutor<NID, TID> {
+    /// Creates a new Threaded Executor that distributes the given nodes round-robin
+    /// across the given thread ids.
+    ///
+    /// Note: If `thread_ids` contains the main thread id, the nodes assigned to it
+    /// are executed on the main thread.  If `thread_ids` is empty, every node is
+    /// executed on the main thread.
+    pub fn new_balanced(
+        interrupt: Receiver<bool>,
+        main_thread_id: TID,
+        thread_ids: Vec<TID>,
+        nodes: Vec<Box<dyn Node<NID>>>,
+    ) -> Self {
+        let mut threads: Vec<(Vec<_>, TID)> = thread_ids
+            .into_iter()
+            .map(|thread_id| (Vec::new(), thread_id))
+            .collect();
+        if threads.is_empty() {
+            let mut executor = Self::new(interrupt, main_thread_id);
+            for node in nodes {
+                executor.add_node(node);
+            }
+            return executor;
+        }
+
+        let thread_count = threads.len();
+        for (idx, node) in nodes.into_iter().enumerate() {
+            threads[idx % thread_count].0.push(node);
+        }
+        Self::new_with(interrupt, main_thread_id, threads)
+    }
+
+    /// Wait for the executors on the other threads to stop, returning them to the
+    /// list of executors.
+    ///
+    /// If there is a shutdown timeout, any executor that hasn't stopped within the
+    /// timeout is abandoned (see `take_abandoned`) and its interrupt propagator is
+    /// unregistered.
+    ///
+    /// Note: `handles` must be in the same order as the interrupt propagators.
+    fn join_executors(&mut self, handles: Vec<(JoinHandle<SimpleExecutor<NID>>, TID)>) {
+        let deadline = self
+            .shutdown_timeout
+            .map(|timeout| self.clock.now() + timeout);
+        let mut propagator_idx = 0;
+        for (handle, tid) in handles {
+            if let Some(deadline) = deadline {
+                while !handle.is_finished() && self.clock.now() < deadline {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                if !handle.is_finished() {
+                    log_shutdown_timeout();
+                    self.interrupt_propagators.remove(propagator_idx);
+                    self.abandoned.push((handle, tid));
+                    continue;
+                }
+            }
+            self.executors.push((handle.join().unwrap(), tid));
+            propagator_idx += 1;
+        }
+    }
+
+    /// Move a node from whichever thread it is currently on to the thread with
+    /// a given thread id, creating an executor for the thread if one does not
+    /// exist yet.
+    ///
+    /// Note: the node itself is moved (not recreated), so any state it holds is
+    /// kept.  If no node has the given id, nothing happens.
+    pub fn move_node(&mut self, id: &NID, to: TID) {
+        if let Some(node) = self.remove_node(id) {
+            self.add_node_with_context(node, to);
+        }
+    }
+}
+
+impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> ThreadedExecutor<NID, TID> {
+    /// Add a group of nodes to the Threaded Executor that can be removed together via
+    /// `remove_group`.
+    ///
+    /// Note: Adding nodes to an existing group adds the nodes to the group.
+    pub fn add_node_group(&mut self, group_id: impl Into<String>, nodes: Vec<Box<dyn Node<NID>>>) {
+        let ids: Vec<NID> = nodes.iter().map(|node| node.get_id()).collect();
+        for node in nodes {
+            self.add_node(node);
+        }
+        self.groups.insert(group_id.into(), ids);
+    }
+
+    /// Remove every node in a group from the Threaded Executor, returning the removed nodes.
+    pub fn remove_group(&mut self, group_id: &str) -> Vec<Box<dyn Node<NID>>> {
+        self.groups
+            .remove(group_id)
+            .iter()
+            .filter_map(|id| self.remove_node(id))
+            .collect()
+    }
+
+    /// Get the ids of the nodes in a group
+    pub fn group_node_ids(&self, group_id: &str) -> Option<&[NID]> {
+        self.groups.get(group_id)
+    }
+}
+
 impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> Executor<NID>
     for ThreadedExecutor<NID, TID>
 {
@@ -121,35 +329,50 @@ impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> Executor<
 
     fn start(&mut self) {
         let mut handles = Vec::new();
-        for (mut executor, tid) in self.executors.drain(..) {
-            handles.push(thread::spawn(move || {
-                executor.start();
-                (executor, tid)
-            }));
+        for (mut executor, tid) in core::mem::take(&mut self.executors) {
+            #[cfg(feature = "affinity")]
+            let core = self.core_for(&tid);
+            handles.push((
+                thread::spawn(move || {
+                    #[cfg(feature = "affinity")]
+                    pin_to_core(core);
+                    executor.start();
+                    executor
+                }),
+                tid,
+            ));
         }
 
         self.start_self();
 
-        for handle in handles {
-            self.executors.push(handle.join().unwrap());
+        for (handle, tid) in handles {
+            self.executors.push((handle.join().unwrap(), tid));
         }
     }
 
     fn update_for_ms(&mut self, ms: u128) {
         // Dispatch the other threads
         let mut handles = Vec::new();
-        for (mut executor, tid) in self.executors.drain(..) {
-            handles.push(thread::spawn(move || {
-                executor.update_for_ms(ms);
-                (executor, tid)
-            }));
+        for (mut executor, tid) in core::mem::take(&mut self.executors) {
+            executor.set_shutdown_timeout(self.shutdown_timeout);
+            #[cfg(feature = "affinity")]
+            let core = self.core_for(&tid);
+            handles.push((
+                thread::spawn(move || {
+                    #[cfg(feature = "affinity")]
+                    pin_to_core(core);
+                    executor.update_for_ms(ms);
+                    executor
+                }),
+                tid,
+            ));
         }
 
         // Start this exector
         self.start_self();
 
         // Run this executor
-        self.state = ExecutorState::Running;
+        self.set_state(ExecutorState::Running);
         while self
             .clock
             .now()
@@ -167,39 +390,59 @@ impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> Executor<
                     >= self.backing.last().unwrap().priority
             {
                 let mut node_wrapper = self.backing.pop().unwrap();
-                node_wrapper.node.update();
+                let now = self
+                    .clock
+                    .now()
+                    .duration_since(self.start_instant)
+                    .as_micros();
+                let missed = missed_deadlines(
+                    now,
+                    node_wrapper.priority,
+                    node_wrapper.node.get_update_delay_us(),
+                );
+                if missed > 0 {
+                    node_wrapper.node.on_deadline_missed(missed);
+                    log_deadline_missed(missed);
+                }
+                if let Err(error) = node_wrapper.node.try_update_at(now) {
+                    log_update_error(&error);
+                }
                 node_wrapper.priority += node_wrapper.node.get_update_delay_us();
                 insert_into(&mut self.backing, node_wrapper);
             }
         }
 
         // Stop the Executor
-        for node_wrapper in self.backing.iter_mut() {
-            node_wrapper.priority = 0;
-            node_wrapper.node.shutdown();
-        }
-        self.state = ExecutorState::Stopped;
+        let reason = shutdown_reason(self.interrupted);
+        shutdown_nodes(&mut self.backing, reason, self.shutdown_timeout);
+        self.set_state(ExecutorState::Stopped);
 
-        for handle in handles {
-            self.executors.push(handle.join().unwrap());
-        }
+        self.join_executors(handles);
     }
 
     fn update_loop(&mut self) {
         // Dispatch the other threads
         let mut handles = Vec::new();
-        for (mut executor, tid) in self.executors.drain(..) {
-            handles.push(thread::spawn(move || {
-                executor.update_loop();
-                (executor, tid)
-            }));
+        for (mut executor, tid) in core::mem::take(&mut self.executors) {
+            executor.set_shutdown_timeout(self.shutdown_timeout);
+            #[cfg(feature = "affinity")]
+            let core = self.core_for(&tid);
+            handles.push((
+                thread::spawn(move || {
+                    #[cfg(feature = "affinity")]
+                    pin_to_core(core);
+                    executor.update_loop();
+                    executor
+                }),
+                tid,
+            ));
         }
 
         // Start this executor
         self.start_self();
 
         // Run the executor
-        self.state = ExecutorState::Running;
+        self.set_state(ExecutorState::Running);
         while !self.check_interrupt() {
             if self.backing.last().is_some()
                 && self
@@ -210,27 +453,47 @@ impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> Executor<
                     >= self.backing.last().unwrap().priority
             {
                 let mut node_wrapper = self.backing.pop().unwrap();
-                node_wrapper.node.update();
+                let now = self
+                    .clock
+                    .now()
+                    .duration_since(self.start_instant)
+                    .as_micros();
+                let missed = missed_deadlines(
+                    now,
+                    node_wrapper.priority,
+                    node_wrapper.node.get_update_delay_us(),
+                );
+                if missed > 0 {
+                    node_wrapper.node.on_deadline_missed(missed);
+                    log_deadline_missed(missed);
+                }
+                if let Err(error) = node_wrapper.node.try_update_at(now) {
+                    log_update_error(&error);
+                }
                 node_wrapper.priority += node_wrapper.node.get_update_delay_us();
                 insert_into(&mut self.backing, node_wrapper);
             }
         }
 
         // Stop this executor
-        for node_wrapper in self.backing.iter_mut() {
-            node_wrapper.priority = 0;
-            node_wrapper.node.shutdown();
-        }
-        self.state = ExecutorState::Stopped;
+        let reason = shutdown_reason(self.interrupted);
+        shutdown_nodes(&mut self.backing, reason, self.shutdown_timeout);
+        self.set_state(ExecutorState::Stopped);
 
-        for handle in handles {
-            self.executors.push(handle.join().unwrap());
-        }
+        self.join_executors(handles);
+    }
+
+    /// Get the current state of the executor
+    fn state(&self) -> ExecutorState {
+        self.state
     }
 
     fn check_interrupt(&mut self) -> bool {
         if let Ok(interrupt) = self.interrupt.try_recv() {
             self.interrupted = interrupt;
+            if interrupt {
+                log_interrupt();
+            }
             for tx in self.interrupt_propagators.iter_mut() {
                 tx.send(interrupt).unwrap();
             }
@@ -249,18 +512,17 @@ impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> Executor<
         }
 
         if self.state == ExecutorState::Stopped {
-            self.backing.push(NodeWrapper { priority: 0, node });
+            self.backing.push(NodeWrapper::new(0, node));
         } else if self.state == ExecutorState::Started {
             insert_into(
                 &mut self.backing,
-                NodeWrapper {
-                    priority: self
-                        .clock
+                NodeWrapper::new(
+                    self.clock
                         .now()
                         .duration_since(self.start_instant)
                         .as_micros(),
                     node,
-                },
+                ),
             );
         }
     }
@@ -301,10 +563,40 @@ impl<NID: PartialEq + Send + 'static, TID: PartialEq + Send + 'static> Executor<
 
         if let Some(idx) = delete_executor {
             self.executors.remove(idx);
+            self.interrupt_propagators.remove(idx);
         }
 
         found_node
     }
+
+    /// Get the ids of the nodes in the main thread's backing and in each of the
+    /// sub-executors.
+    fn node_ids(&self) -> Vec<NID> {
+        self.backing
+            .iter()
+            .chain(
+                self.executors
+                    .iter()
+                    .flat_map(|(executor, _)| executor.backing.iter()),
+            )
+            .map(|node_wrapper| node_wrapper.node.get_id())
+            .collect()
+    }
+}
+
+/// Pin the calling thread to a given core (if there is one)
+#[cfg(feature = "affinity")]
+fn pin_to_core(core: Option<usize>) {
+    let Some(core) = core else {
+        return;
+    };
+
+    let pinned = core_affinity::get_core_ids()
+        .and_then(|core_ids| core_ids.into_iter().find(|core_id| core_id.id == core))
+        .is_some_and(core_affinity::set_for_current);
+    if !pinned {
+        log_thread_affinity_failed(core);
+    }
 }
 
 #[cfg(test)]
@@ -313,6 +605,11 @@ mod tests {
 
     use std::{any::Any, time::Duration};
 
+    #[cfg(all(feature = "affinity", target_os = "linux"))]
+    use std::sync::{Arc, Mutex};
+
+    use ncomm_core::ShutdownReason;
+
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     enum State {
         Stopped,
@@ -360,6 +657,43 @@ mod tests {
         }
     }
 
+    pub struct ReasonNode {
+        id: u8,
+        pub reason: Option<ShutdownReason>,
+    }
+
+    impl Node<u8> for ReasonNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn shutdown_with_reason(&mut self, reason: ShutdownReason) {
+            self.reason = Some(reason);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    pub struct StuckNode {
+        id: u8,
+    }
+
+    impl Node<u8> for StuckNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn shutdown(&mut self) {
+            thread::sleep(Duration::from_secs(5));
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
     #[test]
     fn test_start() {
         let (_, rx) = unbounded();
@@ -648,4 +982,301 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_node_ids() {
+        let (_, rx) = unbounded();
+
+        let mut executor = ThreadedExecutor::new_with(
+            rx,
+            0,
+            vec![
+                (vec![Box::new(SimpleNode::new(0, 10_000))], 0),
+                (vec![Box::new(SimpleNode::new(1, 100_000))], 1),
+                (vec![Box::new(SimpleNode::new(2, 110_000))], 2),
+            ],
+        );
+        executor.add_node_with_context(Box::new(SimpleNode::new(3, 10_000)), 1);
+
+        let mut ids = executor.node_ids();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+        assert!(executor.contains_node(&3));
+        assert!(!executor.contains_node(&4));
+
+        executor.remove_node(&2);
+        assert!(!executor.contains_node(&2));
+    }
+
+    #[test]
+    fn test_move_node() {
+        let (_, rx) = unbounded();
+
+        let mut executor = ThreadedExecutor::new_with(
+            rx,
+            0,
+            vec![
+                (vec![Box::new(SimpleNode::new(0, 10_000))], 0),
+                (
+                    vec![
+                        Box::new(SimpleNode::new(1, 100_000)),
+                        Box::new(SimpleNode::new(2, 110_000)),
+                    ],
+                    1,
+                ),
+            ],
+        );
+        let thread_ids = |executor: &ThreadedExecutor<u8, u8>, tid: u8| -> Vec<u8> {
+            executor
+                .executors
+                .iter()
+                .filter(|(_, thread_id)| thread_id.eq(&tid))
+                .flat_map(|(executor, _)| executor.backing.iter())
+                .map(|node_wrapper| node_wrapper.node.get_id())
+                .collect()
+        };
+
+        // Move from a sub-executor to a new thread
+        executor.move_node(&1, 2);
+        assert_eq!(thread_ids(&executor, 1), vec![2]);
+        assert_eq!(thread_ids(&executor, 2), vec![1]);
+
+        // Move from the main thread to an existing thread
+        executor.move_node(&0, 1);
+        assert!(executor.backing.is_empty());
+        let mut ids = thread_ids(&executor, 1);
+        ids.sort();
+        assert_eq!(ids, vec![0, 2]);
+
+        // Move from a sub-executor to the main thread
+        executor.move_node(&1, 0);
+        assert_eq!(executor.backing[0].node.get_id(), 1);
+        assert!(thread_ids(&executor, 2).is_empty());
+
+        // Moving a missing node does nothing
+        executor.move_node(&5, 3);
+        assert_eq!(executor.node_ids().len(), 3);
+    }
+
+    #[test]
+    fn test_update_loop_interrupted_shutdown_reason() {
+        let (tx, rx) = unbounded();
+
+        let mut executor = ThreadedExecutor::new_with(
+            rx,
+            0,
+            vec![
+                (
+                    vec![Box::new(ReasonNode {
+                        id: 0,
+                        reason: None,
+                    })],
+                    0,
+                ),
+                (
+                    vec![Box::new(ReasonNode {
+                        id: 1,
+                        reason: None,
+                    })],
+                    1,
+                ),
+            ],
+        );
+
+        let handle = thread::spawn(move || {
+            executor.update_loop();
+            executor
+        });
+        thread::sleep(Duration::from_millis(50));
+        tx.send(true).unwrap();
+
+        let executor = handle.join().unwrap();
+        let node_wrappers = executor.backing.iter().chain(
+            executor
+                .executors
+                .iter()
+                .flat_map(|(executor, _)| executor.backing.iter()),
+        );
+        let mut count = 0;
+        for node_wrapper in node_wrappers {
+            let reason_node: &dyn Any = &node_wrapper.node;
+            let reason_node: &Box<ReasonNode> = unsafe { reason_node.downcast_ref_unchecked() };
+            assert_eq!(reason_node.reason, Some(ShutdownReason::Interrupted));
+            count += 1;
+        }
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_update_for_ms_shutdown_timeout() {
+        let (_, rx) = unbounded();
+
+        let mut executor = ThreadedExecutor::new_with(
+            rx,
+            0,
+            vec![
+                (vec![Box::new(StuckNode { id: 0 })], 0),
+                (
+                    vec![
+                        Box::new(SimpleNode::new(1, 10_000)),
+                        Box::new(StuckNode { id: 2 }),
+                    ],
+                    1,
+                ),
+            ],
+        );
+        executor.set_shutdown_timeout(Some(Duration::from_millis(100)));
+
+        let start = std::time::Instant::now();
+        executor.update_for_ms(20);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(executor.node_ids(), vec![1]);
+    }
+
+    #[test]
+    fn test_update_for_ms_abandoned_executor() {
+        let (tx, rx) = unbounded();
+
+        let mut executor = ThreadedExecutor::new_with(
+            rx,
+            0,
+            vec![
+                (vec![Box::new(SimpleNode::new(0, 10_000))], 0),
+                (
+                    vec![
+                        Box::new(SimpleNode::new(1, 10_000)),
+                        Box::new(StuckNode { id: 2 }),
+                        Box::new(StuckNode { id: 3 }),
+                        Box::new(StuckNode { id: 4 }),
+                        Box::new(StuckNode { id: 5 }),
+                    ],
+                    1,
+                ),
+                (vec![Box::new(SimpleNode::new(6, 10_000))], 2),
+            ],
+        );
+        executor.set_shutdown_timeout(Some(Duration::from_millis(100)));
+
+        let start = std::time::Instant::now();
+        executor.update_for_ms(20);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(executor.node_ids(), vec![0, 6]);
+        assert_eq!(
+            executor.interrupt_propagators.len(),
+            executor.executors.len()
+        );
+
+        // Interrupts are only propagated to the executors that are still part of
+        // the executor
+        tx.send(true).unwrap();
+        assert!(executor.check_interrupt());
+
+        let mut abandoned = executor.take_abandoned();
+        assert_eq!(abandoned.len(), 1);
+        let (handle, tid) = abandoned.remove(0);
+        assert_eq!(tid, 1);
+        let sub_executor = handle.join().unwrap();
+        assert_eq!(sub_executor.node_ids(), vec![1]);
+        assert!(executor.take_abandoned().is_empty());
+    }
+
+    #[test]
+    fn test_new_balanced() {
+        let (_, rx) = unbounded();
+
+        let nodes: Vec<Box<dyn Node<u8>>> = (0..8)
+            .map(|id| Box::new(SimpleNode::new(id, 10_000)) as Box<dyn Node<u8>>)
+            .collect();
+        let executor = ThreadedExecutor::new_balanced(rx, 0, vec![1, 2, 3], nodes);
+
+        assert!(executor.backing.is_empty());
+        assert_eq!(executor.executors.len(), 3);
+        for (executor, _) in executor.executors.iter() {
+            let count = executor.backing.len();
+            assert!(count == 2 || count == 3);
+        }
+        let mut ids = executor.node_ids();
+        ids.sort();
+        assert_eq!(ids, (0..8).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_new_balanced_main_thread() {
+        let (_, rx) = unbounded();
+
+        let nodes: Vec<Box<dyn Node<u8>>> = vec![
+            Box::new(SimpleNode::new(0, 10_000)),
+            Box::new(SimpleNode::new(1, 10_000)),
+        ];
+        let executor = ThreadedExecutor::new_balanced(rx, 0, Vec::new(), nodes);
+
+        assert_eq!(executor.backing.len(), 2);
+        assert!(executor.executors.is_empty());
+    }
+
+    #[cfg(all(feature = "affinity", target_os = "linux"))]
+    struct AffinityNode {
+        id: u8,
+        cpus_allowed: Arc<Mutex<Option<String>>>,
+    }
+
+    #[cfg(all(feature = "affinity", target_os = "linux"))]
+    impl Node<u8> for AffinityNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            let status = std::fs::read_to_string("/proc/thread-self/status").unwrap();
+            *self.cpus_allowed.lock().unwrap() = status
+                .lines()
+                .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+                .map(|list| list.trim().to_string());
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "affinity", target_os = "linux"))]
+    fn test_update_for_ms_thread_affinity() {
+        let (_, rx) = unbounded();
+        let core_ids = core_affinity::get_core_ids().unwrap();
+        let core = core_ids.last().unwrap().id;
+
+        let pinned = Arc::new(Mutex::new(None));
+        let unpinned = Arc::new(Mutex::new(None));
+        let mut executor = ThreadedExecutor::new_with(
+            rx,
+            0,
+            vec![
+                (
+                    vec![Box::new(AffinityNode {
+                        id: 0,
+                        cpus_allowed: pinned.clone(),
+                    })],
+                    1,
+                ),
+                (
+                    vec![Box::new(AffinityNode {
+                        id: 1,
+                        cpus_allowed: unpinned.clone(),
+                    })],
+                    2,
+                ),
+            ],
+        );
+        executor.set_thread_affinity(1, core);
+        // A core that doesn't exist falls back to running without affinity
+        executor.set_thread_affinity(2, usize::MAX);
+        executor.update_for_ms(20);
+
+        assert_eq!(*pinned.lock().unwrap(), Some(core.to_string()));
+        assert!(unpinned.lock().unwrap().is_some());
+        if core_ids.len() > 1 {
+            assert_ne!(*unpinned.lock().unwrap(), Some(core.to_string()));
+        }
+    }
 }
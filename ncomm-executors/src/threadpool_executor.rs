@@ -3,17 +3,33 @@
 //! nodes to be run on a threadpool
 //!
 
-use std::{any::Any, cmp::max};
+use std::{any::Any, cmp::max, time::Duration};
 
 use quanta::{Clock, Instant};
 
 use threadpool::ThreadPool;
 
-use crossbeam::channel::{unbounded, Receiver};
+use crossbeam::channel::{unbounded, Receiver, Sender};
 
 use ncomm_core::{Executor, ExecutorState, Node};
 
-use crate::{insert_into, NodeWrapper};
+use crate::{
+    log_deadline_missed, log_interrupt, log_nodes_started, log_update_error, missed_deadlines,
+    shutdown_nodes, shutdown_reason, NodeGroups, NodeHealth, NodeWrapper, StateCallback,
+};
+
+#[cfg(feature = "metrics")]
+use crate::{metrics::refresh_metrics, NodeMetrics};
+#[cfg(feature = "metrics")]
+use std::{collections::HashMap, hash::Hash};
+
+/// The time (in microseconds) before a node's next update that a sleeping
+/// ThreadPool Executor will wake up and busy wait for precision.
+const SLEEP_SPIN_MARGIN_US: u128 = 1_000;
+
+/// The longest time (in microseconds) a sleeping ThreadPool Executor will sleep
+/// for before checking for an interrupt.
+const MAX_SLEEP_US: u128 = 10_000;
 
 /// ThreadPool Executor
 ///
@@ -27,6 +43,20 @@ use crate::{insert_into, NodeWrapper};
 /// Addendum: The main thread of the ThreadPool is conducting the scheduling so
 /// the ThreadPool will only have n-1 worker threads where n is the total number
 /// of threads allocated to the threadpool executor.
+///
+/// Nodes are only handed to the ThreadPool when one of its workers is idle.  When
+/// multiple nodes are due at the same time, nodes with a higher priority class
+/// (see `add_node_with_priority`) are dispatched first so a saturated pool slips
+/// its low priority nodes before its high priority ones.
+///
+/// By default, a node that falls behind is updated back-to-back until it catches
+/// up with its schedule.  With `set_drop_stale(true)`, a node that is overdue by
+/// at least one full update period instead skips the missed updates so an
+/// overloaded executor stays real-time at the cost of skipped cycles.
+///
+/// Like the Simple Executor, the scheduler busy waits between node updates by
+/// default.  With `set_sleep(true)`, the scheduler instead sleeps until shortly
+/// before the next node is due (or a worker returns an updated node).
 pub struct ThreadPoolExecutor<ID: PartialEq> {
     /// The sorted backing vector for the executor
     backing: Vec<NodeWrapper<ID>>,
@@ -42,6 +72,25 @@ pub struct ThreadPoolExecutor<ID: PartialEq> {
     interrupt: Receiver<bool>,
     /// Whether or not the executor has been interrupted
     interrupted: bool,
+    /// How long a node's shutdown may take before the node is abandoned
+    shutdown_timeout: Option<Duration>,
+    /// The callback invoked whenever the executor changes state
+    on_state_change: Option<StateCallback>,
+    /// The ids of the nodes in each group of nodes
+    groups: NodeGroups<ID>,
+    /// Whether nodes that are overdue by at least one update period skip their
+    /// missed updates
+    drop_stale: bool,
+    /// The sequence number given to the next node added to the executor
+    next_sequence: u64,
+    /// Whether the scheduler should sleep (instead of busy waiting) until
+    /// shortly before the next node update
+    sleep: bool,
+    /// The number of nodes currently being updated by the ThreadPool
+    in_flight: usize,
+    /// The execution metrics of every node keyed by id (refreshed by `metrics`)
+    #[cfg(feature = "metrics")]
+    metrics: HashMap<ID, NodeMetrics>,
 }
 
 impl<ID: PartialEq> ThreadPoolExecutor<ID> {
@@ -59,6 +108,15 @@ impl<ID: PartialEq> ThreadPoolExecutor<ID> {
             start_instant: now,
             interrupt,
             interrupted: false,
+            shutdown_timeout: None,
+            on_state_change: None,
+            groups: NodeGroups::default(),
+            drop_stale: false,
+            next_sequence: 0,
+            sleep: false,
+            in_flight: 0,
+            #[cfg(feature = "metrics")]
+            metrics: HashMap::new(),
         }
     }
 
@@ -69,9 +127,12 @@ impl<ID: PartialEq> ThreadPoolExecutor<ID> {
         mut nodes: Vec<Box<dyn Node<ID>>>,
     ) -> Self {
         let mut backing = Vec::new();
-        for node in nodes.drain(..) {
-            backing.push(NodeWrapper { priority: 0, node });
+        for (sequence, node) in nodes.drain(..).enumerate() {
+            let mut node_wrapper = NodeWrapper::new(0, node);
+            node_wrapper.sequence = sequence as u64;
+            insert_in_order(&mut backing, node_wrapper);
         }
+        let next_sequence = backing.len() as u64;
 
         let clock = Clock::new();
         let now = clock.now();
@@ -85,8 +146,326 @@ impl<ID: PartialEq> ThreadPoolExecutor<ID> {
             start_instant: now,
             interrupt,
             interrupted: false,
+            shutdown_timeout: None,
+            on_state_change: None,
+            groups: NodeGroups::default(),
+            drop_stale: false,
+            next_sequence,
+            sleep: false,
+            in_flight: 0,
+            #[cfg(feature = "metrics")]
+            metrics: HashMap::new(),
+        }
+    }
+
+    /// Set how long shutting down the nodes may take when the executor stops.
+    ///
+    /// With a timeout, nodes are shut down on a watchdog thread against a single
+    /// deadline and any node whose shutdown hasn't finished by the deadline is
+    /// abandoned (and removed from the executor) so the executor can still return.
+    /// By default, there is no timeout.
+    pub fn set_shutdown_timeout(&mut self, shutdown_timeout: Option<Duration>) {
+        self.shutdown_timeout = shutdown_timeout;
+    }
+
+    /// Set whether nodes that are overdue by at least one full update period skip
+    /// their missed updates.
+    ///
+    /// When enabled, an overdue node is rescheduled relative to the current time
+    /// (after being notified of the missed updates via `on_deadline_missed`) instead
+    /// of being updated back-to-back until it catches up.  By default, overdue nodes
+    /// are not skipped.
+    pub fn set_drop_stale(&mut self, drop_stale: bool) {
+        self.drop_stale = drop_stale;
+    }
+
+    /// Set whether the scheduler should sleep instead of busy waiting.
+    ///
+    /// When enabled, the scheduler waits for a worker to return an updated node
+    /// until just before the next node update and then busy waits for the remaining
+    /// time, freeing up the scheduler's core.  While every worker is busy, the
+    /// scheduler waits for a worker to return its node.  When disabled (the default), the
+    /// scheduler busy waits for the lowest possible jitter.
+    ///
+    /// Note: While sleeping, an interrupt is only noticed once the scheduler wakes
+    /// up (at most 10 milliseconds later).
+    pub fn set_sleep(&mut self, sleep: bool) {
+        self.sleep = sleep;
+    }
+
+    /// Register a callback that is invoked with the new state of the executor
+    /// every time the executor changes state.
+    pub fn on_state_change(&mut self, callback: impl FnMut(ExecutorState) + Send + 'static) {
+        self.on_state_change = Some(Box::new(callback));
+    }
+
+    /// Transition the executor into a given state, notifying the state change
+    /// callback if the state changed.
+    fn set_state(&mut self, state: ExecutorState) {
+        if self.state != state {
+            self.state = state;
+            if let Some(callback) = self.on_state_change.as_mut() {
+                callback(state);
+            }
+        }
+    }
+}
+
+impl<ID: PartialEq + 'static> ThreadPoolExecutor<ID> {
+    /// Add a node to the ThreadPool Executor with a given priority class.
+    ///
+    /// When multiple nodes are due to be updated at the same time, nodes with a
+    /// higher priority class are dispatched to the ThreadPool first.  Nodes added
+    /// via `add_node` have a priority class of 0.
+    ///
+    /// Note: Nodes can only be added to the executor when it is not running.
+    ///
+    /// Additionally, only 1 node can exist per id so additional nodes added with the same
+    /// id will replace the previous node of a given id
+    pub fn add_node_with_priority(&mut self, node: Box<dyn Node<ID>>, priority_class: u8) {
+        // Check the state before replacing the node with the same id so a rejected
+        // node doesn't remove the existing node
+        let priority = match self.state {
+            ExecutorState::Stopped => 0,
+            ExecutorState::Started => self
+                .clock
+                .now()
+                .duration_since(self.start_instant)
+                .as_micros(),
+            _ => return,
+        };
+
+        if let Some(idx) = self
+            .backing
+            .iter()
+            .position(|node_wrapper| node_wrapper.node.get_id().eq(&node.get_id()))
+        {
+            self.backing.remove(idx);
+        }
+
+        let mut node_wrapper = NodeWrapper::new(priority, node);
+        node_wrapper.priority_class = priority_class;
+        node_wrapper.sequence = self.next_sequence;
+        self.next_sequence += 1;
+        insert_in_order(&mut self.backing, node_wrapper);
+    }
+
+    /// Dispatch the nodes that are due to be updated to the idle workers of the
+    /// ThreadPool, highest priority class first (ties are broken by the earliest
+    /// update time).  Nodes that are due but don't fit on an idle worker remain
+    /// in the backing vector until a worker frees up.
+    fn dispatch_due_nodes(&mut self, node_tx: &Sender<NodeWrapper<ID>>) {
+        let idle = self.idle_workers();
+        if idle == 0 {
+            return;
+        }
+
+        let now = self
+            .clock
+            .now()
+            .duration_since(self.start_instant)
+            .as_micros();
+        // The backing vector is sorted so the nodes due soonest are at the end
+        let due_idx = self
+            .backing
+            .partition_point(|node_wrapper| node_wrapper.priority > now);
+        if due_idx == self.backing.len() {
+            return;
+        }
+        let mut due = self.backing.split_off(due_idx);
+        due.sort_by(|a, b| {
+            a.priority_class
+                .cmp(&b.priority_class)
+                .then(b.priority.cmp(&a.priority))
+                .then(b.sequence.cmp(&a.sequence))
+        });
+
+        for _ in 0..idle {
+            let Some(mut node_wrapper) = due.pop() else {
+                break;
+            };
+            let missed = missed_deadlines(
+                now,
+                node_wrapper.priority,
+                node_wrapper.node.get_update_delay_us(),
+            );
+            if self.drop_stale && missed > 0 {
+                node_wrapper.priority = now;
+            }
+            let node_tx = node_tx.clone();
+            self.in_flight += 1;
+            #[cfg(feature = "metrics")]
+            let clock = self.clock.clone();
+            self.pool.execute(move || {
+                if missed > 0 {
+                    node_wrapper.node.on_deadline_missed(missed);
+                    log_deadline_missed(missed);
+                    node_wrapper.health.record_overruns(missed);
+                }
+                #[cfg(feature = "metrics")]
+                let update_start = clock.now();
+                match node_wrapper.node.try_update_at(now) {
+                    Ok(()) => node_wrapper.health.record_success(),
+                    Err(error) => {
+                        log_update_error(&error);
+                        node_wrapper.health.record_error(now);
+                    }
+                }
+                #[cfg(feature = "metrics")]
+                node_wrapper.metrics.record(
+                    clock.now().duration_since(update_start),
+                    node_wrapper.node.get_update_delay_us(),
+                );
+                node_wrapper.priority += node_wrapper.node.get_update_delay_us();
+                node_tx.send(node_wrapper).unwrap();
+            });
+        }
+
+        for node_wrapper in due.drain(..) {
+            insert_in_order(&mut self.backing, node_wrapper);
+        }
+    }
+
+    /// Return a node the ThreadPool finished updating to the backing vector.
+    ///
+    /// If the executor is configured to sleep and no node is due before `end` (in
+    /// microseconds since the executor was started), the scheduler blocks until
+    /// shortly before the next node is due or a node is returned by the ThreadPool.
+    fn receive_node(&mut self, node_rx: &Receiver<NodeWrapper<ID>>, end: u128) {
+        let node_wrapper = match self.sleep_duration(end) {
+            Some(timeout) => node_rx.recv_timeout(timeout).ok(),
+            None => node_rx.try_recv().ok(),
+        };
+        if let Some(node_wrapper) = node_wrapper {
+            self.in_flight -= 1;
+            insert_in_order(&mut self.backing, node_wrapper);
+        }
+    }
+
+    /// Get the number of ThreadPool workers that aren't updating a node.
+    ///
+    /// Note: The nodes in flight are counted instead of the ThreadPool's active
+    /// workers because a worker is still active for a moment after returning its node.
+    fn idle_workers(&self) -> usize {
+        self.pool.max_count().saturating_sub(self.in_flight)
+    }
+
+    /// Get how long the scheduler can sleep for before the next node is due (or
+    /// `end` is reached), returning None if the scheduler should busy wait instead.
+    ///
+    /// Nodes that are due while every worker is busy can't be dispatched until a
+    /// worker returns a node so the scheduler sleeps until a node is returned.
+    fn sleep_duration(&self, end: u128) -> Option<Duration> {
+        if !self.sleep {
+            return None;
+        }
+
+        let now = self
+            .clock
+            .now()
+            .duration_since(self.start_instant)
+            .as_micros();
+        let next_dispatch = match self.backing.last() {
+            Some(node_wrapper) if node_wrapper.priority > now || self.idle_workers() > 0 => {
+                node_wrapper.priority
+            }
+            _ => u128::MAX,
+        };
+        let wake = next_dispatch.min(end).min(now + MAX_SLEEP_US);
+        (wake > now + SLEEP_SPIN_MARGIN_US)
+            .then(|| Duration::from_micros((wake - now - SLEEP_SPIN_MARGIN_US) as u64))
+    }
+
+    /// Wait for every node currently being updated by the ThreadPool to finish
+    /// its update and return the nodes to the backing vector.
+    ///
+    /// This should be called before shutting the nodes down so no node is left
+    /// half-updated (or missing from the backing vector).
+    fn drain_in_flight_nodes(&mut self, node_rx: &Receiver<NodeWrapper<ID>>) {
+        self.pool.join();
+        while let Ok(node_wrapper) = node_rx.try_recv() {
+            insert_in_order(&mut self.backing, node_wrapper);
+        }
+        self.in_flight = 0;
+    }
+}
+
+impl<ID: PartialEq> ThreadPoolExecutor<ID> {
+    /// Get the error and overrun history of the node with a given id.
+    ///
+    /// Note: The history of a node is lost once the node is removed from the executor.
+    pub fn node_health(&self, id: &ID) -> Option<&NodeHealth> {
+        self.backing
+            .iter()
+            .find(|node_wrapper| node_wrapper.node.get_id().eq(id))
+            .map(|node_wrapper| &node_wrapper.health)
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<ID: PartialEq> ThreadPoolExecutor<ID> {
+    /// Get the execution metrics of the node with a given id
+    pub fn node_metrics(&self, id: &ID) -> Option<&NodeMetrics> {
+        self.backing
+            .iter()
+            .find(|node_wrapper| node_wrapper.node.get_id().eq(id))
+            .map(|node_wrapper| &node_wrapper.metrics)
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<ID: PartialEq + Eq + Hash> ThreadPoolExecutor<ID> {
+    /// Get the execution metrics of every node in the executor keyed by the
+    /// id of the node.
+    ///
+    /// Note: The map is kept in the executor and refreshed in place, so polling
+    /// the metrics doesn't build a new map every call.
+    pub fn metrics(&mut self) -> &HashMap<ID, NodeMetrics> {
+        refresh_metrics(&mut self.metrics, &self.backing);
+        &self.metrics
+    }
+}
+
+impl<ID: PartialEq + 'static> ThreadPoolExecutor<ID> {
+    /// Add a group of nodes to the ThreadPool Executor that can be removed together via
+    /// `remove_group`.
+    ///
+    /// Note: Adding nodes to an existing group adds the nodes to the group.
+    pub fn add_node_group(&mut self, group_id: impl Into<String>, nodes: Vec<Box<dyn Node<ID>>>) {
+        let ids: Vec<ID> = nodes.iter().map(|node| node.get_id()).collect();
+        for node in nodes {
+            self.add_node(node);
         }
+        self.groups.insert(group_id.into(), ids);
+    }
+
+    /// Remove every node in a group from the ThreadPool Executor, returning the removed nodes.
+    ///
+    /// Note: Nodes can only be removed from the executor when it is not running.
+    pub fn remove_group(&mut self, group_id: &str) -> Vec<Box<dyn Node<ID>>> {
+        self.groups
+            .remove(group_id)
+            .iter()
+            .filter_map(|id| self.remove_node(id))
+            .collect()
     }
+
+    /// Get the ids of the nodes in a group
+    pub fn group_node_ids(&self, group_id: &str) -> Option<&[ID]> {
+        self.groups.get(group_id)
+    }
+}
+
+/// Insert a node into the sorted backing vector of a ThreadPool Executor.
+///
+/// Unlike `insert_into`, ties between nodes with the same priority are broken by
+/// the order the nodes were added to the executor (earlier nodes closer to the end
+/// of the vector) so the order nodes are dispatched in doesn't depend on the order
+/// the ThreadPool finished updating them in.
+fn insert_in_order<ID: PartialEq>(backing: &mut Vec<NodeWrapper<ID>>, node: NodeWrapper<ID>) {
+    let idx = backing
+        .partition_point(|other| (other.priority, other.sequence) > (node.priority, node.sequence));
+    backing.insert(idx, node);
 }
 
 impl<ID: PartialEq + 'static> Executor<ID> for ThreadPoolExecutor<ID> {
@@ -103,9 +482,12 @@ impl<ID: PartialEq + 'static> Executor<ID> for ThreadPoolExecutor<ID> {
             node_wrapper.priority = 0;
             node_wrapper.node.start();
         }
+        self.backing
+            .sort_by_key(|node_wrapper| core::cmp::Reverse(node_wrapper.sequence));
+        log_nodes_started(self.backing.len());
 
         self.interrupted = false;
-        self.state = ExecutorState::Started;
+        self.set_state(ExecutorState::Started);
         self.start_instant = self.clock.now();
     }
 
@@ -114,7 +496,7 @@ impl<ID: PartialEq + 'static> Executor<ID> for ThreadPoolExecutor<ID> {
         self.start();
 
         // Run the Executor
-        self.state = ExecutorState::Running;
+        self.set_state(ExecutorState::Running);
         let (node_tx, node_rx) = unbounded();
         while self
             .clock
@@ -124,34 +506,15 @@ impl<ID: PartialEq + 'static> Executor<ID> for ThreadPoolExecutor<ID> {
             < ms
             && !self.check_interrupt()
         {
-            if self.backing.last().is_some()
-                && self
-                    .clock
-                    .now()
-                    .duration_since(self.start_instant)
-                    .as_micros()
-                    >= self.backing.last().unwrap().priority
-            {
-                let mut node_wrapper = self.backing.pop().unwrap();
-                let node_tx = node_tx.clone();
-                self.pool.execute(move || {
-                    node_wrapper.node.update();
-                    node_wrapper.priority += node_wrapper.node.get_update_delay_us();
-                    node_tx.send(node_wrapper).unwrap();
-                });
-            }
-
-            if let Ok(node_wrapper) = node_rx.try_recv() {
-                insert_into(&mut self.backing, node_wrapper);
-            }
+            self.dispatch_due_nodes(&node_tx);
+            self.receive_node(&node_rx, ms * 1_000);
         }
 
         // Stop the Executor
-        for node_wrapper in self.backing.iter_mut() {
-            node_wrapper.priority = 0;
-            node_wrapper.node.shutdown();
-        }
-        self.state = ExecutorState::Stopped;
+        self.drain_in_flight_nodes(&node_rx);
+        let reason = shutdown_reason(self.interrupted);
+        shutdown_nodes(&mut self.backing, reason, self.shutdown_timeout);
+        self.set_state(ExecutorState::Stopped);
     }
 
     fn update_loop(&mut self) {
@@ -159,43 +522,32 @@ impl<ID: PartialEq + 'static> Executor<ID> for ThreadPoolExecutor<ID> {
         self.start();
 
         // Run the Executor
-        self.state = ExecutorState::Running;
+        self.set_state(ExecutorState::Running);
         let (node_tx, node_rx) = unbounded();
         while !self.check_interrupt() {
-            if self.backing.last().is_some()
-                && self
-                    .clock
-                    .now()
-                    .duration_since(self.start_instant)
-                    .as_micros()
-                    >= self.backing.last().unwrap().priority
-            {
-                let mut node_wrapper = self.backing.pop().unwrap();
-                let node_tx = node_tx.clone();
-                self.pool.execute(move || {
-                    node_wrapper.node.update();
-                    node_wrapper.priority += node_wrapper.node.get_update_delay_us();
-                    node_tx.send(node_wrapper).unwrap();
-                });
-            }
-
-            if let Ok(node_wrapper) = node_rx.try_recv() {
-                insert_into(&mut self.backing, node_wrapper);
-            }
+            self.dispatch_due_nodes(&node_tx);
+            self.receive_node(&node_rx, u128::MAX);
         }
 
         // Stop the Executor
-        for node_wrapper in self.backing.iter_mut() {
-            node_wrapper.priority = 0;
-            node_wrapper.node.shutdown();
-        }
-        self.state = ExecutorState::Stopped;
+        self.drain_in_flight_nodes(&node_rx);
+        let reason = shutdown_reason(self.interrupted);
+        shutdown_nodes(&mut self.backing, reason, self.shutdown_timeout);
+        self.set_state(ExecutorState::Stopped);
+    }
+
+    /// Get the current state of the executor
+    fn state(&self) -> ExecutorState {
+        self.state
     }
 
     /// Check the interrupt receiver for an interrupt
     fn check_interrupt(&mut self) -> bool {
         if let Ok(interrupt) = self.interrupt.try_recv() {
             self.interrupted = interrupt;
+            if interrupt {
+                log_interrupt();
+            }
         }
         self.interrupted
     }
@@ -207,29 +559,7 @@ impl<ID: PartialEq + 'static> Executor<ID> for ThreadPoolExecutor<ID> {
     /// Additionally, only 1 node can exist per id so additional nodes added with the same
     /// id will replace the previous node of a given id
     fn add_node(&mut self, node: Box<dyn Node<ID>>) {
-        if let Some(idx) = self
-            .backing
-            .iter()
-            .position(|node_wrapper| node_wrapper.node.get_id().eq(&node.get_id()))
-        {
-            self.backing.remove(idx);
-        }
-
-        if self.state == ExecutorState::Stopped {
-            self.backing.push(NodeWrapper { priority: 0, node });
-        } else if self.state == ExecutorState::Started {
-            insert_into(
-                &mut self.backing,
-                NodeWrapper {
-                    priority: self
-                        .clock
-                        .now()
-                        .duration_since(self.start_instant)
-                        .as_micros(),
-                    node,
-                },
-            );
-        }
+        self.add_node_with_priority(node, 0);
     }
 
     /// Remove a node from the Threadpool Executor.
@@ -250,13 +580,29 @@ impl<ID: PartialEq + 'static> Executor<ID> for ThreadPoolExecutor<ID> {
             None
         }
     }
+
+    /// Get the ids of the nodes in the Threadpool Executor.
+    fn node_ids(&self) -> Vec<ID> {
+        self.backing
+            .iter()
+            .map(|node_wrapper| node_wrapper.node.get_id())
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use std::{any::Any, thread, time::Duration};
+    use std::{
+        any::Any,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
 
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     enum State {
@@ -306,6 +652,72 @@ mod tests {
         }
     }
 
+    struct WorkNode {
+        id: u8,
+        work: Duration,
+        num: u8,
+    }
+
+    impl Node<u8> for WorkNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            self.num += 1;
+            thread::sleep(self.work);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    /// A node that stalls for 10 update periods on its first update
+    struct StallingNode {
+        updates: Arc<AtomicU32>,
+    }
+
+    impl Node<u8> for StallingNode {
+        fn get_id(&self) -> u8 {
+            0
+        }
+
+        fn update(&mut self) {
+            if self.updates.fetch_add(1, Ordering::Relaxed) == 0 {
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    struct InFlightNode {
+        id: u8,
+        state: State,
+    }
+
+    impl Node<u8> for InFlightNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            self.state = State::Updating;
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        fn shutdown(&mut self) {
+            self.state = State::Stopped;
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            1_000
+        }
+    }
+
     #[test]
     fn test_start() {
         let (_, rx) = unbounded();
@@ -424,6 +836,21 @@ mod tests {
         assert_eq!(node_zero.node.get_update_delay_us(), 1_000);
     }
 
+    #[test]
+    fn test_add_node_same_id_while_running() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            ThreadPoolExecutor::new_with(3, rx, vec![Box::new(SimpleNode::new(0, 10_000))]);
+        executor.state = ExecutorState::Running;
+
+        // The rejected node doesn't replace the existing node
+        executor.add_node(Box::new(SimpleNode::new(0, 1_000)));
+
+        assert_eq!(executor.backing.len(), 1);
+        assert_eq!(executor.backing[0].node.get_update_delay_us(), 10_000);
+    }
+
     #[test]
     fn test_remove_node() {
         let (_, rx) = unbounded();
@@ -476,4 +903,316 @@ mod tests {
         assert!(executor.interrupted);
         assert_eq!(executor.state, ExecutorState::Stopped);
     }
+
+    #[cfg(feature = "metrics")]
+    struct SleepingNode {
+        id: u8,
+    }
+
+    #[cfg(feature = "metrics")]
+    impl Node<u8> for SleepingNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            1_000_000
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn test_update_for_ms_metrics() {
+        let (_, rx) = unbounded();
+
+        let mut executor =
+            ThreadPoolExecutor::new_with(3, rx, vec![Box::new(SleepingNode { id: 0 })]);
+
+        executor.update_for_ms(100);
+
+        let node_metrics = *executor.metrics().get(&0).unwrap();
+        assert_eq!(executor.node_metrics(&0), Some(&node_metrics));
+        assert_eq!(node_metrics.count, 1);
+        assert!(Duration::from_millis(5) <= node_metrics.mean);
+        assert!(node_metrics.mean < Duration::from_millis(20));
+        assert!(node_metrics.min <= node_metrics.mean && node_metrics.mean <= node_metrics.max);
+        assert_eq!(node_metrics.overruns, 0);
+    }
+
+    #[test]
+    fn test_node_ids() {
+        let (_, rx) = unbounded();
+
+        let mut executor = ThreadPoolExecutor::new_with(
+            3,
+            rx,
+            vec![
+                Box::new(SimpleNode::new(0, 10_000)),
+                Box::new(SimpleNode::new(1, 25_000)),
+            ],
+        );
+        executor.add_node(Box::new(SimpleNode::new(2, 1_000)));
+
+        let mut ids = executor.node_ids();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 2]);
+        assert!(executor.contains_node(&2));
+        assert!(!executor.contains_node(&3));
+
+        executor.remove_node(&1);
+        assert!(!executor.contains_node(&1));
+    }
+
+    #[test]
+    fn test_update_for_ms_priority_saturated_pool() {
+        let (_, rx) = unbounded();
+
+        // Two threads leaves a single worker which the nodes below oversubscribe
+        let mut executor = ThreadPoolExecutor::new(2, rx);
+        for id in 1..=3 {
+            executor.add_node(Box::new(WorkNode {
+                id,
+                work: Duration::from_millis(5),
+                num: 0,
+            }));
+        }
+        executor.add_node_with_priority(
+            Box::new(WorkNode {
+                id: 0,
+                work: Duration::from_millis(3),
+                num: 0,
+            }),
+            10,
+        );
+
+        executor.update_for_ms(200);
+        thread::sleep(Duration::from_millis(20));
+
+        for node_wrapper in executor.backing.iter() {
+            let work_node: &dyn Any = &node_wrapper.node;
+            let work_node: &Box<WorkNode> = unsafe { work_node.downcast_ref_unchecked() };
+            if work_node.id == 0 {
+                assert!(work_node.num >= 18);
+            } else {
+                assert!(work_node.num <= 12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_loop_interrupt_drains_in_flight_nodes() {
+        let (tx, rx) = unbounded();
+
+        let nodes: Vec<Box<dyn Node<u8>>> = (0..12)
+            .map(|id| {
+                Box::new(InFlightNode {
+                    id,
+                    state: State::Stopped,
+                }) as Box<dyn Node<u8>>
+            })
+            .collect();
+        let mut executor = ThreadPoolExecutor::new_with(4, rx, nodes);
+
+        let interrupter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(30));
+            tx.send(true).unwrap();
+        });
+        executor.update_loop();
+        interrupter.join().unwrap();
+
+        // Every node (including those being updated when the interrupt arrived)
+        // was returned to the executor and shut down
+        assert_eq!(executor.backing.len(), 12);
+        for node_wrapper in executor.backing.iter() {
+            let in_flight_node: &dyn Any = &node_wrapper.node;
+            let in_flight_node: &Box<InFlightNode> =
+                unsafe { in_flight_node.downcast_ref_unchecked() };
+            assert_eq!(in_flight_node.state, State::Stopped);
+        }
+        assert_eq!(executor.pool.panic_count(), 0);
+    }
+
+    #[test]
+    fn test_update_for_ms_drop_stale_saturated_pool() {
+        let (_, rx) = unbounded();
+
+        // Two threads leaves a single worker which the stalled node saturates
+        let updates = Arc::new(AtomicU32::new(0));
+        let mut executor = ThreadPoolExecutor::new_with(
+            2,
+            rx,
+            vec![Box::new(StallingNode {
+                updates: updates.clone(),
+            })],
+        );
+        executor.set_drop_stale(true);
+
+        executor.update_for_ms(200);
+
+        // Without dropping stale updates the node would run its ~10 missed updates
+        // back-to-back after stalling, for ~20 updates in total
+        let updates = updates.load(Ordering::Relaxed);
+        assert!((8..=13).contains(&updates));
+    }
+
+    /// A node that records its id in an update log shared with other nodes
+    struct LoggingNode {
+        id: u8,
+        log: Arc<std::sync::Mutex<Vec<u8>>>,
+    }
+
+    impl Node<u8> for LoggingNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            self.log.lock().unwrap().push(self.id);
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            10_000
+        }
+    }
+
+    #[test]
+    fn test_update_for_ms_equal_rate_ordering() {
+        let (_, rx) = unbounded();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        // A single worker updates the nodes one at a time
+        let mut executor = ThreadPoolExecutor::new_with(
+            2,
+            rx,
+            vec![
+                Box::new(LoggingNode {
+                    id: 0,
+                    log: log.clone(),
+                }),
+                Box::new(LoggingNode {
+                    id: 1,
+                    log: log.clone(),
+                }),
+            ],
+        );
+
+        for _ in 0..3 {
+            log.lock().unwrap().clear();
+            executor.update_for_ms(55);
+
+            // Nodes due at the same time are always updated in the order they were added
+            let log = log.lock().unwrap().clone();
+            assert!(log.len() >= 10);
+            for updates in log.chunks_exact(2) {
+                assert_eq!(updates, [0, 1]);
+            }
+        }
+    }
+
+    /// Get the CPU time (in clock ticks) the current thread has spent running
+    #[cfg(target_os = "linux")]
+    fn thread_cpu_ticks() -> u64 {
+        let stat = std::fs::read_to_string("/proc/thread-self/stat").unwrap();
+        // The utime and stime fields are the 12th and 13th fields after the command
+        let fields: Vec<&str> = stat[stat.rfind(')').unwrap() + 2..].split(' ').collect();
+        fields[11].parse::<u64>().unwrap() + fields[12].parse::<u64>().unwrap()
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_update_for_ms_sleep() {
+        let run = |sleep: bool| {
+            let (_, rx) = unbounded();
+            let updates = Arc::new(AtomicU32::new(0));
+            let mut executor = ThreadPoolExecutor::new_with(
+                2,
+                rx,
+                vec![Box::new(StallingNode {
+                    updates: updates.clone(),
+                })],
+            );
+            executor.set_sleep(sleep);
+            // Skip the stalling first update
+            updates.store(1, Ordering::Relaxed);
+
+            let start = thread_cpu_ticks();
+            executor.update_for_ms(300);
+            (
+                updates.load(Ordering::Relaxed) - 1,
+                thread_cpu_ticks() - start,
+            )
+        };
+
+        let (busy_updates, busy_ticks) = run(false);
+        let (sleep_updates, sleep_ticks) = run(true);
+
+        // The node is updated every 10 milliseconds either way
+        assert!((28..=32).contains(&busy_updates));
+        assert!((28..=32).contains(&sleep_updates));
+        // But the sleeping scheduler barely uses any CPU time
+        assert!(sleep_ticks * 4 < busy_ticks);
+    }
+
+    /// A node that counts its updates and takes 5 milliseconds to update
+    struct SlowCountingNode {
+        id: u8,
+        updates: Arc<AtomicU32>,
+    }
+
+    impl Node<u8> for SlowCountingNode {
+        fn get_id(&self) -> u8 {
+            self.id
+        }
+
+        fn update(&mut self) {
+            self.updates.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        fn get_update_delay_us(&self) -> u128 {
+            5_000
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_update_for_ms_sleep_saturated_pool() {
+        let run = |sleep: bool| {
+            let (_, rx) = unbounded();
+            let updates = Arc::new(AtomicU32::new(0));
+            // A single worker can't keep up with both nodes
+            let mut executor = ThreadPoolExecutor::new_with(
+                2,
+                rx,
+                vec![
+                    Box::new(SlowCountingNode {
+                        id: 0,
+                        updates: updates.clone(),
+                    }),
+                    Box::new(SlowCountingNode {
+                        id: 1,
+                        updates: updates.clone(),
+                    }),
+                ],
+            );
+            executor.set_sleep(sleep);
+
+            let start = thread_cpu_ticks();
+            executor.update_for_ms(300);
+            (updates.load(Ordering::Relaxed), thread_cpu_ticks() - start)
+        };
+
+        let (busy_updates, busy_ticks) = run(false);
+        let (sleep_updates, sleep_ticks) = run(true);
+
+        // The worker is kept busy either way
+        assert!((50..=62).contains(&busy_updates));
+        assert!((50..=62).contains(&sleep_updates));
+        // But the sleeping scheduler waits for the worker instead of spinning
+        assert!(sleep_ticks * 4 < busy_ticks);
+    }
 }
@@ -0,0 +1,274 @@
+//!
+//! Approximate Time Synchronization Node
+//!
+//! The Approximate Time Sync Node buffers recent timestamped data from a number
+//! of input streams and publishes sets of data (one piece of data per input)
+//! whose timestamps all fall within a configurable window (the slop) of one
+//! another.
+//!
+//! This makes it possible to fuse data from sensors that publish asynchronously
+//! (i.e. a camera and a lidar) without requiring their timestamps to match exactly.
+//!
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use ncomm_core::{Node, Publisher, Subscriber};
+
+/// The recent data received from one of the inputs of an Approximate Time Sync Node
+struct Input<S> {
+    /// The subscriber receiving the input's data
+    subscriber: S,
+    /// The timestamp of the latest data received from the subscriber
+    last_seen: Option<Instant>,
+}
+
+/// Approximate Time Sync Node
+///
+/// Every update, the node receives the latest timestamped data from each input
+/// and then publishes every set of data it can align, where a set contains one
+/// piece of data from each input (in the order of the inputs) and the difference
+/// between the earliest and latest timestamps in the set is at most the slop.
+///
+/// Data older than a published set (and data that can no longer be aligned
+/// with the other inputs) is dropped.  Additionally, each input buffers at most
+/// `queue_size` pieces of data, dropping the oldest piece of data when full.
+///
+/// Note: The inputs are polled once per update so the node's update delay
+/// should be shorter than the period of the fastest input.
+pub struct ApproxTimeSync<ID, Data, S, P>
+where
+    ID: PartialEq + Clone + Send,
+    Data: Clone + Send,
+    S: Subscriber<Target = Option<(Data, Instant)>> + Send,
+    P: Publisher<Data = Vec<(Data, Instant)>> + Send,
+{
+    /// The inputs data is received from
+    inputs: Vec<Input<S>>,
+    /// The buffered data from each input (oldest first)
+    queues: Vec<VecDeque<(Data, Instant)>>,
+    /// The publisher sets of aligned data are published to
+    output: P,
+    /// The largest difference between timestamps in a set of aligned data
+    slop: Duration,
+    /// The largest number of pieces of data buffered for each input
+    queue_size: usize,
+    /// The delay (in microseconds) between updates of the node
+    update_delay: u128,
+    /// The id of the node
+    id: ID,
+}
+
+impl<ID, Data, S, P> ApproxTimeSync<ID, Data, S, P>
+where
+    ID: PartialEq + Clone + Send,
+    Data: Clone + Send,
+    S: Subscriber<Target = Option<(Data, Instant)>> + Send,
+    P: Publisher<Data = Vec<(Data, Instant)>> + Send,
+{
+    /// Create a new Approximate Time Sync Node that aligns the data from a number
+    /// of inputs, publishing the aligned sets of data to an output publisher
+    pub fn new(
+        id: ID,
+        inputs: Vec<S>,
+        output: P,
+        slop: Duration,
+        queue_size: usize,
+        update_delay: u128,
+    ) -> Self {
+        let queues = inputs.iter().map(|_| VecDeque::new()).collect();
+        let inputs = inputs
+            .into_iter()
+            .map(|subscriber| Input {
+                subscriber,
+                last_seen: None,
+            })
+            .collect();
+
+        Self {
+            inputs,
+            queues,
+            output,
+            slop,
+            queue_size,
+            update_delay,
+            id,
+        }
+    }
+
+    /// Receive the latest data from each input, buffering any data that hasn't
+    /// been seen before
+    fn receive(&mut self) {
+        for (input, queue) in self.inputs.iter_mut().zip(self.queues.iter_mut()) {
+            let Some((data, timestamp)) = input.subscriber.get().clone() else {
+                continue;
+            };
+            if input.last_seen == Some(timestamp) {
+                continue;
+            }
+
+            input.last_seen = Some(timestamp);
+            queue.push_back((data, timestamp));
+            if queue.len() > self.queue_size {
+                queue.pop_front();
+            }
+        }
+    }
+
+    /// Find the best-aligned set of buffered data, returning the index of the
+    /// chosen data in each input's queue (if the set is within the slop).
+    ///
+    /// When the data can't be aligned, the oldest piece of buffered data is
+    /// dropped as it can never be part of a set.
+    fn align(&mut self) -> Option<Vec<usize>> {
+        // The latest of the oldest pieces of data is the earliest time every input
+        // has data for, so it is the time the other inputs are aligned to
+        let pivot = self
+            .queues
+            .iter()
+            .map(|queue| queue.front().map(|(_, timestamp)| *timestamp))
+            .collect::<Option<Vec<Instant>>>()?
+            .into_iter()
+            .max()?;
+
+        let indices: Vec<usize> = self
+            .queues
+            .iter()
+            .map(|queue| {
+                queue
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, (_, timestamp))| abs_diff(*timestamp, pivot))
+                    .map(|(idx, _)| idx)
+                    .unwrap()
+            })
+            .collect();
+
+        let timestamps = || {
+            self.queues
+                .iter()
+                .zip(indices.iter())
+                .map(|(queue, idx)| queue[*idx].1)
+        };
+        let earliest = timestamps().min()?;
+        let latest = timestamps().max()?;
+        if latest - earliest <= self.slop {
+            return Some(indices);
+        }
+
+        let oldest = self
+            .queues
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, queue)| queue.front().map(|(_, timestamp)| *timestamp))
+            .map(|(idx, _)| idx)?;
+        self.queues[oldest].pop_front();
+        None
+    }
+
+    /// Publish every set of data that can be aligned
+    fn publish_aligned(&mut self) {
+        while self.queues.iter().all(|queue| !queue.is_empty()) {
+            let Some(indices) = self.align() else {
+                continue;
+            };
+
+            let set = self
+                .queues
+                .iter_mut()
+                .zip(indices)
+                .map(|(queue, idx)| {
+                    let data = queue.remove(idx).unwrap();
+                    queue.drain(..idx);
+                    data
+                })
+                .collect();
+            let _ = self.output.publish(set);
+        }
+    }
+}
+
+/// The absolute difference between two instants
+fn abs_diff(a: Instant, b: Instant) -> Duration {
+    a.checked_duration_since(b)
+        .unwrap_or_else(|| b.duration_since(a))
+}
+
+impl<ID, Data, S, P> Node<ID> for ApproxTimeSync<ID, Data, S, P>
+where
+    ID: PartialEq + Clone + Send,
+    Data: Clone + Send,
+    S: Subscriber<Target = Option<(Data, Instant)>> + Send,
+    P: Publisher<Data = Vec<(Data, Instant)>> + Send,
+{
+    fn get_id(&self) -> ID {
+        self.id.clone()
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.update_delay
+    }
+
+    fn update(&mut self) {
+        self.receive();
+        self.publish_aligned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ncomm_publishers_and_subscribers::local::LocalPublisher;
+
+    #[test]
+    fn test_approx_time_sync_pairs_within_slop() {
+        let mut camera = LocalPublisher::new();
+        let mut lidar = LocalPublisher::new();
+        let mut output = LocalPublisher::new();
+        let mut fused = output.subscribe_buffered();
+
+        let mut node = ApproxTimeSync::new(
+            0u8,
+            vec![camera.subscribe(), lidar.subscribe()],
+            output,
+            Duration::from_millis(10),
+            10,
+            1_000,
+        );
+
+        let base = Instant::now();
+        let at = |ms: u64| base + Duration::from_millis(ms);
+
+        // Data 3ms apart is paired
+        camera.publish((0u64, at(0))).unwrap();
+        node.update();
+        lidar.publish((100u64, at(3))).unwrap();
+        node.update();
+
+        // Data 50ms apart isn't, so the stale camera data is dropped
+        camera.publish((1, at(100))).unwrap();
+        node.update();
+        lidar.publish((101, at(150))).unwrap();
+        node.update();
+
+        // and the lidar data is paired with the next (closer) camera data
+        camera.publish((2, at(152))).unwrap();
+        node.update();
+
+        let sets: Vec<Vec<(u64, Instant)>> = fused
+            .get()
+            .iter()
+            .map(|set| set.as_ref().clone().unwrap())
+            .collect();
+        assert_eq!(
+            sets,
+            vec![
+                vec![(0, at(0)), (100, at(3))],
+                vec![(2, at(152)), (101, at(150))],
+            ]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//!
+//! Bag Replay Node
+//!
+//! The Bag Replay Node replays a log file recorded by a `LoggingPublisher`
+//! through a `LocalPublisher` so that downstream nodes can be run against
+//! recorded data without live hardware.
+//!
+//! The recorded data is published with the timing it was recorded with, sped up
+//! (or slowed down) by a playback speed multiplier, and can optionally be looped.
+//!
+
+use std::{io::Error, path::Path};
+
+use ncomm_core::Node;
+use ncomm_publishers_and_subscribers::{local::LocalPublisher, logging::ReplayPublisher};
+use ncomm_utils::packing::Packable;
+
+/// Bag Replay Node
+///
+/// Every update, the node publishes each recorded piece of data whose recorded
+/// timestamp has been reached by the simulated time since the replay started
+/// (the elapsed time multiplied by the playback speed).
+///
+/// Note: The node is a `ReplayPublisher` configured with a playback speed and
+/// looping so data can only be replayed as precisely as the node's update delay.
+pub struct BagReplayNode<ID: PartialEq + Clone + Send, Data: Packable + Send + Sync> {
+    /// The replay of the log file
+    replay: ReplayPublisher<ID, LocalPublisher<Data>>,
+}
+
+impl<ID: PartialEq + Clone + Send, Data: Packable + Send + Sync> BagReplayNode<ID, Data> {
+    /// Create a new Bag Replay Node that replays the log file at `path` through a
+    /// local publisher at a given playback speed
+    pub fn new(
+        id: ID,
+        path: impl AsRef<Path>,
+        publisher: LocalPublisher<Data>,
+        speed: f64,
+        looping: bool,
+        update_delay: u128,
+    ) -> Result<Self, Error> {
+        let mut replay = ReplayPublisher::new(id, path, publisher, update_delay)?;
+        replay.set_speed(speed);
+        replay.set_looping(looping);
+        Ok(Self { replay })
+    }
+
+    /// Check whether every piece of recorded data has been replayed (a looping
+    /// node is never finished)
+    pub fn is_finished(&self) -> bool {
+        self.replay.is_finished()
+    }
+}
+
+impl<ID: PartialEq + Clone + Send, Data: Packable + Send + Sync> Node<ID>
+    for BagReplayNode<ID, Data>
+{
+    fn get_id(&self) -> ID {
+        self.replay.get_id()
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.replay.get_update_delay_us()
+    }
+
+    fn start(&mut self) {
+        self.replay.start();
+    }
+
+    fn update(&mut self) {
+        self.replay.update();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        path::PathBuf,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use ncomm_core::{Publisher, Subscriber};
+    use ncomm_publishers_and_subscribers::logging::LoggingPublisher;
+
+    /// A recorded bag in the temporary directory that is deleted when dropped
+    struct TempBag(PathBuf);
+
+    impl TempBag {
+        /// Record three pieces of data 100ms apart to a bag whose path is unique
+        /// to the test (and the process running it)
+        fn record(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "ncomm_bag_replay_{name}_{}.log",
+                std::process::id()
+            ));
+            let mut publisher = LoggingPublisher::new(LocalPublisher::new(), &path).unwrap();
+            for data in 1u32..=3 {
+                if data > 1 {
+                    thread::sleep(Duration::from_millis(100));
+                }
+                publisher.publish(data).unwrap();
+            }
+            Self(path)
+        }
+    }
+
+    impl Drop for TempBag {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_bag_replay_speed() {
+        let bag = TempBag::record("speed");
+
+        let mut publisher = LocalPublisher::new();
+        let mut subscriber = publisher.subscribe_buffered();
+        let mut node = BagReplayNode::new(0u8, &bag.0, publisher, 10.0, false, 1_000).unwrap();
+
+        let start = Instant::now();
+        node.start();
+        while !node.is_finished() && start.elapsed() < Duration::from_secs(1) {
+            node.update();
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // The 200ms recording is replayed in ~20ms
+        assert!(start.elapsed() < Duration::from_millis(150));
+        let data: Vec<u32> = subscriber.get().iter().map(|data| data.unwrap()).collect();
+        assert_eq!(data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_bag_replay_loop() {
+        let bag = TempBag::record("loop");
+
+        let mut publisher = LocalPublisher::new();
+        let mut subscriber = publisher.subscribe_buffered();
+        let mut node = BagReplayNode::new(0u8, &bag.0, publisher, 10.0, true, 1_000).unwrap();
+
+        let start = Instant::now();
+        node.start();
+        while subscriber.get().len() < 5 && start.elapsed() < Duration::from_secs(1) {
+            node.update();
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert!(!node.is_finished());
+        let data: Vec<u32> = subscriber.get().iter().map(|data| data.unwrap()).collect();
+        assert_eq!(data, vec![1, 2, 3, 1, 2]);
+    }
+}
@@ -0,0 +1,191 @@
+//!
+//! Bridge Node
+//!
+//! The Bridge Node relays data received by a subscriber on one transport to a
+//! publisher on another transport (i.e. re-publishing a remote UDP feed as a
+//! local topic).
+//!
+
+use ncomm_core::{ConsumingSubscriber, Node, Publisher};
+
+/// Bridge Node
+///
+/// Every update, the node takes any new data from its subscriber and re-publishes
+/// it with its publisher.
+///
+/// Note: Only the latest data received since the previous update is relayed, so
+/// the node's update delay should be shorter than the period of the data.
+///
+/// Addendum: The data is taken from the subscriber (rather than relying on
+/// `has_update`) so data is only ever relayed once, even by subscribers that
+/// always report having an update.
+pub struct BridgeNode<ID, Data, S, P>
+where
+    ID: PartialEq + Clone + Send,
+    Data: Send,
+    S: ConsumingSubscriber<Data = Data> + Send,
+    P: Publisher<Data = Data> + Send,
+{
+    /// The subscriber data is received from
+    subscriber: S,
+    /// The publisher data is re-published with
+    publisher: P,
+    /// The delay (in microseconds) between updates of the node
+    update_delay: u128,
+    /// The id of the node
+    id: ID,
+}
+
+impl<ID, Data, S, P> BridgeNode<ID, Data, S, P>
+where
+    ID: PartialEq + Clone + Send,
+    Data: Send,
+    S: ConsumingSubscriber<Data = Data> + Send,
+    P: Publisher<Data = Data> + Send,
+{
+    /// Create a new Bridge Node relaying data from a subscriber to a publisher
+    pub fn new(id: ID, subscriber: S, publisher: P, update_delay: u128) -> Self {
+        Self {
+            subscriber,
+            publisher,
+            update_delay,
+            id,
+        }
+    }
+
+    /// Destroy the Bridge Node, returning its subscriber and publisher
+    pub fn into_inner(self) -> (S, P) {
+        (self.subscriber, self.publisher)
+    }
+}
+
+impl<ID, Data, S, P> Node<ID> for BridgeNode<ID, Data, S, P>
+where
+    ID: PartialEq + Clone + Send,
+    Data: Send,
+    S: ConsumingSubscriber<Data = Data> + Send,
+    P: Publisher<Data = Data> + Send,
+{
+    fn get_id(&self) -> ID {
+        self.id.clone()
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.update_delay
+    }
+
+    fn update(&mut self) {
+        if let Some(data) = self.subscriber.take() {
+            let _ = self.publisher.publish(data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        collections::VecDeque,
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket},
+        thread::sleep,
+        time::Duration,
+    };
+
+    use ncomm_core::Subscriber;
+    use ncomm_publishers_and_subscribers::{
+        local::LocalPublisher,
+        udp::{UdpPublisher, UdpSubscriber},
+    };
+
+    /// A subscriber that keeps the latest data it received and (like the serial
+    /// subscribers) always reports having an update
+    struct CachingSubscriber {
+        incoming: VecDeque<u64>,
+        data: Option<u64>,
+    }
+
+    impl Subscriber for CachingSubscriber {
+        type Target = Option<u64>;
+
+        fn get(&mut self) -> &Self::Target {
+            if let Some(data) = self.incoming.drain(..).next_back() {
+                self.data = Some(data);
+            }
+            &self.data
+        }
+    }
+
+    impl ConsumingSubscriber for CachingSubscriber {
+        type Data = u64;
+
+        fn take(&mut self) -> Option<Self::Data> {
+            self.get();
+            self.data.take()
+        }
+    }
+
+    #[test]
+    fn test_bridge_udp_to_local() {
+        let mut remote = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8054)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8055))],
+        )
+        .unwrap();
+        let subscriber: UdpSubscriber<u64> =
+            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8055)))
+                .unwrap();
+        let mut local = LocalPublisher::new();
+        let mut relayed = local.subscribe_buffered();
+
+        let mut node = BridgeNode::new(0u8, subscriber, local, 1_000);
+
+        remote.publish(7u64).unwrap();
+        sleep(Duration::from_millis(50));
+        node.update();
+        // Nothing new was received so nothing is relayed
+        node.update();
+        // Datagrams of the wrong size aren't data so nothing is relayed
+        UdpSocket::bind(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)))
+            .unwrap()
+            .send_to(
+                &[1, 2, 3],
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8055)),
+            )
+            .unwrap();
+        sleep(Duration::from_millis(50));
+        node.update();
+        remote.publish(8).unwrap();
+        sleep(Duration::from_millis(50));
+        node.update();
+
+        let relayed: Vec<u64> = relayed
+            .get()
+            .iter()
+            .map(|data| data.as_ref().unwrap())
+            .collect();
+        assert_eq!(relayed, vec![7, 8]);
+    }
+
+    #[test]
+    fn test_bridge_default_has_update() {
+        let subscriber = CachingSubscriber {
+            incoming: VecDeque::from([1, 2]),
+            data: None,
+        };
+        let mut local = LocalPublisher::new();
+        let mut relayed = local.subscribe_buffered();
+
+        let mut node = BridgeNode::new(0u8, subscriber, local, 1_000);
+        for _ in 0..5 {
+            node.update();
+        }
+
+        let relayed: Vec<u64> = relayed
+            .get()
+            .iter()
+            .map(|data| data.as_ref().unwrap())
+            .collect();
+        assert_eq!(relayed, vec![2]);
+    }
+}
@@ -0,0 +1,510 @@
+//!
+//! Discovery Nodes
+//!
+//! The Discovery Server Node keeps a registry of the services offered by the
+//! nodes in a (possibly multi-process) system so that other nodes can find the
+//! address of a service by name instead of hard-coding addresses.
+//!
+//! Discovery Client Nodes register their services with the Discovery Server
+//! Node when they are started and can resolve the name of a service to its
+//! address (i.e. before creating a publisher or client for the service).
+//!
+//! Both nodes communicate via the UDP client and server from
+//! ncomm-clients-and-servers.
+//!
+
+use std::{
+    collections::HashMap,
+    io::Error,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use ncomm_clients_and_servers::udp::{UdpClient, UdpClientServerError, UdpServer};
+use ncomm_core::{Client, Node, Server};
+use ncomm_utils::packing::{Packable, PackingError};
+
+/// The maximum length (in bytes) of the name of a service
+pub const MAX_SERVICE_NAME_LEN: usize = 32;
+
+/// The length of a packed socket address (family, ip, and port)
+const ADDRESS_LEN: usize = 19;
+
+/// The length of a packed service name (length and name)
+const SERVICE_NAME_LEN: usize = 1 + MAX_SERVICE_NAME_LEN;
+
+/// The time a Discovery Client Node waits between polls while resolving a service
+const RESOLVE_POLL_PERIOD: Duration = Duration::from_millis(1);
+
+/// Pack a socket address into a buffer of at least `ADDRESS_LEN` bytes
+fn pack_address(address: SocketAddr, buffer: &mut [u8]) {
+    match address.ip() {
+        IpAddr::V4(ip) => {
+            buffer[0] = 4;
+            buffer[1..5].copy_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            buffer[0] = 6;
+            buffer[1..17].copy_from_slice(&ip.octets());
+        }
+    }
+    buffer[17..19].copy_from_slice(&address.port().to_be_bytes());
+}
+
+/// Unpack a socket address from a buffer of at least `ADDRESS_LEN` bytes
+fn unpack_address(data: &[u8]) -> Result<SocketAddr, PackingError> {
+    let port = u16::from_be_bytes([data[17], data[18]]);
+    match data[0] {
+        4 => Ok(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(data[1], data[2], data[3], data[4])),
+            port,
+        )),
+        6 => Ok(SocketAddr::new(
+            IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(&data[1..17]).unwrap())),
+            port,
+        )),
+        family => Err(PackingError::InvalidDiscriminant(family)),
+    }
+}
+
+/// Pack the name of a service into a buffer of at least `SERVICE_NAME_LEN` bytes
+fn pack_service_name(service: &str, buffer: &mut [u8]) -> Result<(), PackingError> {
+    if service.len() > MAX_SERVICE_NAME_LEN {
+        return Err(PackingError::InvalidBufferSize {
+            expected: 1 + service.len(),
+            got: SERVICE_NAME_LEN,
+        });
+    }
+    buffer[0] = service.len() as u8;
+    buffer[1..1 + service.len()].copy_from_slice(service.as_bytes());
+    Ok(())
+}
+
+/// Unpack the name of a service from a buffer of at least `SERVICE_NAME_LEN` bytes
+fn unpack_service_name(data: &[u8]) -> Result<String, PackingError> {
+    let len = data[0] as usize;
+    if len > MAX_SERVICE_NAME_LEN {
+        return Err(PackingError::InvalidBufferSize {
+            expected: 1 + len,
+            got: SERVICE_NAME_LEN,
+        });
+    }
+    String::from_utf8(data[1..1 + len].to_vec()).map_err(|_| PackingError::Utf8Error)
+}
+
+/// A service offered by a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ServiceRecord {
+    /// The id of the node offering the service
+    pub node_id: u64,
+    /// The address the service can be reached at
+    pub address: SocketAddr,
+}
+
+/// A request sent from a Discovery Client Node to a Discovery Server Node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiscoveryRequest {
+    /// Register a service (replacing any service already registered with the
+    /// same name)
+    Register {
+        /// The name of the service
+        service: String,
+        /// The service being registered
+        record: ServiceRecord,
+    },
+    /// Look up the service with a given name
+    Lookup {
+        /// The name of the service
+        service: String,
+    },
+}
+
+impl DiscoveryRequest {
+    /// Get the name of the service the request is for
+    pub fn service(&self) -> &str {
+        match self {
+            Self::Register { service, .. } => service,
+            Self::Lookup { service } => service,
+        }
+    }
+}
+
+impl Packable for DiscoveryRequest {
+    fn len() -> usize {
+        1 + SERVICE_NAME_LEN + 8 + ADDRESS_LEN
+    }
+
+    fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+        if buffer.len() < Self::len() {
+            return Err(PackingError::InvalidBufferSize {
+                expected: Self::len(),
+                got: buffer.len(),
+            });
+        }
+
+        buffer[..Self::len()].fill(0);
+        let service = match self {
+            Self::Register { service, record } => {
+                buffer[0] = 0;
+                buffer[1 + SERVICE_NAME_LEN..9 + SERVICE_NAME_LEN]
+                    .copy_from_slice(&record.node_id.to_be_bytes());
+                pack_address(record.address, &mut buffer[9 + SERVICE_NAME_LEN..]);
+                service
+            }
+            Self::Lookup { service } => {
+                buffer[0] = 1;
+                service
+            }
+        };
+        pack_service_name(&service, &mut buffer[1..])
+    }
+
+    fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+        if data.len() < Self::len() {
+            return Err(PackingError::InvalidBufferSize {
+                expected: Self::len(),
+                got: data.len(),
+            });
+        }
+
+        let service = unpack_service_name(&data[1..])?;
+        match data[0] {
+            0 => Ok(Self::Register {
+                service,
+                record: ServiceRecord {
+                    node_id: u64::from_be_bytes(
+                        data[1 + SERVICE_NAME_LEN..9 + SERVICE_NAME_LEN]
+                            .try_into()
+                            .unwrap(),
+                    ),
+                    address: unpack_address(&data[9 + SERVICE_NAME_LEN..])?,
+                },
+            }),
+            1 => Ok(Self::Lookup { service }),
+            kind => Err(PackingError::InvalidDiscriminant(kind)),
+        }
+    }
+}
+
+/// A response sent from a Discovery Server Node containing the service the
+/// request was for (if it is registered)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiscoveryResponse(pub Option<ServiceRecord>);
+
+impl Packable for DiscoveryResponse {
+    fn len() -> usize {
+        1 + 8 + ADDRESS_LEN
+    }
+
+    fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+        if buffer.len() < Self::len() {
+            return Err(PackingError::InvalidBufferSize {
+                expected: Self::len(),
+                got: buffer.len(),
+            });
+        }
+
+        buffer[..Self::len()].fill(0);
+        if let Some(record) = self.0 {
+            buffer[0] = 1;
+            buffer[1..9].copy_from_slice(&record.node_id.to_be_bytes());
+            pack_address(record.address, &mut buffer[9..]);
+        }
+        Ok(())
+    }
+
+    fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+        if data.len() < Self::len() {
+            return Err(PackingError::InvalidBufferSize {
+                expected: Self::len(),
+                got: data.len(),
+            });
+        }
+
+        if data[0] == 0 {
+            return Ok(Self(None));
+        }
+        Ok(Self(Some(ServiceRecord {
+            node_id: u64::from_be_bytes(data[1..9].try_into().unwrap()),
+            address: unpack_address(&data[9..])?,
+        })))
+    }
+}
+
+/// The Discovery Server Node.
+///
+/// The Discovery Server Node answers registration and lookup requests from
+/// Discovery Client Nodes.  Any client that sends a request is answered, so
+/// clients don't need to be known ahead of time.
+pub struct DiscoveryServerNode<ID: PartialEq + Clone + Send> {
+    /// The identifier for the node
+    id: ID,
+    /// The delay (in microseconds) between updates of the node
+    update_delay: u128,
+    /// The udp server requests are received with
+    server: UdpServer<DiscoveryRequest, DiscoveryResponse, SocketAddr>,
+    /// The registered services keyed by their name
+    services: HashMap<String, ServiceRecord>,
+}
+
+impl<ID: PartialEq + Clone + Send> DiscoveryServerNode<ID> {
+    /// Create a new Discovery Server Node answering requests sent to `bind_address`
+    pub fn new(id: ID, bind_address: SocketAddr, update_delay: u128) -> Result<Self, Error> {
+        Ok(Self {
+            id,
+            update_delay,
+            server: UdpServer::new(bind_address)?,
+            services: HashMap::new(),
+        })
+    }
+
+    /// Get the service registered with a given name
+    pub fn service(&self, service: &str) -> Option<ServiceRecord> {
+        self.services.get(service).copied()
+    }
+
+    /// Handle a request, returning the response to the request
+    fn handle_request(&mut self, request: &DiscoveryRequest) -> DiscoveryResponse {
+        if let DiscoveryRequest::Register { service, record } = request {
+            self.services.insert(service.clone(), *record);
+        }
+        DiscoveryResponse(self.service(request.service()))
+    }
+}
+
+impl<ID: PartialEq + Clone + Send> Node<ID> for DiscoveryServerNode<ID> {
+    fn get_id(&self) -> ID {
+        self.id.clone()
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.update_delay
+    }
+
+    fn update(&mut self) {
+        for request in self.server.poll_for_requests() {
+            let (address, request) = match request {
+                Ok(request) => request,
+                Err(UdpClientServerError::UnknownRequester((request, address))) => {
+                    self.server.add_clients(vec![(address, address)]);
+                    (address, request)
+                }
+                Err(_) => continue,
+            };
+
+            let response = self.handle_request(&request);
+            let _ = self.server.send_response(address, request, response);
+        }
+    }
+}
+
+/// The Discovery Client Node.
+///
+/// The Discovery Client Node registers its services with a Discovery Server Node
+/// when it is started and resolves the names of other services to their addresses.
+pub struct DiscoveryClientNode<ID: PartialEq + Copy + Send + Into<u64>> {
+    /// The identifier for the node
+    id: ID,
+    /// The delay (in microseconds) between updates of the node
+    update_delay: u128,
+    /// The udp client requests are sent with
+    client: UdpClient<DiscoveryRequest, DiscoveryResponse>,
+    /// The services registered by this node (and their addresses)
+    services: Vec<(String, SocketAddr)>,
+    /// The services that have been resolved keyed by their name
+    resolved: HashMap<String, ServiceRecord>,
+}
+
+impl<ID: PartialEq + Copy + Send + Into<u64>> DiscoveryClientNode<ID> {
+    /// Create a new Discovery Client Node that communicates with the Discovery Server
+    /// Node at `server_address` and registers the given services when started
+    pub fn new(
+        id: ID,
+        bind_address: SocketAddr,
+        server_address: SocketAddr,
+        services: Vec<(String, SocketAddr)>,
+        update_delay: u128,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            id,
+            update_delay,
+            client: UdpClient::new(bind_address, server_address)?,
+            services,
+            resolved: HashMap::new(),
+        })
+    }
+
+    /// Register a service offered by this node with the Discovery Server Node
+    pub fn register(
+        &mut self,
+        service: impl Into<String>,
+        address: SocketAddr,
+    ) -> Result<(), UdpClientServerError<DiscoveryRequest>> {
+        self.client.send_request(DiscoveryRequest::Register {
+            service: service.into(),
+            record: ServiceRecord {
+                node_id: self.id.into(),
+                address,
+            },
+        })
+    }
+
+    /// Request the address of a service from the Discovery Server Node.
+    ///
+    /// The response is received on the next update of the node, after which the
+    /// service is available via `resolved`.
+    pub fn lookup(
+        &mut self,
+        service: impl Into<String>,
+    ) -> Result<(), UdpClientServerError<DiscoveryRequest>> {
+        self.client.send_request(DiscoveryRequest::Lookup {
+            service: service.into(),
+        })
+    }
+
+    /// Get a service that has previously been resolved
+    pub fn resolved(&self, service: &str) -> Option<ServiceRecord> {
+        self.resolved.get(service).copied()
+    }
+
+    /// Resolve the name of a service to its address, waiting up to `timeout` for
+    /// the Discovery Server Node to respond.
+    ///
+    /// Note: None is returned if the service isn't registered or the server didn't
+    /// respond in time.
+    pub fn resolve(&mut self, service: &str, timeout: Duration) -> Option<SocketAddr> {
+        self.resolved.remove(service);
+        self.lookup(service).ok()?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.poll_responses();
+            if let Some(record) = self.resolved(service) {
+                return Some(record.address);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::sleep(RESOLVE_POLL_PERIOD);
+        }
+    }
+
+    /// Record the services from each of the responses received from the server
+    fn poll_responses(&mut self) {
+        for (request, response) in self.client.poll_for_responses().into_iter().flatten() {
+            if let DiscoveryResponse(Some(record)) = response {
+                self.resolved.insert(request.service().to_string(), record);
+            }
+        }
+    }
+}
+
+impl<ID: PartialEq + Copy + Send + Into<u64>> Node<ID> for DiscoveryClientNode<ID> {
+    fn get_id(&self) -> ID {
+        self.id
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.update_delay
+    }
+
+    fn start(&mut self) {
+        for (service, address) in self.services.clone() {
+            let _ = self.register(service, address);
+        }
+    }
+
+    fn update(&mut self) {
+        self.poll_responses();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{net::SocketAddrV4, thread, time::Duration};
+
+    #[test]
+    fn test_pack_discovery_request() {
+        let request = DiscoveryRequest::Register {
+            service: "camera".to_string(),
+            record: ServiceRecord {
+                node_id: 42,
+                address: "[::1]:9000".parse().unwrap(),
+            },
+        };
+        let mut buffer = vec![0u8; DiscoveryRequest::len()];
+        request.clone().pack(&mut buffer).unwrap();
+        assert_eq!(DiscoveryRequest::unpack(&buffer).unwrap(), request);
+
+        let request = DiscoveryRequest::Lookup {
+            service: "x".repeat(MAX_SERVICE_NAME_LEN + 1),
+        };
+        assert_eq!(
+            request.pack(&mut buffer),
+            Err(PackingError::InvalidBufferSize {
+                expected: MAX_SERVICE_NAME_LEN + 2,
+                got: SERVICE_NAME_LEN
+            })
+        );
+    }
+
+    #[test]
+    fn test_register_and_resolve_services() {
+        let server_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7020));
+        let camera_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9000));
+        let lidar_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9001));
+
+        let mut server = DiscoveryServerNode::new(0u8, server_address, 1_000).unwrap();
+        let mut sensors = DiscoveryClientNode::new(
+            1u8,
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7021)),
+            server_address,
+            vec![
+                ("camera".to_string(), camera_address),
+                ("lidar".to_string(), lidar_address),
+            ],
+            1_000,
+        )
+        .unwrap();
+        let mut consumer = DiscoveryClientNode::new(
+            2u8,
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7022)),
+            server_address,
+            Vec::new(),
+            1_000,
+        )
+        .unwrap();
+
+        sensors.start();
+        thread::sleep(Duration::from_millis(20));
+        server.update();
+        assert_eq!(
+            server.service("camera"),
+            Some(ServiceRecord {
+                node_id: 1,
+                address: camera_address
+            })
+        );
+
+        // Answer the lookups from a separate thread while the consumer resolves them
+        let handle = thread::spawn(move || {
+            for _ in 0..100 {
+                server.update();
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        assert_eq!(
+            consumer.resolve("camera", Duration::from_millis(100)),
+            Some(camera_address)
+        );
+        assert_eq!(
+            consumer.resolve("lidar", Duration::from_millis(100)),
+            Some(lidar_address)
+        );
+        assert_eq!(consumer.resolve("radar", Duration::from_millis(20)), None);
+        assert_eq!(consumer.resolved("lidar").unwrap().node_id, 1);
+        handle.join().unwrap();
+    }
+}
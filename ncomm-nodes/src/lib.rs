@@ -7,6 +7,36 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[deny(missing_docs)]
+#[cfg(feature = "std")]
+pub mod approx_time_sync;
+#[cfg(feature = "std")]
+pub use approx_time_sync::ApproxTimeSync;
+
+#[deny(missing_docs)]
+#[cfg(feature = "std")]
+pub mod bag_replay;
+#[cfg(feature = "std")]
+pub use bag_replay::BagReplayNode;
+
+#[deny(missing_docs)]
+#[cfg(feature = "std")]
+pub mod bridge;
+#[cfg(feature = "std")]
+pub use bridge::BridgeNode;
+
+#[deny(missing_docs)]
+#[cfg(feature = "discovery")]
+pub mod discovery;
+#[cfg(feature = "discovery")]
+pub use discovery::{DiscoveryClientNode, DiscoveryServerNode};
+
+#[deny(missing_docs)]
+#[cfg(feature = "std")]
+pub mod parameter_server;
+#[cfg(feature = "std")]
+pub use parameter_server::ParameterServerNode;
+
 #[deny(missing_docs)]
 #[cfg(feature = "rerun")]
 pub mod rerun;
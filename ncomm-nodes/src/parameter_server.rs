@@ -0,0 +1,334 @@
+//!
+//! Parameter Server Node
+//!
+//! The Parameter Server Node stores named parameters that can be read and
+//! changed at runtime by other nodes (i.e. to tune the gains of a controller
+//! without recompiling).
+//!
+//! Other nodes get and set parameters by sending requests via local clients
+//! created by the Parameter Server Node.  Additionally, every change to a
+//! parameter is broadcast to subscribers so nodes can react to new values
+//! without polling the server.
+//!
+
+use std::{collections::HashMap, hash::Hash};
+
+use ncomm_clients_and_servers::local::{LocalClient, LocalServer};
+use ncomm_core::{Node, Publisher, Server};
+use ncomm_publishers_and_subscribers::local::{LocalBufferedSubscriber, LocalPublisher};
+
+/// The value of a parameter
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParameterValue {
+    /// A floating point parameter
+    F64(f64),
+    /// An integer parameter
+    I64(i64),
+    /// A boolean parameter
+    Bool(bool),
+    /// A string parameter
+    String(String),
+}
+
+impl ParameterValue {
+    /// Get the value of a floating point parameter
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::F64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value of an integer parameter
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::I64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a boolean parameter
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a string parameter
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Check whether two values are of the same type
+    fn same_type(&self, other: &Self) -> bool {
+        core::mem::discriminant(self) == core::mem::discriminant(other)
+    }
+}
+
+impl From<f64> for ParameterValue {
+    fn from(value: f64) -> Self {
+        Self::F64(value)
+    }
+}
+
+impl From<i64> for ParameterValue {
+    fn from(value: i64) -> Self {
+        Self::I64(value)
+    }
+}
+
+impl From<bool> for ParameterValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<String> for ParameterValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for ParameterValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+/// A request sent to a Parameter Server Node
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParameterRequest {
+    /// Get the value of a parameter
+    Get {
+        /// The name of the parameter
+        name: String,
+    },
+    /// Set the value of a parameter
+    Set {
+        /// The name of the parameter
+        name: String,
+        /// The new value of the parameter
+        value: ParameterValue,
+    },
+}
+
+impl ParameterRequest {
+    /// Create a request for the value of a parameter
+    pub fn get(name: impl Into<String>) -> Self {
+        Self::Get { name: name.into() }
+    }
+
+    /// Create a request to set the value of a parameter
+    pub fn set(name: impl Into<String>, value: impl Into<ParameterValue>) -> Self {
+        Self::Set {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Get the name of the parameter the request is for
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Get { name } => name,
+            Self::Set { name, .. } => name,
+        }
+    }
+}
+
+/// A response sent from a Parameter Server Node containing the value of the
+/// parameter after the request was handled (if the parameter exists)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterResponse(pub Option<ParameterValue>);
+
+/// A change to a parameter broadcast by a Parameter Server Node
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterUpdate {
+    /// The name of the parameter
+    pub name: String,
+    /// The new value of the parameter
+    pub value: ParameterValue,
+}
+
+/// The Parameter Server Node.
+///
+/// The Parameter Server Node answers get and set requests from its clients,
+/// publishing a `ParameterUpdate` whenever the value of a parameter changes.
+///
+/// Note: A parameter keeps the type it was first set with so requests setting a
+/// parameter to a value of a different type are ignored (and answered with the
+/// parameter's current value).
+pub struct ParameterServerNode<ID: PartialEq + Clone + Send, K: Hash + Eq + Clone = String> {
+    /// The identifier for the node
+    id: ID,
+    /// The delay (in microseconds) between updates of the node
+    update_delay: u128,
+    /// The local server requests are received with
+    server: LocalServer<ParameterRequest, ParameterResponse, K>,
+    /// The publisher changes to parameters are broadcast with
+    publisher: LocalPublisher<ParameterUpdate>,
+    /// The parameters keyed by their name
+    parameters: HashMap<String, ParameterValue>,
+}
+
+impl<ID: PartialEq + Clone + Send, K: Hash + Eq + Clone> ParameterServerNode<ID, K> {
+    /// Create a new Parameter Server Node without any parameters
+    pub fn new(id: ID, update_delay: u128) -> Self {
+        Self {
+            id,
+            update_delay,
+            server: LocalServer::new(),
+            publisher: LocalPublisher::new(),
+            parameters: HashMap::new(),
+        }
+    }
+
+    /// Create a client that can get and set the parameters of this node
+    pub fn create_client(&mut self, key: K) -> LocalClient<ParameterRequest, ParameterResponse> {
+        self.server.create_client(key)
+    }
+
+    /// Create a subscriber that receives every change to the parameters of this node
+    pub fn subscribe_updates(&mut self) -> LocalBufferedSubscriber<ParameterUpdate> {
+        self.publisher.subscribe_buffered()
+    }
+
+    /// Get the value of a parameter
+    pub fn get(&self, name: &str) -> Option<&ParameterValue> {
+        self.parameters.get(name)
+    }
+
+    /// Set the value of a parameter, returning the value of the parameter afterwards.
+    ///
+    /// Note: The change is only published if the value of the parameter changed.
+    pub fn set(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<ParameterValue>,
+    ) -> Option<&ParameterValue> {
+        let name = name.into();
+        let value = value.into();
+
+        let changed = match self.parameters.get(&name) {
+            Some(current) => current.same_type(&value) && *current != value,
+            None => true,
+        };
+        if changed {
+            self.parameters.insert(name.clone(), value.clone());
+            let _ = self.publisher.publish(ParameterUpdate {
+                name: name.clone(),
+                value,
+            });
+        }
+
+        self.parameters.get(&name)
+    }
+
+    /// Handle a request, returning the response to the request
+    fn handle_request(&mut self, request: ParameterRequest) -> ParameterResponse {
+        match request {
+            ParameterRequest::Get { name } => ParameterResponse(self.get(&name).cloned()),
+            ParameterRequest::Set { name, value } => {
+                ParameterResponse(self.set(name, value).cloned())
+            }
+        }
+    }
+}
+
+impl<ID: PartialEq + Clone + Send, K: Hash + Eq + Clone + Send> Node<ID>
+    for ParameterServerNode<ID, K>
+{
+    fn get_id(&self) -> ID {
+        self.id.clone()
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.update_delay
+    }
+
+    fn update(&mut self) {
+        for request in self.server.poll_for_requests() {
+            let Ok((key, request)) = request;
+            let response = self.handle_request(request.clone());
+            let _ = self.server.send_response(key, request, response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ncomm_core::{Client, Subscriber};
+
+    #[test]
+    fn test_set_and_get_parameter() {
+        let mut server = ParameterServerNode::new(0u8, 1_000);
+        let mut tuner = server.create_client("tuner".to_string());
+        let mut controller = server.create_client("controller".to_string());
+        let mut updates = server.subscribe_updates();
+        server.set("kp", 1.0);
+
+        tuner
+            .send_request(ParameterRequest::set("kp", 2.5))
+            .unwrap();
+        tuner
+            .send_request(ParameterRequest::set("kp", true))
+            .unwrap();
+        server.update();
+        let responses: Vec<ParameterResponse> = tuner
+            .poll_for_responses()
+            .into_iter()
+            .map(|response| response.unwrap().1)
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                ParameterResponse(Some(ParameterValue::F64(2.5))),
+                ParameterResponse(Some(ParameterValue::F64(2.5))),
+            ]
+        );
+
+        controller
+            .send_request(ParameterRequest::get("kp"))
+            .unwrap();
+        controller
+            .send_request(ParameterRequest::get("ki"))
+            .unwrap();
+        server.update();
+        let responses: Vec<ParameterResponse> = controller
+            .poll_for_responses()
+            .into_iter()
+            .map(|response| response.unwrap().1)
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                ParameterResponse(Some(ParameterValue::F64(2.5))),
+                ParameterResponse(None),
+            ]
+        );
+
+        let updates: Vec<ParameterUpdate> = updates
+            .get()
+            .iter()
+            .map(|update| update.as_ref().clone().unwrap())
+            .collect();
+        assert_eq!(
+            updates,
+            vec![
+                ParameterUpdate {
+                    name: "kp".to_string(),
+                    value: ParameterValue::F64(1.0),
+                },
+                ParameterUpdate {
+                    name: "kp".to_string(),
+                    value: ParameterValue::F64(2.5),
+                },
+            ]
+        );
+    }
+}
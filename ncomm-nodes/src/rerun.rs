@@ -29,7 +29,10 @@ use rerun::{
 };
 
 use ncomm_core::Node;
-use ncomm_publishers_and_subscribers::rerun::{RerunPublisher, RerunTimestampedPublisher};
+use ncomm_publishers_and_subscribers::rerun::{
+    RerunImagePublisher, RerunPoints3DPublisher, RerunPublisher, RerunTimestampedPublisher,
+    RerunTransformPublisher,
+};
 
 /// The Rerun Node.
 ///
@@ -45,6 +48,9 @@ pub struct RerunNode<
     stream: RecordingStream,
     /// The name of the file to save the collected data to
     output_path: Option<Path>,
+    /// The address (and flush timeout) of the remote Rerun server the stream
+    /// should be (re)connected to when the node is started
+    remote: Option<(SocketAddr, Option<Duration>)>,
 }
 
 impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send + 'static>
@@ -62,6 +68,7 @@ impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send
             id,
             stream,
             output_path: None,
+            remote: None,
         })
     }
 
@@ -77,6 +84,7 @@ impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send
             id,
             stream,
             output_path,
+            remote: Some((rerun::default_server_addr(), rerun::default_flush_timeout())),
         })
     }
 
@@ -95,6 +103,7 @@ impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send
             id,
             stream,
             output_path,
+            remote: Some((address, flush_timeout)),
         })
     }
 
@@ -110,6 +119,7 @@ impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send
             id,
             stream,
             output_path,
+            remote: None,
         })
     }
 
@@ -137,9 +147,16 @@ impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send
             id,
             stream,
             output_path,
+            remote: None,
         })
     }
 
+    /// Block until all of the data logged to the Rerun stream has been flushed to
+    /// the stream's sink.
+    pub fn flush(&self) {
+        self.stream.flush_blocking();
+    }
+
     /// Create a publisher for the same Rerun stream referenced by this Node.  This publisher
     /// publishes a data and uses the publishing timestamp as the timestamp for data collection.
     pub fn create_rerun_publisher<LogPath: Into<EntityPath> + Clone, Arch: AsComponents>(
@@ -161,6 +178,33 @@ impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send
     ) -> RerunTimestampedPublisher<LogPath, Arch> {
         RerunTimestampedPublisher::new(self.stream.clone(), path)
     }
+
+    /// Create a publisher for the same Rerun stream referenced by this Node that logs
+    /// published point clouds as `Points3D`.
+    pub fn create_points3d_publisher<LogPath: Into<EntityPath> + Clone>(
+        &mut self,
+        path: LogPath,
+    ) -> RerunPoints3DPublisher<LogPath> {
+        RerunPoints3DPublisher::new(self.stream.clone(), path)
+    }
+
+    /// Create a publisher for the same Rerun stream referenced by this Node that logs
+    /// published RGB camera frames as `Image`s.
+    pub fn create_image_publisher<LogPath: Into<EntityPath> + Clone>(
+        &mut self,
+        path: LogPath,
+    ) -> RerunImagePublisher<LogPath> {
+        RerunImagePublisher::new(self.stream.clone(), path)
+    }
+
+    /// Create a publisher for the same Rerun stream referenced by this Node that logs
+    /// published poses as `Transform3D`s.
+    pub fn create_transform_publisher<LogPath: Into<EntityPath> + Clone>(
+        &mut self,
+        path: LogPath,
+    ) -> RerunTransformPublisher<LogPath> {
+        RerunTransformPublisher::new(self.stream.clone(), path)
+    }
 }
 
 impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send + 'static> Node<Id>
@@ -174,14 +218,117 @@ impl<Id: PartialEq + Clone + Send + 'static, Path: Into<PathBuf> + Clone + Send
         10_000_000
     }
 
+    /// Reconnect to the remote Rerun server (if the node logs to one).
+    ///
+    /// Note: Nodes logging to a file or a spawned / served viewer keep their
+    /// original sink so their recordings aren't redirected to the default server.
     fn start(&mut self) {
-        self.stream.connect();
+        if let Some((address, flush_timeout)) = self.remote {
+            self.stream.connect_opts(address, flush_timeout);
+        }
     }
 
+    /// Flush all of the pending data to the stream's sink (saving to the output
+    /// path if one was given) before disconnecting the stream.
     fn shutdown(&mut self) {
+        self.flush();
         if let Some(path) = self.output_path.as_ref() {
             let _ = self.stream.save(path.clone());
         }
         self.stream.disconnect();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::File;
+
+    use ncomm_core::Publisher;
+    use ncomm_publishers_and_subscribers::rerun::{PointCloud, Pose, RgbImage};
+    use rerun::external::{
+        re_log_encoding::decoder::{Decoder, VersionPolicy},
+        re_log_types::LogMsg,
+    };
+
+    /// Read the entity paths of every chunk logged to an `.rrd` file
+    fn logged_entity_paths(path: &PathBuf) -> Vec<String> {
+        let decoder = Decoder::new(VersionPolicy::Warn, File::open(path).unwrap()).unwrap();
+        decoder
+            .filter_map(Result::ok)
+            .filter_map(|msg| match msg {
+                LogMsg::ArrowMsg(_, arrow_msg) => {
+                    arrow_msg.schema.metadata.get("rerun.entity_path").cloned()
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_typed_publishers() {
+        let path = std::env::temp_dir().join("ncomm_rerun_typed_publishers.rrd");
+        let mut node = RerunNode::new("ncomm_rerun_test", path.clone(), 0u8).unwrap();
+
+        let mut points_publisher = node.create_points3d_publisher("robot/points");
+        let mut image_publisher = node.create_image_publisher("robot/camera");
+        let mut transform_publisher = node.create_transform_publisher("robot/base_link");
+
+        points_publisher
+            .publish(PointCloud {
+                points: vec![[0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [-1.0, 0.5, 2.0]],
+            })
+            .unwrap();
+        image_publisher
+            .publish(RgbImage {
+                width: 2,
+                height: 2,
+                pixels: vec![255; 12],
+            })
+            .unwrap();
+        transform_publisher
+            .publish(Pose {
+                translation: [1.0, 0.0, 0.0],
+                ..Default::default()
+            })
+            .unwrap();
+
+        drop(points_publisher);
+        drop(image_publisher);
+        drop(transform_publisher);
+        node.flush();
+        drop(node);
+
+        let entity_paths = logged_entity_paths(&path);
+        for expected in ["robot/points", "robot/camera", "robot/base_link"] {
+            assert!(entity_paths
+                .iter()
+                .any(|entity_path| entity_path.ends_with(expected)));
+        }
+    }
+
+    #[test]
+    fn test_shutdown_flushes_recording() {
+        let path = std::env::temp_dir().join("ncomm_rerun_shutdown_flush.rrd");
+        let _ = std::fs::remove_file(&path);
+        let mut node = RerunNode::new("ncomm_rerun_test", path.clone(), 0u8).unwrap();
+        let mut publisher = node.create_points3d_publisher("robot/points");
+
+        node.start();
+        for i in 0..10 {
+            publisher
+                .publish(PointCloud {
+                    points: vec![[i as f32, 0.0, 0.0]],
+                })
+                .unwrap();
+            node.update();
+        }
+        node.shutdown();
+
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        assert!(logged_entity_paths(&path)
+            .iter()
+            .any(|entity_path| entity_path.ends_with("robot/points")));
+    }
+}
@@ -20,7 +20,19 @@ pub mod udp;
 #[cfg(feature = "std")]
 pub mod tcp;
 
+#[cfg(feature = "std")]
+pub mod logging;
+
+#[cfg(feature = "std")]
+pub mod multi;
+
 #[cfg(feature = "rerun")]
 pub mod rerun;
 
+#[cfg(feature = "shared-memory")]
+pub mod shm;
+
+#[cfg(feature = "tls")]
+pub mod tls;
+
 pub mod serial;
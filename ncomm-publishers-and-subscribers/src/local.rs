@@ -7,15 +7,16 @@
 //!
 
 use std::{
+    any::Any,
     collections::HashMap,
     hash::Hash,
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
-use crossbeam::channel::{self, Receiver, SendError, Sender};
+use crossbeam::channel::{self, Receiver, Sender, TrySendError};
 
-use ncomm_core::{Publisher, Subscriber};
+use ncomm_core::{ConsumingSubscriber, Publisher, Subscriber};
 
 /// Local Subscriber that utilizes a crossbeam multi subscriber channel
 /// to receive data from a local publisher
@@ -36,6 +37,25 @@ impl<Data> Subscriber for LocalSubscriber<Data> {
 
         self.data.as_ref()
     }
+
+    fn has_update(&mut self) -> bool {
+        !self.rx.is_empty()
+    }
+}
+
+impl<Data: Clone> ConsumingSubscriber for LocalSubscriber<Data> {
+    type Data = Data;
+
+    /// Take the current data from the subscriber.
+    ///
+    /// Note: Data published locally is shared between every subscriber (and the
+    /// publisher) so the data is only moved out of the subscriber when no one else
+    /// holds it and is cloned otherwise.
+    fn take(&mut self) -> Option<Self::Data> {
+        self.get();
+        let data = std::mem::replace(&mut self.data, Arc::new(None));
+        Arc::try_unwrap(data).unwrap_or_else(|data| data.as_ref().clone())
+    }
 }
 
 /// Local Subscriber that stores incoming data into a buffer for processing all at once
@@ -63,6 +83,49 @@ impl<Data> Subscriber for LocalBufferedSubscriber<Data> {
 
         &self.buffer
     }
+
+    fn has_update(&mut self) -> bool {
+        !self.rx.is_empty()
+    }
+}
+
+/// Local Subscriber that stores incoming data, along with the instant the data
+/// was received, into a buffer for processing all at once.
+///
+/// With multiple clones of a publisher sending data concurrently, the timestamps
+/// allow the buffered data to be ordered by the time it was received.
+///
+/// Note: Data is received (and timestamped) when `get` is called so the subscriber
+/// should be polled frequently for accurate timestamps.
+pub struct LocalTimestampedBufferedSubscriber<Data> {
+    /// The receiver end of a crossbeam channel
+    rx: Receiver<Arc<Option<Data>>>,
+    /// The buffer of data (and the instant it was received) stored in the subscriber
+    #[allow(clippy::type_complexity)]
+    buffer: Vec<(Arc<Option<Data>>, Instant)>,
+}
+
+impl<Data> LocalTimestampedBufferedSubscriber<Data> {
+    /// Clear the data buffer
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+impl<Data> Subscriber for LocalTimestampedBufferedSubscriber<Data> {
+    type Target = Vec<(Arc<Option<Data>>, Instant)>;
+
+    fn get(&mut self) -> &Self::Target {
+        for data in self.rx.try_iter() {
+            self.buffer.push((data, Instant::now()))
+        }
+
+        &self.buffer
+    }
+
+    fn has_update(&mut self) -> bool {
+        !self.rx.is_empty()
+    }
 }
 
 /// Local subscriber where data has a specific time-to-live and will decay
@@ -70,12 +133,29 @@ impl<Data> Subscriber for LocalBufferedSubscriber<Data> {
 pub struct LocalTTLSubscriber<Data> {
     /// The receiver end of a crossbeam channel
     rx: Receiver<Arc<Option<Data>>>,
-    /// The current data stored in the local subscriber
+    /// The current data stored in the local subscriber (kept after it expires)
     data: Option<(Arc<Option<Data>>, Instant)>,
     /// The time-to-live of a piece of data
     ttl: Duration,
 }
 
+impl<Data> LocalTTLSubscriber<Data> {
+    /// Get the instant the most recent piece of data was received (as of the
+    /// most recent call to `get`), even if that data has since expired.
+    pub fn last_seen(&self) -> Option<Instant> {
+        self.data.as_ref().map(|(_, received)| *received)
+    }
+
+    /// Check whether the publisher is alive (i.e. whether data was received
+    /// within the time-to-live).
+    ///
+    /// Note: Only data received as of the most recent call to `get` is considered.
+    pub fn is_alive(&self) -> bool {
+        self.last_seen()
+            .is_some_and(|last_seen| last_seen.elapsed() <= self.ttl)
+    }
+}
+
 impl<Data> Subscriber for LocalTTLSubscriber<Data> {
     type Target = Option<(Arc<Option<Data>>, Instant)>;
 
@@ -84,14 +164,22 @@ impl<Data> Subscriber for LocalTTLSubscriber<Data> {
             self.data = Some((data, Instant::now()));
         }
 
-        if self.data.as_ref().is_some()
-            && Instant::now().duration_since(self.data.as_ref().unwrap().1) > self.ttl
+        // Expired data is kept (but not returned) so the instant it was received
+        // is still known
+        if self
+            .data
+            .as_ref()
+            .is_some_and(|(_, received)| received.elapsed() > self.ttl)
         {
-            self.data = None;
+            return &None;
         }
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        !self.rx.is_empty()
+    }
 }
 
 /// Local subscriber that maps incoming data to into a location in a hashmap
@@ -118,6 +206,10 @@ impl<Data, K: Eq + Hash, F: Fn(&Option<Data>) -> K> Subscriber
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        !self.rx.is_empty()
+    }
 }
 
 /// Local subscriber that maps incoming data to into a location in a hashmap
@@ -150,18 +242,272 @@ impl<Data, K: Eq + Hash, F: Fn(&Option<Data>) -> K> Subscriber
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        !self.rx.is_empty()
+    }
+}
+
+/// Local subscriber that invokes a callback on each piece of data it receives
+/// instead of storing the data.
+///
+/// Note: The callback is only invoked when `pump` is called so `pump` should be
+/// called periodically (i.e. in a Node's update method).
+pub struct LocalCallbackSubscriber<Data, F: FnMut(&Data)> {
+    /// The receiver end of a crossbeam channel
+    rx: Receiver<Arc<Option<Data>>>,
+    /// The callback invoked on each piece of received data
+    callback: F,
+}
+
+impl<Data, F: FnMut(&Data)> LocalCallbackSubscriber<Data, F> {
+    /// Drain all of the pending data, invoking the callback on each piece of
+    /// data in the order it was published
+    pub fn pump(&mut self) {
+        for data in self.rx.try_iter() {
+            if let Some(data) = data.as_ref() {
+                (self.callback)(data);
+            }
+        }
+    }
+
+    /// Check whether there is pending data waiting to be pumped
+    pub fn has_update(&self) -> bool {
+        !self.rx.is_empty()
+    }
+}
+
+/// Subscriber that receives data from a plain crossbeam channel (i.e. one created
+/// outside of NComm) instead of from a local publisher, keeping the most recently
+/// received data.
+pub struct ChannelSubscriber<Data> {
+    /// The receiver end of the crossbeam channel
+    rx: Receiver<Data>,
+    /// The current data stored in the channel subscriber
+    data: Option<Data>,
+}
+
+impl<Data> ChannelSubscriber<Data> {
+    /// Create a new Channel Subscriber receiving data from a crossbeam channel
+    pub fn new(rx: Receiver<Data>) -> Self {
+        Self { rx, data: None }
+    }
+
+    /// Destroy the Channel Subscriber, returning the receiver end of its channel
+    pub fn into_inner(self) -> Receiver<Data> {
+        self.rx
+    }
+}
+
+impl<Data> From<Receiver<Data>> for ChannelSubscriber<Data> {
+    fn from(rx: Receiver<Data>) -> Self {
+        Self::new(rx)
+    }
+}
+
+impl<Data> Subscriber for ChannelSubscriber<Data> {
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        if let Some(data) = self.rx.try_iter().last() {
+            self.data = Some(data);
+        }
+
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        !self.rx.is_empty()
+    }
+}
+
+impl<Data> ConsumingSubscriber for ChannelSubscriber<Data> {
+    type Data = Data;
+
+    fn take(&mut self) -> Option<Self::Data> {
+        self.get();
+        self.data.take()
+    }
+}
+
+/// Local subscriber whose data is awaited (i.e. in a `tokio::select!`) instead of
+/// polled.
+///
+/// Data from the wrapped local subscriber is forwarded to the async subscriber
+/// by a bridge thread that blocks on the subscriber's channel.
+///
+/// Note: The bridge thread exits once every publisher is dropped or, after the
+/// async subscriber is dropped, once the next piece of data is published.
+#[cfg(feature = "tokio")]
+pub struct AsyncSubscriber<Data> {
+    /// The receiver end of the tokio channel the bridge thread forwards data to
+    rx: tokio::sync::mpsc::UnboundedReceiver<Arc<Option<Data>>>,
+}
+
+#[cfg(feature = "tokio")]
+impl<Data: Send + Sync + 'static> AsyncSubscriber<Data> {
+    /// Create a new async subscriber from a local subscriber.
+    ///
+    /// Note: If the local subscriber already holds data, that data is the first
+    /// data received by the async subscriber.
+    pub fn new(subscriber: LocalSubscriber<Data>) -> Self {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let LocalSubscriber { rx: local_rx, data } = subscriber;
+
+        std::thread::spawn(move || {
+            if data.is_some() && tx.send(data).is_err() {
+                return;
+            }
+            for data in local_rx.iter() {
+                if tx.send(data).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { rx }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<Data: Clone> AsyncSubscriber<Data> {
+    /// Wait for the next piece of published data.
+    ///
+    /// Note: None is returned once every publisher has been dropped and all of
+    /// the published data has been received.
+    pub async fn recv(&mut self) -> Option<Data> {
+        while let Some(data) = self.rx.recv().await {
+            if let Some(data) = Arc::try_unwrap(data).unwrap_or_else(|data| data.as_ref().clone()) {
+                return Some(data);
+            }
+        }
+        None
+    }
+}
+
+/// Subscriber that joins the latest data from a tuple of (2 to 6) local subscribers.
+///
+/// The SyncSubscriber's data is a tuple of the latest data from each of the inner
+/// subscribers and is only `Some` once every inner subscriber has received data.
+///
+/// ```
+/// use ncomm_core::{Publisher, Subscriber};
+/// use ncomm_publishers_and_subscribers::local::{LocalPublisher, SyncSubscriber};
+///
+/// let mut imu = LocalPublisher::new();
+/// let mut gps = LocalPublisher::new();
+/// let mut fused = SyncSubscriber::new((imu.subscribe(), gps.subscribe()));
+///
+/// imu.publish(0.5f32).unwrap();
+/// assert!(fused.get().is_none());
+/// gps.publish((42.0f64, -71.0f64)).unwrap();
+/// assert_eq!(*fused.get(), Some((0.5, (42.0, -71.0))));
+/// ```
+pub struct SyncSubscriber<Subscribers, Data> {
+    /// The inner subscribers
+    subscribers: Subscribers,
+    /// The latest data from every inner subscriber
+    data: Option<Data>,
+}
+
+impl<Subscribers, Data> SyncSubscriber<Subscribers, Data> {
+    /// Create a new SyncSubscriber joining the data from a tuple of local subscribers
+    pub fn new(subscribers: Subscribers) -> Self {
+        Self {
+            subscribers,
+            data: None,
+        }
+    }
+}
+
+/// Implement the SyncSubscriber for a tuple of local subscribers
+macro_rules! sync_subscriber {
+    ($($data: ident $subscriber: ident),+) => {
+        impl<$($data: Clone),+> Subscriber
+            for SyncSubscriber<($(LocalSubscriber<$data>,)+), ($($data,)+)>
+        {
+            type Target = Option<($($data,)+)>;
+
+            fn get(&mut self) -> &Self::Target {
+                let ($($subscriber,)+) = &mut self.subscribers;
+                if let ($(Some($subscriber),)+) = ($($subscriber.get(),)+) {
+                    self.data = Some(($($subscriber.clone(),)+));
+                }
+
+                &self.data
+            }
+
+            fn has_update(&mut self) -> bool {
+                let ($($subscriber,)+) = &mut self.subscribers;
+                $($subscriber.has_update())|+
+            }
+        }
+    };
+}
+
+sync_subscriber!(A a, B b);
+sync_subscriber!(A a, B b, C c);
+sync_subscriber!(A a, B b, C c, D d);
+sync_subscriber!(A a, B b, C c, D d, E e);
+sync_subscriber!(A a, B b, C c, D d, E e, F f);
+
+/// The OverflowPolicy determines how a bounded local publisher reacts to a
+/// subscriber's queue being full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Publishing returns a `TrySendError::Full` error and the new piece of
+    /// data is not queued for the full subscriber.
+    #[default]
+    Reject,
+    /// The oldest piece of data in the full subscriber's queue is dropped to
+    /// make room for the new piece of data.
+    DropOldest,
+}
+
+/// The sending end of the channel between a local publisher and one of its
+/// subscribers
+struct LocalSender<Data> {
+    /// The transmit pipe that is used to send data to the subscriber
+    tx: Sender<Arc<Option<Data>>>,
+    /// A handle to the receiving end of a bounded channel used to drop the
+    /// oldest piece of data when the channel is full
+    oldest: Option<Receiver<Arc<Option<Data>>>>,
+}
+
+impl<Data> LocalSender<Data> {
+    /// Send a piece of data to the subscriber, dropping the oldest piece of
+    /// data in the subscriber's queue if it is full and the overflow policy allows it
+    fn send(&self, data: Arc<Option<Data>>) -> Result<(), TrySendError<Arc<Option<Data>>>> {
+        match self.tx.try_send(data) {
+            Err(TrySendError::Full(data)) => match self.oldest.as_ref() {
+                Some(oldest) => {
+                    let _ = oldest.try_recv();
+                    self.tx.try_send(data)
+                }
+                None => Err(TrySendError::Full(data)),
+            },
+            result => result,
+        }
+    }
 }
 
 /// Local Publisher that utilizes a crossbeam multi publisher multi
 /// subscriber to send data
+///
+/// By default, each subscriber's queue is unbounded.  A publisher created with
+/// `new_bounded` (or a subscriber created with `subscribe_bounded`) instead uses a
+/// bounded queue whose overflow behavior is determined by an `OverflowPolicy`.
 pub struct LocalPublisher<Data> {
     /// The transmit pipe that is used to send data to the subscriber
-    #[allow(clippy::type_complexity)]
-    txs: Arc<Mutex<Vec<Sender<Arc<Option<Data>>>>>>,
+    txs: Arc<Mutex<Vec<LocalSender<Data>>>>,
     /// The most recent data sent over the tx pipes so new subscribers will
     /// automatically have the most recent data
     #[allow(clippy::type_complexity)]
     data: Arc<Mutex<Option<(Arc<Option<Data>>, Instant)>>>,
+    /// The capacity of each subscriber's queue (None for unbounded queues)
+    capacity: Option<usize>,
+    /// How the publisher should handle a bounded subscriber's queue being full
+    overflow: OverflowPolicy,
 }
 
 impl<Data> Default for LocalPublisher<Data> {
@@ -169,6 +515,8 @@ impl<Data> Default for LocalPublisher<Data> {
         Self {
             txs: Arc::new(Mutex::new(Vec::new())),
             data: Arc::new(Mutex::new(None)),
+            capacity: None,
+            overflow: OverflowPolicy::default(),
         }
     }
 }
@@ -179,11 +527,62 @@ impl<Data> LocalPublisher<Data> {
         Self::default()
     }
 
+    /// Create a new local publisher whose subscribers each queue at most `capacity`
+    /// pieces of data, handling full queues according to the given overflow policy.
+    ///
+    /// Note: With the `DropOldest` policy the publisher holds onto the receiving end
+    /// of each subscriber's queue so dropped subscribers are never pruned.
+    pub fn new_bounded(capacity: usize, overflow: OverflowPolicy) -> Self {
+        Self {
+            capacity: Some(capacity),
+            overflow,
+            ..Self::default()
+        }
+    }
+
+    /// Get the number of subscribers the publisher is sending data to.
+    ///
+    /// Note: Dropped subscribers are only pruned when data is published so the count
+    /// may include subscribers that have been dropped since the last publish.
+    pub fn subscriber_count(&self) -> usize {
+        self.txs.lock().unwrap().len()
+    }
+
+    /// Create a channel to a new subscriber with a given capacity (None for an
+    /// unbounded channel), returning the receiving end of the channel.
+    fn register(&mut self, capacity: Option<usize>) -> Receiver<Arc<Option<Data>>> {
+        let (tx, rx) = match capacity {
+            Some(capacity) => channel::bounded(capacity),
+            None => channel::unbounded(),
+        };
+        let oldest = match (capacity, self.overflow) {
+            (Some(_), OverflowPolicy::DropOldest) => Some(rx.clone()),
+            _ => None,
+        };
+        self.txs.lock().unwrap().push(LocalSender { tx, oldest });
+        rx
+    }
+
+    /// Create a local subscriber whose queue holds at most `capacity` pieces of data,
+    /// regardless of whether the publisher itself is bounded.
+    ///
+    /// Full queues are handled according to the publisher's overflow policy.
+    pub fn subscribe_bounded(&mut self, capacity: usize) -> LocalSubscriber<Data> {
+        let rx = self.register(Some(capacity));
+        let data = self
+            .data
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|data| data.0.clone())
+            .unwrap_or_else(|| Arc::new(None));
+
+        LocalSubscriber { rx, data }
+    }
+
     /// Create a local subscriber
     pub fn subscribe(&mut self) -> LocalSubscriber<Data> {
-        let mut txs = self.txs.lock().unwrap();
-        let (tx, rx) = channel::unbounded();
-        txs.push(tx);
+        let rx = self.register(self.capacity);
 
         let data = self
             .data
@@ -204,9 +603,7 @@ impl<Data> LocalPublisher<Data> {
 
     /// Create a local buffered subscriber
     pub fn subscribe_buffered(&mut self) -> LocalBufferedSubscriber<Data> {
-        let mut txs = self.txs.lock().unwrap();
-        let (tx, rx) = channel::unbounded();
-        txs.push(tx);
+        let rx = self.register(self.capacity);
 
         let mut buffer = Vec::new();
         if let Some(data) = self.data.lock().unwrap().as_ref() {
@@ -216,11 +613,24 @@ impl<Data> LocalPublisher<Data> {
         LocalBufferedSubscriber { rx, buffer }
     }
 
+    /// Create a local buffered subscriber that timestamps the data it receives
+    ///
+    /// Note: The most recent data (if any) is timestamped with the instant it was
+    /// published.
+    pub fn subscribe_timestamped_buffered(&mut self) -> LocalTimestampedBufferedSubscriber<Data> {
+        let rx = self.register(self.capacity);
+
+        let mut buffer = Vec::new();
+        if let Some(data) = self.data.lock().unwrap().as_ref() {
+            buffer.push(data.clone());
+        }
+
+        LocalTimestampedBufferedSubscriber { rx, buffer }
+    }
+
     /// Create a local subscriber with a specific time-to-live of pieces of data
     pub fn subscribe_ttl(&mut self, timeout: Duration) -> LocalTTLSubscriber<Data> {
-        let mut txs = self.txs.lock().unwrap();
-        let (tx, rx) = channel::unbounded();
-        txs.push(tx);
+        let rx = self.register(self.capacity);
 
         let data = match self.data.lock().unwrap().as_ref() {
             Some(data) => {
@@ -248,9 +658,7 @@ impl<Data> LocalPublisher<Data> {
         &mut self,
         map: F,
     ) -> LocalMappedSubscriber<Data, K, F> {
-        let mut txs = self.txs.lock().unwrap();
-        let (tx, rx) = channel::unbounded();
-        txs.push(tx);
+        let rx = self.register(self.capacity);
 
         let mut hashmap = HashMap::new();
         if let Some(data) = self.data.lock().unwrap().as_ref() {
@@ -276,9 +684,7 @@ impl<Data> LocalPublisher<Data> {
         map: F,
         ttl: Duration,
     ) -> LocalMappedTTLSubscriber<Data, K, F> {
-        let mut txs = self.txs.lock().unwrap();
-        let (tx, rx) = channel::unbounded();
-        txs.push(tx);
+        let rx = self.register(self.capacity);
 
         let mut hashmap = HashMap::new();
         if let Some(data) = self.data.lock().unwrap().as_ref() {
@@ -296,6 +702,26 @@ impl<Data> LocalPublisher<Data> {
             ttl,
         }
     }
+
+    /// Create a local subscriber whose data is awaited instead of polled
+    #[cfg(feature = "tokio")]
+    pub fn subscribe_async(&mut self) -> AsyncSubscriber<Data>
+    where
+        Data: Send + Sync + 'static,
+    {
+        AsyncSubscriber::new(self.subscribe())
+    }
+
+    /// Create a local subscriber that invokes a callback on every piece of data
+    /// published after the subscriber was created
+    pub fn subscribe_callback<F: FnMut(&Data)>(
+        &mut self,
+        callback: F,
+    ) -> LocalCallbackSubscriber<Data, F> {
+        let rx = self.register(self.capacity);
+
+        LocalCallbackSubscriber { rx, callback }
+    }
 }
 
 impl<Data> Clone for LocalPublisher<Data> {
@@ -303,23 +729,91 @@ impl<Data> Clone for LocalPublisher<Data> {
         Self {
             txs: self.txs.clone(),
             data: self.data.clone(),
+            capacity: self.capacity,
+            overflow: self.overflow,
         }
     }
 }
 
 impl<Data> Publisher for LocalPublisher<Data> {
     type Data = Data;
-    type Error = SendError<Arc<Option<Data>>>;
+    type Error = TrySendError<Arc<Option<Data>>>;
 
+    /// Send the data to every subscriber, pruning subscribers that have been dropped.
+    ///
+    /// Note: If a subscriber's bounded queue is full the data is still sent to the
+    /// remaining subscribers and the first error encountered is returned.
     fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
         let data = Arc::new(Some(data));
-        let txs = self.txs.lock().unwrap();
-        for tx in txs.iter() {
-            tx.send(data.clone())?;
-        }
+        let mut result = Ok(());
+        let mut txs = self.txs.lock().unwrap();
+        txs.retain(|tx| match tx.send(data.clone()) {
+            Err(TrySendError::Disconnected(_)) => false,
+            Err(err) => {
+                if result.is_ok() {
+                    result = Err(err);
+                }
+                true
+            }
+            Ok(()) => true,
+        });
         let mut data_ref = self.data.lock().unwrap();
         *data_ref = Some((data, Instant::now()));
-        Ok(())
+        result
+    }
+}
+
+/// A node that can be constructed from a set of named local topics, declaring
+/// the topics it publishes and subscribes to instead of being handed its
+/// publishers and subscribers.
+pub trait FromTopics {
+    /// Create the node, taking its publishers and subscribers from the topics
+    fn from_topics(topics: &mut LocalTopics) -> Self;
+}
+
+/// A registry of local publishers keyed by the name of the topic they publish.
+///
+/// Every publisher and subscriber taken from the registry for a topic shares
+/// the same underlying local publisher, so nodes can be wired together by topic
+/// name (i.e. via `FromTopics`) without passing publishers between them.
+#[derive(Default)]
+pub struct LocalTopics {
+    /// The local publisher of each topic (as a `LocalPublisher<Data>`)
+    topics: HashMap<String, Box<dyn Any>>,
+}
+
+impl LocalTopics {
+    /// Create an empty registry of topics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a publisher for a topic, creating the topic if it doesn't exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the topic already exists with a different type of data.
+    pub fn publisher<Data: 'static>(&mut self, topic: &str) -> LocalPublisher<Data> {
+        self.topics
+            .entry(topic.to_string())
+            .or_insert_with(|| Box::new(LocalPublisher::<Data>::new()))
+            .downcast_ref::<LocalPublisher<Data>>()
+            .unwrap_or_else(|| panic!("topic \"{}\" has a different type of data", topic))
+            .clone()
+    }
+
+    /// Create a local subscriber to a topic, creating the topic if it doesn't exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the topic already exists with a different type of data.
+    pub fn subscribe<Data: 'static>(&mut self, topic: &str) -> LocalSubscriber<Data> {
+        self.publisher(topic).subscribe()
+    }
+
+    /// Construct a node whose publishers and subscribers are wired from these topics
+    pub fn build<N: FromTopics>(&mut self) -> N {
+        N::from_topics(self)
     }
 }
 
@@ -350,6 +844,18 @@ mod tests {
         assert_eq!(subscriber.get().unwrap(), data);
     }
 
+    #[test]
+    fn test_take_local_subscriber() {
+        let mut publisher = LocalPublisher::new();
+        let mut subscriber = publisher.subscribe();
+
+        let data = TestData::new();
+        publisher.publish(data).unwrap();
+        assert_eq!(subscriber.take(), Some(data));
+        assert_eq!(subscriber.take(), None);
+        assert_eq!(*subscriber.get(), None);
+    }
+
     #[test]
     fn test_publish_buffered_subscriber() {
         let mut publisher = LocalPublisher::new();
@@ -438,4 +944,273 @@ mod tests {
             data
         );
     }
+
+    #[test]
+    fn test_local_subscriber_has_update() {
+        let mut publisher = LocalPublisher::new();
+        let mut subscriber = publisher.subscribe();
+        let mut buffered_subscriber = publisher.subscribe_buffered();
+        assert!(!subscriber.has_update());
+        assert!(!buffered_subscriber.has_update());
+
+        let data = TestData::new();
+        publisher.publish(data).unwrap();
+        assert!(subscriber.has_update());
+        assert!(buffered_subscriber.has_update());
+
+        assert_eq!(subscriber.get().unwrap(), data);
+        assert_eq!(buffered_subscriber.get().len(), 1);
+        assert!(!subscriber.has_update());
+        assert!(!buffered_subscriber.has_update());
+    }
+
+    #[test]
+    fn test_publish_callback_subscriber() {
+        let mut publisher = LocalPublisher::new();
+        let mut received = Vec::new();
+        let mut subscriber = publisher.subscribe_callback(|data: &TestData| received.push(*data));
+
+        let datas: Vec<TestData> = (0..50).map(|_| TestData::new()).collect();
+        for data in datas.iter() {
+            publisher.publish(*data).unwrap();
+        }
+        assert!(subscriber.has_update());
+
+        subscriber.pump();
+        assert!(!subscriber.has_update());
+        drop(subscriber);
+
+        assert_eq!(received, datas);
+    }
+
+    #[test]
+    fn test_publish_bounded_reject() {
+        let mut publisher = LocalPublisher::new_bounded(4, OverflowPolicy::Reject);
+        let mut subscriber = publisher.subscribe_buffered();
+
+        for num in 0..4 {
+            publisher.publish(TestData { num }).unwrap();
+        }
+        assert!(matches!(
+            publisher.publish(TestData { num: 4 }),
+            Err(TrySendError::Full(_))
+        ));
+
+        let received: Vec<u64> = subscriber
+            .get()
+            .iter()
+            .map(|data| data.unwrap().num)
+            .collect();
+        assert_eq!(received, vec![0, 1, 2, 3]);
+
+        // Draining the queue makes room for new data
+        publisher.publish(TestData { num: 5 }).unwrap();
+        assert_eq!(subscriber.get().last().unwrap().unwrap().num, 5);
+    }
+
+    #[test]
+    fn test_publish_bounded_drop_oldest() {
+        let mut publisher = LocalPublisher::new_bounded(4, OverflowPolicy::DropOldest);
+        let mut subscriber = publisher.subscribe_buffered();
+
+        for num in 0..10 {
+            publisher.publish(TestData { num }).unwrap();
+        }
+
+        let received: Vec<u64> = subscriber
+            .get()
+            .iter()
+            .map(|data| data.unwrap().num)
+            .collect();
+        assert_eq!(received, vec![6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_subscribe_bounded() {
+        let mut publisher = LocalPublisher::new();
+        let mut unbounded_subscriber = publisher.subscribe_buffered();
+        let mut bounded_subscriber = publisher.subscribe_bounded(2);
+
+        publisher.publish(TestData { num: 0 }).unwrap();
+        publisher.publish(TestData { num: 1 }).unwrap();
+        assert!(publisher.publish(TestData { num: 2 }).is_err());
+
+        // The unbounded subscriber still receives data the bounded subscriber rejects
+        assert_eq!(unbounded_subscriber.get().len(), 3);
+        assert_eq!(bounded_subscriber.get().unwrap().num, 1);
+    }
+
+    #[test]
+    fn test_publish_dropped_subscriber() {
+        let mut publisher = LocalPublisher::new();
+        let subscriber = publisher.subscribe();
+        let mut buffered_subscriber = publisher.subscribe_buffered();
+        assert_eq!(publisher.subscriber_count(), 2);
+
+        drop(subscriber);
+        publisher.publish(TestData::new()).unwrap();
+        assert_eq!(publisher.subscriber_count(), 1);
+        assert_eq!(buffered_subscriber.get().len(), 1);
+
+        drop(buffered_subscriber);
+        publisher.publish(TestData::new()).unwrap();
+        publisher.publish(TestData::new()).unwrap();
+        assert_eq!(publisher.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_sync_subscriber() {
+        let mut publisher_a = LocalPublisher::new();
+        let mut publisher_b = LocalPublisher::new();
+        let mut subscriber =
+            SyncSubscriber::new((publisher_a.subscribe(), publisher_b.subscribe()));
+        assert!(subscriber.get().is_none());
+
+        // Nothing is yielded until every publisher has published
+        let data_a = TestData::new();
+        publisher_a.publish(data_a).unwrap();
+        assert!(subscriber.has_update());
+        assert!(subscriber.get().is_none());
+
+        let data_b = TestData::new();
+        publisher_b.publish(data_b).unwrap();
+        assert_eq!(*subscriber.get(), Some((data_a, data_b)));
+        assert!(!subscriber.has_update());
+
+        // Afterwards, the latest data from each publisher is joined
+        let data_a = TestData::new();
+        publisher_a.publish(TestData::new()).unwrap();
+        publisher_a.publish(data_a).unwrap();
+        assert_eq!(*subscriber.get(), Some((data_a, data_b)));
+    }
+
+    struct Doubler {
+        input: LocalSubscriber<u64>,
+        output: LocalPublisher<u64>,
+    }
+
+    impl FromTopics for Doubler {
+        fn from_topics(topics: &mut LocalTopics) -> Self {
+            Self {
+                input: topics.subscribe("input"),
+                output: topics.publisher("output"),
+            }
+        }
+    }
+
+    impl Doubler {
+        fn update(&mut self) {
+            if let Some(value) = *self.input.get() {
+                self.output.publish(value * 2).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_node_from_topics() {
+        let mut topics = LocalTopics::new();
+        let mut doubler: Doubler = topics.build();
+        let mut input = topics.publisher::<u64>("input");
+        let mut output = topics.subscribe::<u64>("output");
+
+        input.publish(21).unwrap();
+        doubler.update();
+        assert_eq!(*output.get(), Some(42));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_topic_type_mismatch() {
+        let mut topics = LocalTopics::new();
+        let _ = topics.publisher::<u64>("topic");
+        let _ = topics.subscribe::<String>("topic");
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_async_subscriber() {
+        let mut publisher = LocalPublisher::new();
+        let mut subscriber = publisher.subscribe_async();
+        let data = TestData::new();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let received = runtime.block_on(async move {
+            let publish = tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                publisher.publish(data).unwrap();
+            });
+            let received = tokio::select! {
+                received = subscriber.recv() => received,
+                _ = tokio::time::sleep(Duration::from_secs(1)) => None,
+            };
+            publish.await.unwrap();
+
+            // Once the publisher is dropped the subscriber stops receiving data
+            (received, subscriber.recv().await)
+        });
+        assert_eq!(received, (Some(data), None));
+    }
+
+    #[test]
+    fn test_timestamped_buffered_subscriber_concurrent_publishers() {
+        let mut publisher = LocalPublisher::new();
+        publisher.publish((0u8, 0u32)).unwrap();
+        let mut subscriber = publisher.subscribe_timestamped_buffered();
+
+        let handles: Vec<_> = (1..=2u8)
+            .map(|id| {
+                let mut publisher = publisher.clone();
+                std::thread::spawn(move || {
+                    for seq in 0..100u32 {
+                        publisher.publish((id, seq)).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        let mut received = Vec::new();
+        for handle in handles {
+            while !handle.is_finished() {
+                received.extend(subscriber.get().iter().cloned());
+                subscriber.clear();
+            }
+            handle.join().unwrap();
+        }
+        received.extend(subscriber.get().iter().cloned());
+
+        received.sort_by_key(|(_, timestamp)| *timestamp);
+        assert_eq!(received.len(), 201);
+        assert_eq!(*received[0].0, Some((0, 0)));
+        // Sorting by timestamp keeps the order each publisher published its data in
+        for id in 1..=2u8 {
+            let sequence: Vec<u32> = received
+                .iter()
+                .filter_map(|(data, _)| data.filter(|(data_id, _)| *data_id == id))
+                .map(|(_, seq)| seq)
+                .collect();
+            assert_eq!(sequence, (0..100).collect::<Vec<u32>>());
+        }
+    }
+
+    #[test]
+    fn test_channel_subscriber() {
+        let (tx, rx) = channel::unbounded();
+        let mut subscriber = ChannelSubscriber::new(rx);
+        assert_eq!(*subscriber.get(), None);
+
+        tx.send(TestData { num: 1 }).unwrap();
+        tx.send(TestData { num: 2 }).unwrap();
+        assert!(subscriber.has_update());
+        assert_eq!(*subscriber.get(), Some(TestData { num: 2 }));
+        assert!(!subscriber.has_update());
+
+        // The most recent data is kept until newer data is received
+        assert_eq!(*subscriber.get(), Some(TestData { num: 2 }));
+        tx.send(TestData { num: 3 }).unwrap();
+        assert_eq!(subscriber.take(), Some(TestData { num: 3 }));
+        assert_eq!(*subscriber.get(), None);
+    }
 }
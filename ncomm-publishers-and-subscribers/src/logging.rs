@@ -0,0 +1,431 @@
+//!
+//! A Logging Publisher and Replay Publisher
+//!
+//! The Logging Publisher wraps another publisher, forwarding each piece of data
+//! to the inner publisher while recording the packed data (and the time it was
+//! published) to a log file.  The Replay Publisher is a Node that reads a log
+//! file and re-publishes the recorded data with the timing it was recorded with.
+//!
+//! Each piece of data is recorded in the log file as a length-prefixed frame:
+//! ```text
+//! | timestamp (u64, us) | data length (u32) | packed data |
+//! ```
+//! where the timestamp is the time since the Logging Publisher was created and
+//! both integers are little-endian.
+//!
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Seek, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use ncomm_core::{Node, Publisher};
+use ncomm_utils::packing::{Packable, PackingError};
+
+/// The length of the header at the start of each frame (in bytes)
+const FRAME_HEADER_LEN: usize = 12;
+
+/// The longest frame a Log Reader will read (in bytes) so a corrupt length can't
+/// make the reader allocate an arbitrary amount of memory
+const MAX_FRAME_LEN: usize = 1 << 24;
+
+/// An Error when attempting to publish data over a Logging Publisher
+#[derive(Debug)]
+pub enum LoggingPublishError<E> {
+    /// std::io::Error occurred while recording the data
+    IOError(Error),
+    /// An error occurred with packing the data
+    PackingError(PackingError),
+    /// The inner publisher failed to publish the data
+    PublishError(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for LoggingPublishError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IOError(err) => write!(f, "io error: {}", err),
+            Self::PackingError(err) => write!(f, "packing error: {}", err),
+            Self::PublishError(err) => write!(f, "publish error: {}", err),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for LoggingPublishError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(err) => Some(err),
+            Self::PackingError(err) => Some(err),
+            Self::PublishError(err) => Some(err),
+        }
+    }
+}
+
+/// A publisher that forwards data to an inner publisher while recording the
+/// packed data (and when it was published) to a log file for replay
+pub struct LoggingPublisher<P: Publisher>
+where
+    P::Data: Packable + Clone,
+{
+    /// The publisher data is forwarded to
+    inner: P,
+    /// The log file frames are written to
+    file: BufWriter<File>,
+    /// The time the recording was started
+    start: Instant,
+    /// The buffer frames are packed into before being written (reused across publishes)
+    buffer: Vec<u8>,
+}
+
+impl<P: Publisher> LoggingPublisher<P>
+where
+    P::Data: Packable + Clone,
+{
+    /// Create a new LoggingPublisher that creates (or truncates) the log file at
+    /// `path` and forwards data to an inner publisher
+    pub fn new(inner: P, path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self {
+            inner,
+            file: BufWriter::new(File::create(path)?),
+            start: Instant::now(),
+            buffer: Vec::with_capacity(FRAME_HEADER_LEN + P::Data::len()),
+        })
+    }
+
+    /// Get a reference to the inner publisher
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner publisher (i.e. to add subscribers)
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.inner
+    }
+
+    /// Record a piece of data in the log file
+    fn record(&mut self, data: P::Data) -> Result<(), LoggingPublishError<P::Error>> {
+        let timestamp = self.start.elapsed().as_micros() as u64;
+        let len = P::Data::len();
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&timestamp.to_le_bytes());
+        self.buffer.extend_from_slice(&(len as u32).to_le_bytes());
+        self.buffer.resize(FRAME_HEADER_LEN + len, 0);
+        data.pack(&mut self.buffer[FRAME_HEADER_LEN..])
+            .map_err(LoggingPublishError::PackingError)?;
+
+        self.file
+            .write_all(&self.buffer)
+            .and_then(|_| self.file.flush())
+            .map_err(LoggingPublishError::IOError)
+    }
+}
+
+impl<P: Publisher> Publisher for LoggingPublisher<P>
+where
+    P::Data: Packable + Clone,
+{
+    type Data = P::Data;
+    type Error = LoggingPublishError<P::Error>;
+
+    /// Record the data and then forward it to the inner publisher.
+    ///
+    /// Note: Data that fails to be recorded is not forwarded.
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        self.record(data.clone())?;
+        self.inner
+            .publish(data)
+            .map_err(LoggingPublishError::PublishError)
+    }
+}
+
+/// A reader for the frames recorded by a Logging Publisher
+pub struct LogReader {
+    /// The log file frames are read from
+    file: BufReader<File>,
+}
+
+impl LogReader {
+    /// Open the log file at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self {
+            file: BufReader::new(File::open(path)?),
+        })
+    }
+
+    /// Read the header of the next frame from the log file, returning the time the
+    /// frame was recorded at and the length of its packed data (or None at the end
+    /// of the log file)
+    fn next_header(&mut self) -> Result<Option<(Duration, usize)>, Error> {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        match self.file.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+
+        let timestamp = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let len = u32::from_le_bytes(header[8..].try_into().unwrap());
+        Ok(Some((Duration::from_micros(timestamp), len as usize)))
+    }
+
+    /// Read the next frame from the log file, returning the time the frame was
+    /// recorded at and the packed data (or None at the end of the log file)
+    ///
+    /// Note: Frames longer than 16 MiB are rejected as corrupt.
+    pub fn next_frame(&mut self) -> Result<Option<(Duration, Vec<u8>)>, Error> {
+        let Some((timestamp, len)) = self.next_header()? else {
+            return Ok(None);
+        };
+        if len > MAX_FRAME_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("frame length {len} exceeds the maximum frame length"),
+            ));
+        }
+
+        let mut data = vec![0u8; len];
+        self.file.read_exact(&mut data)?;
+        Ok(Some((timestamp, data)))
+    }
+
+    /// Return to the start of the log file so the frames can be read again
+    pub fn rewind(&mut self) -> Result<(), Error> {
+        self.file.rewind()
+    }
+
+    /// Read the next frame from the log file, unpacking its data
+    ///
+    /// Note: A frame whose length isn't the packed length of the data is rejected
+    /// before any of its data is read.
+    pub fn next_data<Data: Packable>(&mut self) -> Result<Option<(Duration, Data)>, Error> {
+        let Some((timestamp, len)) = self.next_header()? else {
+            return Ok(None);
+        };
+        if len != Data::len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "frame length {len} doesn't match the data length {}",
+                    Data::len()
+                ),
+            ));
+        }
+
+        let mut data = vec![0u8; len];
+        self.file.read_exact(&mut data)?;
+        let data = Data::unpack(&data)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, format!("{err:?}")))?;
+        Ok(Some((timestamp, data)))
+    }
+}
+
+/// A Node that re-publishes the data recorded by a Logging Publisher, preserving
+/// the time between each piece of data.
+///
+/// The time of the first update of the node corresponds to the time the recording
+/// was started.  Every update, the node publishes all of the recorded data that
+/// is due.  Once the whole log file has been replayed, the node does nothing
+/// (unless it is looping, in which case the log file is replayed again).
+///
+/// Note: Data can only be replayed as precisely as the node's update delay.
+///
+/// Addendum: The recorded data can be replayed faster (or slower) than it was
+/// recorded via a playback speed multiplier (see `set_speed`).
+pub struct ReplayPublisher<ID: PartialEq + Clone + Send, P: Publisher + Send>
+where
+    P::Data: Packable + Send,
+{
+    /// The log file being replayed
+    reader: LogReader,
+    /// The publisher the recorded data is re-published with
+    publisher: P,
+    /// The next recorded piece of data to publish
+    next: Option<(Duration, P::Data)>,
+    /// The (wall clock) time the current playback was started
+    start: Option<Instant>,
+    /// The playback speed multiplier (i.e. 2.0 replays data twice as fast as it
+    /// was recorded)
+    speed: f64,
+    /// Whether the log file is replayed from the beginning once it ends
+    looping: bool,
+    /// The delay (in microseconds) between updates of the node
+    update_delay: u128,
+    /// The id of the node
+    id: ID,
+}
+
+impl<ID: PartialEq + Clone + Send, P: Publisher + Send> ReplayPublisher<ID, P>
+where
+    P::Data: Packable + Send,
+{
+    /// Create a new ReplayPublisher that replays the log file at `path` through
+    /// a publisher
+    pub fn new(
+        id: ID,
+        path: impl AsRef<Path>,
+        publisher: P,
+        update_delay: u128,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            reader: LogReader::open(path)?,
+            publisher,
+            next: None,
+            start: None,
+            speed: 1.0,
+            looping: false,
+            update_delay,
+            id,
+        })
+    }
+
+    /// Set the playback speed multiplier (i.e. 2.0 replays the recorded data twice
+    /// as fast as it was recorded).  By default, data is replayed at the speed it
+    /// was recorded at.
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+
+    /// Set whether the log file is replayed from the beginning once it ends.  By
+    /// default, the log file is only replayed once.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Get a mutable reference to the publisher (i.e. to add subscribers)
+    pub fn publisher_mut(&mut self) -> &mut P {
+        &mut self.publisher
+    }
+
+    /// Check whether every piece of recorded data has been replayed (a looping
+    /// node is never finished)
+    pub fn is_finished(&self) -> bool {
+        self.start.is_some() && self.next.is_none()
+    }
+
+    /// Read the next piece of recorded data, returning to the start of the log
+    /// file at the end of the log file when looping.
+    ///
+    /// Returns whether the log file was started over.
+    fn read_next(&mut self) -> bool {
+        self.next = self.reader.next_data().ok().flatten();
+        if self.next.is_none() && self.looping && self.reader.rewind().is_ok() {
+            self.next = self.reader.next_data().ok().flatten();
+            return true;
+        }
+        false
+    }
+}
+
+impl<ID: PartialEq + Clone + Send, P: Publisher + Send> Node<ID> for ReplayPublisher<ID, P>
+where
+    P::Data: Packable + Send,
+{
+    fn get_id(&self) -> ID {
+        self.id.clone()
+    }
+
+    fn get_update_delay_us(&self) -> u128 {
+        self.update_delay
+    }
+
+    fn start(&mut self) {
+        if self.start.is_some() {
+            let _ = self.reader.rewind();
+        }
+        self.start = None;
+        self.next = None;
+    }
+
+    fn update(&mut self) {
+        if self.start.is_none() {
+            self.start = Some(Instant::now());
+            self.read_next();
+        }
+
+        let elapsed = self.start.unwrap().elapsed().mul_f64(self.speed);
+        while self
+            .next
+            .as_ref()
+            .is_some_and(|(timestamp, _)| *timestamp <= elapsed)
+        {
+            let (timestamp, data) = self.next.take().unwrap();
+            let _ = self.publisher.publish(data);
+
+            // The next loop starts once the last piece of data is published (and
+            // is first published on the next update)
+            if self.read_next() {
+                self.start = Some(self.start.unwrap() + timestamp.div_f64(self.speed));
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    use ncomm_core::Subscriber;
+
+    use crate::local::LocalPublisher;
+
+    #[test]
+    fn test_record_and_replay() {
+        let path = std::env::temp_dir().join("ncomm_logging_replay.log");
+        let gaps = [Duration::from_millis(30), Duration::from_millis(60)];
+
+        let mut publisher = LoggingPublisher::new(LocalPublisher::new(), &path).unwrap();
+        let mut live = publisher.inner_mut().subscribe_buffered();
+        publisher.publish(1u32).unwrap();
+        thread::sleep(gaps[0]);
+        publisher.publish(2u32).unwrap();
+        thread::sleep(gaps[1]);
+        publisher.publish(3u32).unwrap();
+        drop(publisher);
+        assert_eq!(live.get().len(), 3);
+
+        let mut replay = ReplayPublisher::new(0u8, &path, LocalPublisher::new(), 1_000).unwrap();
+        let mut subscriber = replay.publisher_mut().subscribe_buffered();
+        let mut received = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while !replay.is_finished() && Instant::now() < deadline {
+            replay.update();
+            let data = subscriber.get();
+            while received.len() < data.len() {
+                received.push((data[received.len()].unwrap(), Instant::now()));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let data: Vec<u32> = received.iter().map(|(data, _)| *data).collect();
+        assert_eq!(data, vec![1, 2, 3]);
+        for (idx, gap) in gaps.iter().enumerate() {
+            let replayed_gap = received[idx + 1].1 - received[idx].1;
+            assert!(replayed_gap >= *gap - Duration::from_millis(2));
+            assert!(replayed_gap < *gap + Duration::from_millis(25));
+        }
+    }
+
+    #[test]
+    fn test_log_reader_corrupt_length() {
+        let path =
+            std::env::temp_dir().join(format!("ncomm_logging_corrupt_{}.log", std::process::id()));
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&0u64.to_le_bytes());
+        frame.extend_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, &frame).unwrap();
+
+        let mut reader = LogReader::open(&path).unwrap();
+        let err = reader.next_frame().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        reader.rewind().unwrap();
+        let err = reader.next_data::<u32>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+//!
+//! A Publisher that Fans Out to Multiple Publishers
+//!
+//! The Multi Publisher forwards each piece of published data to a number of
+//! publishers (which may use different transports) so one logical publish can
+//! reach, for example, both an in-process subscriber and a remote machine.
+//!
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use ncomm_core::Publisher;
+
+/// A type-erased error returned from one of the publishers in a Multi Publisher
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A publisher whose errors are boxed so publishers with different error types
+/// can be stored together
+type BoxedPublisher<Data> = Box<dyn Publisher<Data = Data, Error = BoxedError> + Send>;
+
+/// An adapter boxing the errors returned from a publisher
+struct ErasedPublisher<P: Publisher>(P);
+
+impl<P: Publisher> Publisher for ErasedPublisher<P>
+where
+    P::Error: std::error::Error + Send + Sync + 'static,
+{
+    type Data = P::Data;
+    type Error = BoxedError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        self.0
+            .publish(data)
+            .map_err(|err| Box::new(err) as BoxedError)
+    }
+}
+
+/// An Error when one or more of the publishers in a Multi Publisher failed to
+/// publish data
+#[derive(Debug)]
+pub struct MultiPublishError(pub Vec<BoxedError>);
+
+impl Display for MultiPublishError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} publisher(s) failed", self.0.len())?;
+        if let Some(err) = self.0.first() {
+            write!(f, ": {}", err)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MultiPublishError {
+    /// Note: Only the first error is returned as the source when multiple
+    /// publishers failed.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.first().map(|err| err.as_ref() as _)
+    }
+}
+
+/// A publisher that forwards each piece of data to every one of its publishers
+///
+/// Note: Data is cloned for every publisher (except the last) so cheaply
+/// cloneable data is preferred.
+pub struct MultiPublisher<Data: Clone> {
+    /// The publishers data is forwarded to
+    publishers: Vec<BoxedPublisher<Data>>,
+}
+
+impl<Data: Clone> Default for MultiPublisher<Data> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Data: Clone> MultiPublisher<Data> {
+    /// Create a new Multi Publisher without any publishers
+    pub fn new() -> Self {
+        Self {
+            publishers: Vec::new(),
+        }
+    }
+
+    /// Add a publisher that data should be forwarded to
+    pub fn add_publisher<P>(&mut self, publisher: P)
+    where
+        P: Publisher<Data = Data> + Send + 'static,
+        P::Error: std::error::Error + Send + Sync + 'static,
+    {
+        self.publishers.push(Box::new(ErasedPublisher(publisher)));
+    }
+
+    /// Get the number of publishers data is forwarded to
+    pub fn len(&self) -> usize {
+        self.publishers.len()
+    }
+
+    /// Check whether the Multi Publisher has no publishers
+    pub fn is_empty(&self) -> bool {
+        self.publishers.is_empty()
+    }
+}
+
+impl<Data: Clone> Publisher for MultiPublisher<Data> {
+    type Data = Data;
+    type Error = MultiPublishError;
+
+    /// Publish the data with every publisher.
+    ///
+    /// Note: Every publisher is given the data even if an earlier publisher failed,
+    /// and the errors from each of the failed publishers are returned together.
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        let mut errors = Vec::new();
+        if let Some((last, publishers)) = self.publishers.split_last_mut() {
+            for publisher in publishers.iter_mut() {
+                if let Err(err) = publisher.publish(data.clone()) {
+                    errors.push(err);
+                }
+            }
+            if let Err(err) = last.publish(data) {
+                errors.push(err);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(MultiPublishError(errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+        thread::sleep,
+        time::Duration,
+    };
+
+    use ncomm_core::Subscriber;
+
+    use crate::{
+        local::LocalPublisher,
+        udp::{UdpPublisher, UdpSubscriber},
+    };
+
+    #[test]
+    fn test_multi_publisher() {
+        let mut local_publisher = LocalPublisher::new();
+        let mut local_subscriber = local_publisher.subscribe();
+        let udp_publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8052)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8053))],
+        )
+        .unwrap();
+        let mut udp_subscriber: UdpSubscriber<u64> =
+            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8053)))
+                .unwrap();
+
+        let mut publisher = MultiPublisher::new();
+        publisher.add_publisher(local_publisher);
+        publisher.add_publisher(udp_publisher);
+        assert_eq!(publisher.len(), 2);
+
+        publisher.publish(42u64).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(*local_subscriber.get(), Some(42));
+        assert_eq!(*udp_subscriber.get(), Some(42));
+    }
+}
@@ -22,8 +22,8 @@ use std::marker::PhantomData;
 use quanta::{Clock, Instant};
 
 use rerun::{
-    external::re_types::blueprint::components::TimelineName, AsComponents, EntityPath,
-    RecordingStream, RecordingStreamError,
+    external::re_types::blueprint::components::TimelineName, AsComponents, EntityPath, Image,
+    Points3D, Quaternion, RecordingStream, RecordingStreamError, Transform3D,
 };
 
 use ncomm_core::Publisher;
@@ -120,3 +120,113 @@ impl<Path: Into<EntityPath> + Clone, Arch: AsComponents> Publisher
         self.stream.log(self.path.clone(), &data.0)
     }
 }
+
+/// A cloud of points in 3D space
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointCloud {
+    /// The (x, y, z) positions of each point in the cloud
+    pub points: Vec<[f32; 3]>,
+}
+
+impl From<PointCloud> for Points3D {
+    fn from(cloud: PointCloud) -> Self {
+        Points3D::new(cloud.points)
+    }
+}
+
+/// An 8-bit RGB image (i.e. a camera frame)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RgbImage {
+    /// The width of the image (in pixels)
+    pub width: u32,
+    /// The height of the image (in pixels)
+    pub height: u32,
+    /// The row-major, interleaved RGB bytes of the image
+    pub pixels: Vec<u8>,
+}
+
+impl From<RgbImage> for Image {
+    fn from(image: RgbImage) -> Self {
+        Image::from_rgb24(image.pixels, [image.width, image.height])
+    }
+}
+
+/// The pose of a frame relative to its parent (i.e. a TF transform)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    /// The (x, y, z) translation of the frame
+    pub translation: [f32; 3],
+    /// The (x, y, z, w) quaternion rotation of the frame
+    pub rotation: [f32; 4],
+}
+
+impl Default for Pose {
+    fn default() -> Self {
+        Self {
+            translation: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+}
+
+impl From<Pose> for Transform3D {
+    fn from(pose: Pose) -> Self {
+        Transform3D::from_translation_rotation(
+            pose.translation,
+            Quaternion::from_xyzw(pose.rotation),
+        )
+    }
+}
+
+/// Rerun Publisher that converts published NComm data into a specific Archetype
+/// before logging it to a given path with the current Rerun timestamp.
+pub struct RerunConvertingPublisher<
+    Path: Into<EntityPath> + Clone,
+    Data: Into<Arch>,
+    Arch: AsComponents,
+> {
+    /// The publisher the converted Archetypes are published with
+    publisher: RerunPublisher<Path, Arch>,
+    /// Marker to denote the datatype that can be published via this publisher
+    _phantom: PhantomData<Data>,
+}
+
+impl<Path: Into<EntityPath> + Clone, Data: Into<Arch>, Arch: AsComponents>
+    RerunConvertingPublisher<Path, Data, Arch>
+{
+    /// Create a new RerunConvertingPublisher.
+    ///
+    /// Note: Like the other Rerun publishers, I would advise creating this
+    /// publisher from the RerunNode in the ncomm-nodes crate.
+    pub fn new(stream: RecordingStream, path: Path) -> Self {
+        Self {
+            publisher: RerunPublisher::new(stream, path),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Reset the start instant for the reference clock
+    pub fn start(&mut self) {
+        self.publisher.start();
+    }
+}
+
+impl<Path: Into<EntityPath> + Clone, Data: Into<Arch>, Arch: AsComponents> Publisher
+    for RerunConvertingPublisher<Path, Data, Arch>
+{
+    type Data = Data;
+    type Error = RecordingStreamError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        self.publisher.publish(data.into())
+    }
+}
+
+/// Rerun Publisher that logs point clouds as `Points3D`
+pub type RerunPoints3DPublisher<Path> = RerunConvertingPublisher<Path, PointCloud, Points3D>;
+
+/// Rerun Publisher that logs camera frames as `Image`s
+pub type RerunImagePublisher<Path> = RerunConvertingPublisher<Path, RgbImage, Image>;
+
+/// Rerun Publisher that logs poses as `Transform3D`s
+pub type RerunTransformPublisher<Path> = RerunConvertingPublisher<Path, Pose, Transform3D>;
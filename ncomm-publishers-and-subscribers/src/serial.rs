@@ -9,7 +9,7 @@ use core::marker::PhantomData;
 
 use embedded_io::{Error, Read, ReadReady, Write};
 
-use ncomm_core::publisher_subscriber::{Publisher, Subscriber};
+use ncomm_core::publisher_subscriber::{ConsumingSubscriber, Publisher, Subscriber};
 use ncomm_utils::packing::{Packable, PackingError};
 
 /// An Error regarding publishing serial data
@@ -165,6 +165,21 @@ where
     }
 }
 
+impl<Data, Serial, Err, const BUFFER_SIZE: usize> ConsumingSubscriber
+    for SerialSubscriber<Data, Serial, Err, BUFFER_SIZE>
+where
+    Data: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err>,
+    Err: Error,
+{
+    type Data = Data;
+
+    fn take(&mut self) -> Option<Self::Data> {
+        self.get();
+        self.data.take()
+    }
+}
+
 /// A serial publisher/subscriber capable of both publishing and subscribing
 /// a specific data type.
 ///
@@ -267,3 +282,460 @@ where
         &self.data
     }
 }
+
+impl<Data, Serial, Err, const BUFFER_SIZE: usize> ConsumingSubscriber
+    for SerialPublisherSubscriber<Data, Serial, Err, BUFFER_SIZE>
+where
+    Data: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err> + Write<Error = Err>,
+    Err: Error,
+{
+    type Data = Data;
+
+    fn take(&mut self) -> Option<Self::Data> {
+        self.get();
+        self.data.take()
+    }
+}
+
+/// The byte used to escape delimiter (and escape) bytes that appear in the
+/// payload of a framed serial message.
+pub const FRAME_ESCAPE: u8 = 0x7D;
+
+/// The value escaped bytes are xor-ed with so that they no longer match the
+/// delimiter or escape byte.
+const FRAME_ESCAPE_MASK: u8 = 0x20;
+
+/// Write `bytes` to the serial device as a single frame terminated by the
+/// `delimiter` byte.
+///
+/// Any delimiter or escape bytes in the payload are escaped by writing the escape
+/// byte followed by the byte xor-ed with the escape mask.
+fn write_frame<Serial: Write<Error = Err>, Err: Error>(
+    serial_device: &mut Serial,
+    bytes: &[u8],
+    delimiter: u8,
+) -> Result<(), Err> {
+    let mut run_start = 0;
+    for (idx, byte) in bytes.iter().enumerate() {
+        if *byte == delimiter || *byte == FRAME_ESCAPE {
+            serial_device.write_all(&bytes[run_start..idx])?;
+            serial_device.write_all(&[FRAME_ESCAPE, *byte ^ FRAME_ESCAPE_MASK])?;
+            run_start = idx + 1;
+        }
+    }
+    serial_device.write_all(&bytes[run_start..])?;
+    serial_device.write_all(&[delimiter])
+}
+
+/// Publisher that publishes delimited frames of data via a serial device.
+///
+/// Each packed message is followed by the delimiter byte and any occurrences of
+/// the delimiter byte within the packed message are escaped so subscribers can
+/// always find the boundaries between messages.
+///
+/// Note: Any serial device implementing the embedded-io traits can be used (i.e.
+/// a serialport port wrapped in an embedded-io adapter on std platforms or the
+/// UART peripheral of a microcontroller).
+pub struct SerialFramedPublisher<
+    Data: Packable,
+    Serial: Write<Error = Err>,
+    Err: Error,
+    const BUFFER_SIZE: usize,
+> {
+    /// The serial peripheral device
+    serial_device: Serial,
+    /// The internal buffer for encoding data
+    buffer: [u8; BUFFER_SIZE],
+    /// The byte marking the end of a frame
+    delimiter: u8,
+    /// A marker to bind the type of data published to the publisher
+    _phantom: PhantomData<Data>,
+}
+
+impl<Data, Serial, Err, const BUFFER_SIZE: usize>
+    SerialFramedPublisher<Data, Serial, Err, BUFFER_SIZE>
+where
+    Data: Packable,
+    Serial: Write<Error = Err>,
+    Err: Error,
+{
+    /// Create a new SerialFramedPublisher from the peripheral that terminates
+    /// each message with the given delimiter byte.
+    ///
+    /// Note: The delimiter can't be the escape byte (`FRAME_ESCAPE`) or the escape
+    /// byte once escaped (0x5D) as either would make escaped bytes look like the
+    /// end of a frame.
+    pub fn new(serial_device: Serial, buffer: [u8; BUFFER_SIZE], delimiter: u8) -> Self {
+        assert!(
+            BUFFER_SIZE >= Data::len(),
+            "The buffer must be large enough to fit encoded data"
+        );
+        assert!(
+            delimiter != FRAME_ESCAPE && delimiter != FRAME_ESCAPE ^ FRAME_ESCAPE_MASK,
+            "The delimiter cannot be the frame escape byte or an escaped escape byte"
+        );
+        Self {
+            serial_device,
+            buffer,
+            delimiter,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Destroy the SerialFramedPublisher returning the serial peripheral
+    pub fn destroy(self) -> Serial {
+        self.serial_device
+    }
+}
+
+impl<Data, Serial, Err, const BUFFER_SIZE: usize> Publisher
+    for SerialFramedPublisher<Data, Serial, Err, BUFFER_SIZE>
+where
+    Data: Packable,
+    Serial: Write<Error = Err>,
+    Err: Error,
+{
+    type Data = Data;
+    type Error = SerialPublishError<Err>;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        let buffer = &mut self.buffer[..Data::len()];
+        buffer.iter_mut().for_each(|v| *v = 0);
+        data.pack(buffer)
+            .map_err(SerialPublishError::PackingError)?;
+
+        write_frame(&mut self.serial_device, buffer, self.delimiter)
+            .map_err(SerialPublishError::IOError)?;
+
+        Ok(())
+    }
+}
+
+/// Serial Subscriber that reads delimited frames of data from a serial line.
+///
+/// The subscriber drains the serial device on every `get`, reassembling frames
+/// that span multiple reads and unpacking every complete frame.  Frames that do
+/// not contain exactly one packed message are discarded.
+///
+/// Note: To make this subscriber no_std compatible the subscriber
+/// has an internal buffer that is statically allocated, hence the reason
+/// for the const BUFFER_SIZE: usize generic
+pub struct SerialFramedSubscriber<
+    Data: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err>,
+    Err: Error,
+    const BUFFER_SIZE: usize,
+> {
+    /// The serial peripheral device
+    serial_device: Serial,
+    /// The internal buffer the current frame is decoded into
+    buffer: [u8; BUFFER_SIZE],
+    /// The number of bytes of the current frame in the buffer
+    frame_len: usize,
+    /// Whether the previous byte was the escape byte
+    escaped: bool,
+    /// Whether the current frame overflowed the buffer (and should be discarded)
+    overflowed: bool,
+    /// The byte marking the end of a frame
+    delimiter: u8,
+    /// The current data stored in the subscriber
+    data: Option<Data>,
+}
+
+impl<Data, Serial, Err, const BUFFER_SIZE: usize>
+    SerialFramedSubscriber<Data, Serial, Err, BUFFER_SIZE>
+where
+    Data: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err>,
+    Err: Error,
+{
+    /// Create a new SerialFramedSubscriber from the peripheral that expects
+    /// each message to be terminated by the given delimiter byte.
+    ///
+    /// Note: The delimiter can't be the escape byte (`FRAME_ESCAPE`) or the escape
+    /// byte once escaped (0x5D) as either would make escaped bytes look like the
+    /// end of a frame.
+    pub fn new(serial_device: Serial, buffer: [u8; BUFFER_SIZE], delimiter: u8) -> Self {
+        assert!(
+            BUFFER_SIZE >= Data::len(),
+            "The buffer must be large enough to fit encoded data"
+        );
+        assert!(
+            delimiter != FRAME_ESCAPE && delimiter != FRAME_ESCAPE ^ FRAME_ESCAPE_MASK,
+            "The delimiter cannot be the frame escape byte or an escaped escape byte"
+        );
+        Self {
+            serial_device,
+            buffer,
+            frame_len: 0,
+            escaped: false,
+            overflowed: false,
+            delimiter,
+            data: None,
+        }
+    }
+
+    /// Destroy the SerialFramedSubscriber returning the serial peripheral
+    pub fn destroy(self) -> Serial {
+        self.serial_device
+    }
+
+    /// Decode a single received byte, returning the unpacked data if the byte
+    /// completed a valid frame.
+    fn decode(&mut self, byte: u8) -> Option<Data> {
+        if byte == self.delimiter {
+            let data = if !self.overflowed && !self.escaped && self.frame_len == Data::len() {
+                Data::unpack(&self.buffer[..self.frame_len]).ok()
+            } else {
+                None
+            };
+            self.frame_len = 0;
+            self.escaped = false;
+            self.overflowed = false;
+            return data;
+        }
+
+        if byte == FRAME_ESCAPE && !self.escaped {
+            self.escaped = true;
+            return None;
+        }
+
+        let byte = if self.escaped {
+            self.escaped = false;
+            byte ^ FRAME_ESCAPE_MASK
+        } else {
+            byte
+        };
+
+        if self.frame_len < BUFFER_SIZE {
+            self.buffer[self.frame_len] = byte;
+            self.frame_len += 1;
+        } else {
+            self.overflowed = true;
+        }
+        None
+    }
+}
+
+impl<Data, Serial, Err, const BUFFER_SIZE: usize> Subscriber
+    for SerialFramedSubscriber<Data, Serial, Err, BUFFER_SIZE>
+where
+    Data: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err>,
+    Err: Error,
+{
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        let mut read_buffer = [0u8; 64];
+
+        while let Ok(true) = self.serial_device.read_ready() {
+            let read = match self.serial_device.read(&mut read_buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+
+            for byte in read_buffer[..read].iter() {
+                if let Some(data) = self.decode(*byte) {
+                    self.data = Some(data);
+                }
+            }
+        }
+
+        &self.data
+    }
+}
+
+impl<Data, Serial, Err, const BUFFER_SIZE: usize> ConsumingSubscriber
+    for SerialFramedSubscriber<Data, Serial, Err, BUFFER_SIZE>
+where
+    Data: Packable,
+    Serial: ReadReady<Error = Err> + Read<Error = Err>,
+    Err: Error,
+{
+    type Data = Data;
+
+    fn take(&mut self) -> Option<Self::Data> {
+        self.get();
+        self.data.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{cell::RefCell, collections::VecDeque, convert::Infallible, rc::Rc};
+
+    use embedded_io::ErrorType;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Data {
+        num: u64,
+    }
+
+    impl Packable for Data {
+        fn len() -> usize {
+            8
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            if buffer.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
+            } else {
+                buffer[..8].copy_from_slice(&self.num.to_le_bytes());
+                Ok(())
+            }
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            if data.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
+            } else {
+                Ok(Self {
+                    num: u64::from_le_bytes(data[..8].try_into().unwrap()),
+                })
+            }
+        }
+    }
+
+    /// A mock serial device that reads back everything written to it, at most
+    /// `chunk_size` bytes at a time.
+    #[derive(Clone)]
+    struct Loopback {
+        bytes: Rc<RefCell<VecDeque<u8>>>,
+        chunk_size: usize,
+    }
+
+    impl Loopback {
+        fn new(chunk_size: usize) -> Self {
+            Self {
+                bytes: Rc::new(RefCell::new(VecDeque::new())),
+                chunk_size,
+            }
+        }
+    }
+
+    impl ErrorType for Loopback {
+        type Error = Infallible;
+    }
+
+    impl Write for Loopback {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.bytes.borrow_mut().extend(buf.iter());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl Read for Loopback {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let mut bytes = self.bytes.borrow_mut();
+            let read = buf.len().min(bytes.len()).min(self.chunk_size);
+            for (idx, byte) in bytes.drain(..read).enumerate() {
+                buf[idx] = byte;
+            }
+            Ok(read)
+        }
+    }
+
+    impl ReadReady for Loopback {
+        fn read_ready(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.bytes.borrow().is_empty())
+        }
+    }
+
+    #[test]
+    fn test_framed_publish_and_subscribe() {
+        let serial = Loopback::new(64);
+        let mut publisher: SerialFramedPublisher<Data, _, _, 8> =
+            SerialFramedPublisher::new(serial.clone(), [0u8; 8], 0x00);
+        let mut subscriber: SerialFramedSubscriber<Data, _, _, 8> =
+            SerialFramedSubscriber::new(serial, [0u8; 8], 0x00);
+
+        assert_eq!(*subscriber.get(), None);
+
+        // The packed data contains both the delimiter and the escape byte
+        let data = Data {
+            num: 0x7D00_7D5D_0000_0020,
+        };
+        publisher.publish(data).unwrap();
+        assert_eq!(*subscriber.get(), Some(data));
+
+        // Taking the data leaves the subscriber without data until more is received
+        assert_eq!(subscriber.take(), Some(data));
+        assert_eq!(subscriber.take(), None);
+    }
+
+    #[test]
+    fn test_framed_subscribe_split_reads() {
+        let serial = Loopback::new(3);
+        let mut publisher: SerialFramedPublisher<Data, _, _, 8> =
+            SerialFramedPublisher::new(serial.clone(), [0u8; 8], b'\n');
+        let mut subscriber: SerialFramedSubscriber<Data, _, _, 8> =
+            SerialFramedSubscriber::new(serial, [0u8; 8], b'\n');
+
+        for num in [10u64, 0x0A0A, 0x7D0A_7D0A] {
+            publisher.publish(Data { num }).unwrap();
+        }
+
+        assert_eq!(*subscriber.get(), Some(Data { num: 0x7D0A_7D0A }));
+    }
+
+    #[test]
+    fn test_framed_escape_bytes() {
+        let serial = Loopback::new(64);
+        let mut publisher: SerialFramedPublisher<Data, _, _, 8> =
+            SerialFramedPublisher::new(serial.clone(), [0u8; 8], b'\n');
+        let mut subscriber: SerialFramedSubscriber<Data, _, _, 8> =
+            SerialFramedSubscriber::new(serial, [0u8; 8], b'\n');
+
+        // Payloads made up of escape bytes and escaped escape bytes
+        for num in [0x7D7D_7D7D_7D7D_7D7D, 0x5D7D_5D7D_2A0A_7D5D] {
+            let data = Data { num };
+            publisher.publish(data).unwrap();
+            assert_eq!(*subscriber.get(), Some(data));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_framed_escaped_escape_delimiter() {
+        let _publisher: SerialFramedPublisher<Data, _, _, 8> =
+            SerialFramedPublisher::new(Loopback::new(64), [0u8; 8], 0x5D);
+    }
+
+    #[test]
+    fn test_framed_subscribe_partial_frame() {
+        let serial = Loopback::new(64);
+        let mut subscriber: SerialFramedSubscriber<Data, _, _, 8> =
+            SerialFramedSubscriber::new(serial.clone(), [0u8; 8], 0x00);
+
+        // Garbage before the first delimiter is discarded
+        serial.bytes.borrow_mut().extend([1, 2, 3, 0x00]);
+        // A frame is only unpacked once its delimiter has been received
+        serial
+            .bytes
+            .borrow_mut()
+            .extend(5u64.to_le_bytes().map(|b| b | 0x01));
+        assert_eq!(*subscriber.get(), None);
+
+        serial.bytes.borrow_mut().push_back(0x00);
+        assert_eq!(
+            *subscriber.get(),
+            Some(Data {
+                num: u64::from_le_bytes([0x05, 1, 1, 1, 1, 1, 1, 1])
+            })
+        );
+    }
+}
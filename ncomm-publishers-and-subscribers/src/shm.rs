@@ -0,0 +1,394 @@
+//!
+//! A Shared-Memory Publisher and Subscriber
+//!
+//! The Shared-Memory Publisher writes packed data into a memory-mapped ring
+//! buffer backed by a file so that any number of Shared-Memory Subscribers
+//! (in the same process or in other processes on the same host) can read the
+//! most recently published data without going through the network stack.
+//!
+//! The mapped file is laid out as a header followed by a number of slots:
+//! ```text
+//! | sequence (u64) | slots (u64) | data length (u64) | slot 0 | slot 1 | ... |
+//! ```
+//! where each slot consists of a u64 slot sequence followed by the packed data
+//! (padded to a multiple of 8 bytes).
+//!
+//! Note: There should only ever be one publisher per file.  The slot sequences act
+//! as a seqlock so subscribers never block the publisher and retry any read that
+//! raced with a write to the same slot.
+//!
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{Error, ErrorKind},
+    marker::PhantomData,
+    path::Path,
+    ptr,
+    sync::atomic::{fence, AtomicU64, Ordering},
+};
+
+use memmap2::{Mmap, MmapMut};
+
+use ncomm_core::{Publisher, Subscriber};
+use ncomm_utils::packing::{Packable, PackingError};
+
+/// The length of the header at the start of the shared memory (in bytes)
+const HEADER_LEN: usize = 24;
+
+/// The offset of the number of slots in the header
+const SLOTS_OFFSET: usize = 8;
+
+/// The offset of the length of the packed data in the header
+const DATA_LEN_OFFSET: usize = 16;
+
+/// The length of the sequence at the start of each slot (in bytes)
+const SLOT_SEQUENCE_LEN: usize = 8;
+
+/// Get the length of a slot holding a packed piece of data with a given length
+fn slot_len(data_len: usize) -> usize {
+    SLOT_SEQUENCE_LEN + data_len.div_ceil(8) * 8
+}
+
+/// Get the atomic u64 at a given (8 byte aligned) offset from the start of a
+/// mapping of the shared memory
+///
+/// # Safety
+///
+/// `base` must be the start of a mapping that is at least `offset + 8` bytes long
+/// and outlives the returned reference, `offset` must be a multiple of 8, and the
+/// u64s in the mapping must only ever be accessed atomically.  If the mapping is
+/// read-only, the returned atomic must only be loaded from.
+unsafe fn atomic_at<'a>(base: *mut u8, offset: usize) -> &'a AtomicU64 {
+    // SAFETY: The mapping is page aligned so the offset is 8 byte aligned.
+    unsafe { AtomicU64::from_ptr(base.add(offset).cast()) }
+}
+
+/// A Shared-Memory Publisher that publishes data in a way defined by the Packable
+/// layout to a memory-mapped file
+pub struct ShmPublisher<Data: Packable> {
+    /// The memory-mapped file
+    mmap: MmapMut,
+    /// The number of slots in the ring buffer
+    slots: usize,
+    /// The number of pieces of data published so far
+    sequence: u64,
+    /// A Phantom Data to bind the specific type of data to send to the publisher
+    phantom: PhantomData<Data>,
+}
+
+impl<Data: Packable> ShmPublisher<Data> {
+    /// Create a new Shared-Memory Publisher that creates the file at `path` (if it
+    /// doesn't exist) and maps a ring buffer with a given number of slots into it.
+    ///
+    /// Note: An existing file is never truncated (only grown if it is too short) so
+    /// subscribers that still have the file mapped are never left with pages that no
+    /// longer exist.
+    pub fn new(path: impl AsRef<Path>, slots: usize) -> Result<Self, Error> {
+        if slots == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "a shared memory ring buffer needs at least one slot",
+            ));
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let len = (HEADER_LEN + slots * slot_len(Data::len())) as u64;
+        if file.metadata()?.len() < len {
+            file.set_len(len)?;
+        }
+        // SAFETY: The file is only modified through the mapping and its length is
+        // never shrunk while it is mapped.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        let mut publisher = Self {
+            mmap,
+            slots,
+            sequence: 0,
+            phantom: PhantomData,
+        };
+        publisher
+            .atomic(SLOTS_OFFSET)
+            .store(slots as u64, Ordering::Relaxed);
+        publisher
+            .atomic(DATA_LEN_OFFSET)
+            .store(Data::len() as u64, Ordering::Relaxed);
+        publisher.atomic(0).store(0, Ordering::Release);
+        Ok(publisher)
+    }
+
+    /// Get the atomic u64 at a given offset in the shared memory
+    fn atomic(&mut self, offset: usize) -> &AtomicU64 {
+        debug_assert!(offset.is_multiple_of(8) && offset + 8 <= self.mmap.len());
+        // SAFETY: The pointer is taken from the mutable mapping which outlives the
+        // borrow of the publisher.
+        unsafe { atomic_at(self.mmap.as_mut_ptr(), offset) }
+    }
+}
+
+impl<Data: Packable> Publisher for ShmPublisher<Data> {
+    type Data = Data;
+    type Error = PackingError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        let sequence = self.sequence + 1;
+        let offset = HEADER_LEN + ((sequence - 1) as usize % self.slots) * slot_len(Data::len());
+
+        // Mark the slot as being written (odd) before writing the data
+        self.atomic(offset)
+            .store(2 * sequence - 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        let start = offset + SLOT_SEQUENCE_LEN;
+        let result = data.pack(&mut self.mmap[start..start + Data::len()]);
+
+        // Mark the slot as committed (even) and point subscribers at it
+        self.atomic(offset).store(2 * sequence, Ordering::Release);
+        if result.is_ok() {
+            self.sequence = sequence;
+            self.atomic(0).store(sequence, Ordering::Release);
+        }
+        result
+    }
+}
+
+/// A Shared-Memory Subscriber that reads the most recently published piece of
+/// data from a memory-mapped file written to by a Shared-Memory Publisher
+pub struct ShmSubscriber<Data: Packable> {
+    /// The (read-only) memory-mapped file
+    mmap: Mmap,
+    /// The number of slots in the ring buffer
+    slots: usize,
+    /// The sequence of the most recently read piece of data
+    sequence: u64,
+    /// A buffer the data is copied into before being unpacked
+    buffer: Vec<u8>,
+    /// The most recently read piece of data
+    data: Option<Data>,
+}
+
+impl<Data: Packable> ShmSubscriber<Data> {
+    /// Create a new Shared-Memory Subscriber mapping the file at `path`.
+    ///
+    /// Note: The file should be created by a Shared-Memory Publisher of the same
+    /// data type before the subscriber is created.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        let mut subscriber = Self {
+            mmap: Self::map(&file)?,
+            slots: 0,
+            sequence: 0,
+            buffer: vec![0; Data::len()],
+            data: None,
+        };
+
+        let slots = subscriber.atomic(SLOTS_OFFSET).load(Ordering::Relaxed) as usize;
+        let data_len = subscriber.atomic(DATA_LEN_OFFSET).load(Ordering::Relaxed) as usize;
+        if slots == 0
+            || data_len != Data::len()
+            || subscriber.mmap.len() < HEADER_LEN + slots * slot_len(data_len)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "the shared memory was not created by a publisher of this data type",
+            ));
+        }
+
+        subscriber.slots = slots;
+        Ok(subscriber)
+    }
+
+    /// Map a file created by a Shared-Memory Publisher
+    fn map(file: &File) -> Result<Mmap, Error> {
+        if (file.metadata()?.len() as usize) < HEADER_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "the shared memory is too small to contain a header",
+            ));
+        }
+        // SAFETY: The publisher never shrinks the file while it is mapped.
+        unsafe { Mmap::map(file) }
+    }
+
+    /// Get the atomic u64 at a given offset in the shared memory
+    ///
+    /// Note: The mapping is read-only so the atomic must only be loaded from.
+    fn atomic(&self, offset: usize) -> &AtomicU64 {
+        debug_assert!(offset.is_multiple_of(8) && offset + 8 <= self.mmap.len());
+        // SAFETY: The mapping outlives the borrow of the subscriber and the
+        // subscriber only ever loads from the atomics in the mapping.
+        unsafe { atomic_at(self.mmap.as_ptr().cast_mut(), offset) }
+    }
+
+    /// Read the most recently committed piece of data, retrying if the read
+    /// raced with the publisher writing to the same slot.
+    fn read_latest(&mut self) {
+        loop {
+            let sequence = self.atomic(0).load(Ordering::Acquire);
+            if sequence == 0 || sequence == self.sequence {
+                return;
+            }
+
+            let offset =
+                HEADER_LEN + ((sequence - 1) as usize % self.slots) * slot_len(Data::len());
+            let slot_sequence = self.atomic(offset).load(Ordering::Acquire);
+            if slot_sequence != 2 * sequence {
+                continue;
+            }
+
+            // SAFETY: The slot lies within the mapping and any concurrent write to it
+            // is detected by the slot sequence changing below.
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    self.mmap.as_ptr().add(offset + SLOT_SEQUENCE_LEN),
+                    self.buffer.as_mut_ptr(),
+                    Data::len(),
+                );
+            }
+            fence(Ordering::Acquire);
+            if self.atomic(offset).load(Ordering::Relaxed) != slot_sequence {
+                continue;
+            }
+
+            self.sequence = sequence;
+            if let Ok(data) = Data::unpack(&self.buffer) {
+                self.data = Some(data);
+            }
+            return;
+        }
+    }
+}
+
+impl<Data: Packable> Subscriber for ShmSubscriber<Data> {
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        self.read_latest();
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.atomic(0).load(Ordering::Acquire) != self.sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Data {
+        num: u64,
+        check: u64,
+    }
+
+    impl Data {
+        pub fn new(num: u64) -> Self {
+            Self { num, check: !num }
+        }
+    }
+
+    impl Packable for Data {
+        fn len() -> usize {
+            16
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            if buffer.len() < 16 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 16,
+                    got: buffer.len(),
+                })
+            } else {
+                buffer[..8].copy_from_slice(&self.num.to_le_bytes());
+                buffer[8..16].copy_from_slice(&self.check.to_le_bytes());
+                Ok(())
+            }
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            if data.len() < 16 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 16,
+                    got: data.len(),
+                })
+            } else {
+                Ok(Self {
+                    num: u64::from_le_bytes(data[..8].try_into().unwrap()),
+                    check: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn test_publish_shm_subscriber() {
+        let path = std::env::temp_dir().join("ncomm_shm_publish.shm");
+        let mut publisher = ShmPublisher::new(&path, 4).unwrap();
+        let mut subscriber: ShmSubscriber<Data> = ShmSubscriber::new(&path).unwrap();
+
+        assert!(!subscriber.has_update());
+        assert_eq!(*subscriber.get(), None);
+
+        publisher.publish(Data::new(1)).unwrap();
+        publisher.publish(Data::new(2)).unwrap();
+        assert!(subscriber.has_update());
+        assert_eq!(*subscriber.get(), Some(Data::new(2)));
+        assert!(!subscriber.has_update());
+    }
+
+    #[test]
+    fn test_shm_publisher_restart() {
+        let path = std::env::temp_dir().join("ncomm_shm_restart.shm");
+        let mut publisher = ShmPublisher::new(&path, 4).unwrap();
+        let mut subscriber: ShmSubscriber<Data> = ShmSubscriber::new(&path).unwrap();
+
+        publisher.publish(Data::new(1)).unwrap();
+        assert_eq!(*subscriber.get(), Some(Data::new(1)));
+        drop(publisher);
+
+        // A restarted publisher reuses the file subscribers still have mapped
+        let mut publisher = ShmPublisher::new(&path, 4).unwrap();
+        publisher.publish(Data::new(2)).unwrap();
+        publisher.publish(Data::new(3)).unwrap();
+        assert_eq!(*subscriber.get(), Some(Data::new(3)));
+    }
+
+    #[test]
+    fn test_shm_subscriber_wrong_type() {
+        let path = std::env::temp_dir().join("ncomm_shm_wrong_type.shm");
+        let _publisher: ShmPublisher<u8> = ShmPublisher::new(&path, 4).unwrap();
+
+        let subscriber: Result<ShmSubscriber<Data>, Error> = ShmSubscriber::new(&path);
+        assert_eq!(subscriber.err().unwrap().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_shm_threads() {
+        let path = std::env::temp_dir().join("ncomm_shm_threads.shm");
+        let mut publisher = ShmPublisher::new(&path, 2).unwrap();
+        let mut subscriber: ShmSubscriber<Data> = ShmSubscriber::new(&path).unwrap();
+
+        let handle = thread::spawn(move || {
+            for num in 1..=100_000 {
+                publisher.publish(Data::new(num)).unwrap();
+            }
+        });
+
+        let mut last = 0;
+        while last < 100_000 {
+            if let Some(data) = *subscriber.get() {
+                assert_eq!(data.check, !data.num);
+                assert!(data.num >= last);
+                last = data.num;
+            }
+        }
+        handle.join().unwrap();
+    }
+}
@@ -13,7 +13,9 @@ use std::{
     time::{Duration, Instant},
 };
 
-use ncomm_core::{Publisher, Subscriber};
+use socket2::SockRef;
+
+use ncomm_core::{AddressedPublisher, ConsumingSubscriber, Publisher, Subscriber};
 use ncomm_utils::packing::{Packable, PackingError};
 
 /// An Error when attempting to publish data over a Tcp Publisher
@@ -23,10 +25,49 @@ pub enum TcpPublishError {
     IOError(Vec<Error>),
     /// An error occurred with packing the data
     PackingError(PackingError),
+
+    /// The address to publish to isn't one of the publisher's addresses
+    UnknownAddress(SocketAddr),
+}
+
+impl std::fmt::Display for TcpPublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IOError(errs) => {
+                write!(f, "io error on {} address(es)", errs.len())?;
+                if let Some(err) = errs.first() {
+                    write!(f, ": {}", err)?;
+                }
+                Ok(())
+            }
+            Self::PackingError(err) => write!(f, "packing error: {}", err),
+            Self::UnknownAddress(address) => write!(f, "unknown address {}", address),
+        }
+    }
+}
+
+impl std::error::Error for TcpPublishError {
+    /// Note: Only the first io error is returned as the source when publishing
+    /// failed on multiple addresses.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(errs) => errs.first().map(|err| err as _),
+            Self::PackingError(err) => Some(err),
+            Self::UnknownAddress(_) => None,
+        }
+    }
 }
 
 /// A Tcp Publisher that publishes data via packing the data
 /// according to the data's Packable implementation
+///
+/// Note: A latched Tcp Publisher keeps the most recently published data so it can
+/// still be delivered (via `deliver_latched`) to subscribers that weren't listening
+/// when the data was published (similar to ROS's transient-local QoS).
+///
+/// Addendum: Each publish opens a connection to every subscriber and writes the
+/// data out in full before closing the connection so the publisher never holds
+/// unsent data and doesn't need to be flushed before it is dropped.
 pub struct TcpPublisher<Data: Packable> {
     /// The list of addresses to publish to
     pub addresses: Vec<SocketAddr>,
@@ -35,6 +76,12 @@ pub struct TcpPublisher<Data: Packable> {
     phantom: PhantomData<Data>,
     /// The amount of time to block when sending data
     write_timeout: Option<Duration>,
+    /// The buffer data is packed into before being sent (reused across publishes)
+    buffer: Vec<u8>,
+    /// Whether the most recently published data is kept for late subscribers
+    latched: bool,
+    /// The addresses the latched data has been delivered to (if there is latched data)
+    delivered: Option<Vec<SocketAddr>>,
 }
 
 impl<Data: Packable> TcpPublisher<Data> {
@@ -43,35 +90,80 @@ impl<Data: Packable> TcpPublisher<Data> {
         Self {
             addresses: send_addresses,
             write_timeout,
+            buffer: Vec::with_capacity(Data::len()),
             phantom: PhantomData,
+            latched: false,
+            delivered: None,
         }
     }
-}
 
-impl<Data: Packable> Publisher for TcpPublisher<Data> {
-    type Data = Data;
-    type Error = TcpPublishError;
+    /// Set whether the publisher keeps the most recently published data for
+    /// subscribers that weren't listening when it was published.
+    ///
+    /// Note: Because the publisher connects to its subscribers, the latched data
+    /// is only sent to a late subscriber when `deliver_latched` is called, so
+    /// `deliver_latched` should be called periodically (i.e. in a Node's update
+    /// method).
+    pub fn set_latched(&mut self, latched: bool) {
+        self.latched = latched;
+        if !latched {
+            self.delivered = None;
+        }
+    }
 
-    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
-        let mut packed_data = vec![0u8; Data::len()];
-        data.pack(&mut packed_data)
-            .map_err(TcpPublishError::PackingError)?;
+    /// Send the latched data to every address it hasn't been delivered to yet
+    /// (including addresses added since the data was published).
+    ///
+    /// If the publisher isn't latched or nothing has been published, nothing is sent.
+    pub fn deliver_latched(&mut self) -> Result<(), TcpPublishError> {
+        let Some(mut delivered) = self.delivered.take() else {
+            return Ok(());
+        };
 
         let mut publish_errors = Vec::new();
         for address in self.addresses.iter() {
-            match TcpStream::connect(address) {
-                Ok(mut stream) => {
-                    if let Err(err) = stream.set_write_timeout(self.write_timeout) {
-                        publish_errors.push(err);
-                    }
-
-                    if let Err(err) = stream.write(&packed_data) {
-                        publish_errors.push(err);
-                    }
-                }
+            if delivered.contains(address) {
+                continue;
+            }
+
+            match send_to(address, &self.buffer, self.write_timeout) {
+                Ok(()) => delivered.push(*address),
                 Err(err) => publish_errors.push(err),
             }
         }
+        self.delivered = Some(delivered);
+
+        if publish_errors.is_empty() {
+            Ok(())
+        } else {
+            Err(TcpPublishError::IOError(publish_errors))
+        }
+    }
+
+    /// Pack a piece of data into the publisher's send buffer
+    fn pack_into_buffer(&mut self, data: Data) -> Result<(), TcpPublishError> {
+        // The buffer no longer holds the latched data
+        self.delivered = None;
+        self.buffer.clear();
+        self.buffer.resize(Data::len(), 0);
+        data.pack(&mut self.buffer)
+            .map_err(TcpPublishError::PackingError)
+    }
+
+    /// Send the packed data in the send buffer to each of the given addresses,
+    /// recording the addresses the data failed to reach
+    fn send_packed(
+        &self,
+        addresses: &[SocketAddr],
+        failed: &mut Vec<SocketAddr>,
+    ) -> Result<(), TcpPublishError> {
+        let mut publish_errors = Vec::new();
+        for address in addresses.iter() {
+            if let Err(err) = send_to(address, &self.buffer, self.write_timeout) {
+                publish_errors.push(err);
+                failed.push(*address);
+            }
+        }
 
         if publish_errors.is_empty() {
             Ok(())
@@ -79,6 +171,102 @@ impl<Data: Packable> Publisher for TcpPublisher<Data> {
             Err(TcpPublishError::IOError(publish_errors))
         }
     }
+
+    /// Latch the data in the send buffer (if the publisher is latched).
+    ///
+    /// Note: Addresses the data wasn't sent to are considered delivered so only
+    /// addresses the data failed to reach receive the latched data.
+    fn latch(&mut self, failed: &[SocketAddr]) {
+        if self.latched {
+            let delivered = self
+                .addresses
+                .iter()
+                .filter(|address| !failed.contains(address))
+                .copied()
+                .collect();
+            self.delivered = Some(delivered);
+        }
+    }
+}
+
+/// Connect to an address and send packed data over the connection
+fn send_to(
+    address: &SocketAddr,
+    packed_data: &[u8],
+    write_timeout: Option<Duration>,
+) -> Result<(), Error> {
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_write_timeout(write_timeout)?;
+    stream.write_all(packed_data)
+}
+
+impl<Data: Packable> Publisher for TcpPublisher<Data> {
+    type Data = Data;
+    type Error = TcpPublishError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        self.pack_into_buffer(data)?;
+
+        let mut failed = Vec::new();
+        let result = self.send_packed(&self.addresses, &mut failed);
+        self.latch(&failed);
+        result
+    }
+}
+
+impl<Data: Packable> AddressedPublisher<SocketAddr> for TcpPublisher<Data> {
+    fn publish_to(
+        &mut self,
+        data: Self::Data,
+        addresses: &[SocketAddr],
+    ) -> Result<(), Self::Error> {
+        if let Some(address) = addresses.iter().find(|v| !self.addresses.contains(v)) {
+            return Err(TcpPublishError::UnknownAddress(*address));
+        }
+
+        self.pack_into_buffer(data)?;
+
+        let mut failed = Vec::new();
+        let result = self.send_packed(addresses, &mut failed);
+        self.latch(&failed);
+        result
+    }
+}
+
+/// Accept incoming connections on a listener, keeping the connections from
+/// whitelisted addresses (or every connection if there is no whitelist).
+///
+/// Note: Connections are only accepted until there are `limit` pending connections
+/// (if there is a limit), leaving the remaining connections in the listener's backlog.
+fn accept_connections(
+    listener: &TcpListener,
+    whitelist: Option<&[IpAddr]>,
+    pending: &mut Vec<(TcpStream, SocketAddr)>,
+    limit: Option<usize>,
+) {
+    while limit.is_none_or(|limit| pending.len() < limit) {
+        let Ok((stream, socket_addr)) = listener.accept() else {
+            break;
+        };
+        if whitelist.is_none_or(|whitelist| whitelist.contains(&socket_addr.ip())) {
+            pending.push((stream, socket_addr));
+        }
+    }
+}
+
+/// Get the IP address the data received over a connection should be mapped to.
+///
+/// Data is mapped by the connection's peer address or, if the peer address can't
+/// be determined (i.e. the peer has already disconnected), the connection's local
+/// address.
+fn mapped_address(
+    peer_address: Result<SocketAddr, Error>,
+    local_address: Result<SocketAddr, Error>,
+) -> Option<IpAddr> {
+    peer_address
+        .or(local_address)
+        .ok()
+        .map(|socket_addr| socket_addr.ip())
 }
 
 /// A Tcp Subscriber that is set to nonblocking and and listens
@@ -89,6 +277,10 @@ pub struct TcpSubscriber<Data: Packable> {
     pub whitelist: Option<Vec<IpAddr>>,
     /// The Tcp Listener for incoming data
     listener: TcpListener,
+    /// Accepted connections whose data has not yet been read
+    pending: Vec<(TcpStream, SocketAddr)>,
+    /// The maximum number of connections read from per call to `get`
+    max_accepts_per_get: Option<usize>,
     /// The current data stored in the subscriber
     data: Option<Data>,
 }
@@ -101,6 +293,8 @@ impl<Data: Packable> TcpSubscriber<Data> {
         Ok(Self {
             whitelist: None,
             listener,
+            pending: Vec::new(),
+            max_accepts_per_get: None,
             data: None,
         })
     }
@@ -116,6 +310,8 @@ impl<Data: Packable> TcpSubscriber<Data> {
         Ok(Self {
             whitelist: Some(whitelist),
             listener,
+            pending: Vec::new(),
+            max_accepts_per_get: None,
             data: None,
         })
     }
@@ -146,6 +342,24 @@ impl<Data: Packable> TcpSubscriber<Data> {
             None
         }
     }
+
+    /// Set the maximum number of connections read from per call to `get` (or None
+    /// to read from every waiting connection).
+    ///
+    /// Note: Connections beyond the limit are left in the listener's backlog until
+    /// the next call to `get` so a burst of connections can't monopolize a
+    /// single call to `get`.
+    pub fn set_max_accepts_per_get(&mut self, max_accepts_per_get: Option<usize>) {
+        self.max_accepts_per_get = max_accepts_per_get;
+    }
+
+    /// Set the maximum number of connections the subscriber's listener keeps
+    /// waiting to be accepted.
+    ///
+    /// Note: The OS may cap the backlog (i.e. to `somaxconn` on Linux).
+    pub fn set_backlog(&self, backlog: i32) -> Result<(), Error> {
+        SockRef::from(&self.listener).listen(backlog)
+    }
 }
 
 impl<Data: Packable> Subscriber for TcpSubscriber<Data> {
@@ -153,13 +367,13 @@ impl<Data: Packable> Subscriber for TcpSubscriber<Data> {
 
     fn get(&mut self) -> &Self::Target {
         let mut buffer = vec![0u8; Data::len()];
-        while let Ok((mut stream, socket_addr)) = self.listener.accept() {
-            if let Some(whitelist) = self.whitelist.as_ref() {
-                if !whitelist.contains(&socket_addr.ip()) {
-                    continue;
-                }
-            }
-
+        accept_connections(
+            &self.listener,
+            self.whitelist.as_deref(),
+            &mut self.pending,
+            self.max_accepts_per_get,
+        );
+        for (mut stream, _) in self.pending.drain(..) {
             if stream.read(&mut buffer).is_ok() {
                 let data = Data::unpack(&buffer).unwrap();
                 self.data = Some(data);
@@ -169,6 +383,25 @@ impl<Data: Packable> Subscriber for TcpSubscriber<Data> {
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        accept_connections(
+            &self.listener,
+            self.whitelist.as_deref(),
+            &mut self.pending,
+            self.max_accepts_per_get,
+        );
+        !self.pending.is_empty()
+    }
+}
+
+impl<Data: Packable> ConsumingSubscriber for TcpSubscriber<Data> {
+    type Data = Data;
+
+    fn take(&mut self) -> Option<Self::Data> {
+        self.get();
+        self.data.take()
+    }
 }
 
 /// A Tcp Subscriber that stores incoming data into a clearable buffer
@@ -177,6 +410,8 @@ pub struct TcpBufferedSubscriber<Data: Packable> {
     pub whitelist: Option<Vec<IpAddr>>,
     /// The Tcp Listener for incoming data
     listener: TcpListener,
+    /// Accepted connections whose data has not yet been read
+    pending: Vec<(TcpStream, SocketAddr)>,
     /// The data buffer
     buffer: Vec<Data>,
 }
@@ -190,6 +425,7 @@ impl<Data: Packable> TcpBufferedSubscriber<Data> {
         Ok(Self {
             whitelist: None,
             listener,
+            pending: Vec::new(),
             buffer: Vec::new(),
         })
     }
@@ -206,6 +442,7 @@ impl<Data: Packable> TcpBufferedSubscriber<Data> {
         Ok(Self {
             whitelist: Some(whitelist),
             listener,
+            pending: Vec::new(),
             buffer: Vec::new(),
         })
     }
@@ -248,13 +485,13 @@ impl<Data: Packable> Subscriber for TcpBufferedSubscriber<Data> {
 
     fn get(&mut self) -> &Self::Target {
         let mut buffer = vec![0u8; Data::len()];
-        while let Ok((mut stream, socket_addr)) = self.listener.accept() {
-            if let Some(whitelist) = self.whitelist.as_ref() {
-                if !whitelist.contains(&socket_addr.ip()) {
-                    continue;
-                }
-            }
-
+        accept_connections(
+            &self.listener,
+            self.whitelist.as_deref(),
+            &mut self.pending,
+            None,
+        );
+        for (mut stream, _) in self.pending.drain(..) {
             if stream.read(&mut buffer).is_ok() {
                 let data = Data::unpack(&buffer).unwrap();
                 self.buffer.push(data);
@@ -264,6 +501,16 @@ impl<Data: Packable> Subscriber for TcpBufferedSubscriber<Data> {
 
         &self.buffer
     }
+
+    fn has_update(&mut self) -> bool {
+        accept_connections(
+            &self.listener,
+            self.whitelist.as_deref(),
+            &mut self.pending,
+            None,
+        );
+        !self.pending.is_empty()
+    }
 }
 
 /// A Tcp Subscriber that subscribes to a TCP stream keeping data
@@ -277,7 +524,9 @@ pub struct TcpTTLSubscriber<Data: Packable> {
     pub whitelist: Option<Vec<IpAddr>>,
     /// The Tcp Listener for incoming data
     listener: TcpListener,
-    /// The current data stored in the subscriber
+    /// Accepted connections whose data has not yet been read
+    pending: Vec<(TcpStream, SocketAddr)>,
+    /// The current data stored in the subscriber (kept after it expires)
     data: Option<(Data, Instant)>,
     /// The time-to-live of the packet
     ttl: Duration,
@@ -292,6 +541,7 @@ impl<Data: Packable> TcpTTLSubscriber<Data> {
         Ok(Self {
             whitelist: None,
             listener,
+            pending: Vec::new(),
             data: None,
             ttl,
         })
@@ -310,6 +560,7 @@ impl<Data: Packable> TcpTTLSubscriber<Data> {
         Ok(Self {
             whitelist: Some(whitelist),
             listener,
+            pending: Vec::new(),
             data: None,
             ttl,
         })
@@ -341,6 +592,21 @@ impl<Data: Packable> TcpTTLSubscriber<Data> {
             None
         }
     }
+
+    /// Get the instant the most recent piece of data was received (as of the
+    /// most recent call to `get`), even if that data has since expired.
+    pub fn last_seen(&self) -> Option<Instant> {
+        self.data.as_ref().map(|(_, received)| *received)
+    }
+
+    /// Check whether the publisher is alive (i.e. whether data was received
+    /// within the time-to-live).
+    ///
+    /// Note: Only data received as of the most recent call to `get` is considered.
+    pub fn is_alive(&self) -> bool {
+        self.last_seen()
+            .is_some_and(|last_seen| last_seen.elapsed() <= self.ttl)
+    }
 }
 
 impl<Data: Packable> Subscriber for TcpTTLSubscriber<Data> {
@@ -348,13 +614,13 @@ impl<Data: Packable> Subscriber for TcpTTLSubscriber<Data> {
 
     fn get(&mut self) -> &Self::Target {
         let mut buffer = vec![0u8; Data::len()];
-        while let Ok((mut stream, socket_addr)) = self.listener.accept() {
-            if let Some(whitelist) = self.whitelist.as_ref() {
-                if !whitelist.contains(&socket_addr.ip()) {
-                    continue;
-                }
-            }
-
+        accept_connections(
+            &self.listener,
+            self.whitelist.as_deref(),
+            &mut self.pending,
+            None,
+        );
+        for (mut stream, _) in self.pending.drain(..) {
             if stream.read(&mut buffer).is_ok() {
                 let data = Data::unpack(&buffer).unwrap();
                 self.data = Some((data, Instant::now()));
@@ -362,14 +628,28 @@ impl<Data: Packable> Subscriber for TcpTTLSubscriber<Data> {
             buffer.iter_mut().for_each(|v| *v = 0);
         }
 
-        if self.data.is_some()
-            && Instant::now().duration_since(self.data.as_ref().unwrap().1) > self.ttl
+        // Expired data is kept (but not returned) so the instant it was received
+        // is still known
+        if self
+            .data
+            .as_ref()
+            .is_some_and(|(_, received)| received.elapsed() > self.ttl)
         {
-            self.data = None;
+            return &None;
         }
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        accept_connections(
+            &self.listener,
+            self.whitelist.as_deref(),
+            &mut self.pending,
+            None,
+        );
+        !self.pending.is_empty()
+    }
 }
 
 /// A Tcp Subscriber that maps incoming data to its IP address.
@@ -384,6 +664,8 @@ impl<Data: Packable> Subscriber for TcpTTLSubscriber<Data> {
 pub struct TcpMappedSubscriber<Data: Packable> {
     /// the Tcp Listener for incoming data
     listener: TcpListener,
+    /// Accepted connections whose data has not yet been read
+    pending: Vec<(TcpStream, SocketAddr)>,
     /// The data currently stored in the subscriber
     data: HashMap<IpAddr, Data>,
 }
@@ -395,6 +677,7 @@ impl<Data: Packable> TcpMappedSubscriber<Data> {
         listener.set_nonblocking(true)?;
         Ok(Self {
             listener,
+            pending: Vec::new(),
             data: HashMap::new(),
         })
     }
@@ -405,16 +688,23 @@ impl<Data: Packable> Subscriber for TcpMappedSubscriber<Data> {
 
     fn get(&mut self) -> &Self::Target {
         let mut buffer = vec![0u8; Data::len()];
-        while let Ok((mut stream, socket_addr)) = self.listener.accept() {
-            if stream.read(&mut buffer).is_ok() {
+        accept_connections(&self.listener, None, &mut self.pending, None);
+        for (mut stream, _) in self.pending.drain(..) {
+            let address = mapped_address(stream.peer_addr(), stream.local_addr());
+            if let (Some(address), Ok(_)) = (address, stream.read(&mut buffer)) {
                 let data = Data::unpack(&buffer).unwrap();
-                self.data.insert(socket_addr.ip(), data);
+                self.data.insert(address, data);
             }
             buffer.iter_mut().for_each(|v| *v = 0);
         }
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        accept_connections(&self.listener, None, &mut self.pending, None);
+        !self.pending.is_empty()
+    }
 }
 
 /// A Tcp Subscriber that stores incoming data stored by IP Address with
@@ -422,9 +712,14 @@ impl<Data: Packable> Subscriber for TcpMappedSubscriber<Data> {
 ///
 /// Note: In this case time-to-live means the amount of time after the
 /// data has been received by the subscriber.
+///
+/// Like the TcpMappedSubscriber, incoming data is mapped by its peer address
+/// or (if the peer address is unavailable) its local address.
 pub struct TcpMappedTTLSubscriber<Data: Packable> {
     /// The Tcp Listener for incoming data
     listener: TcpListener,
+    /// Accepted connections whose data has not yet been read
+    pending: Vec<(TcpStream, SocketAddr)>,
     /// The map containing the most recent piece of data per socket
     /// address
     data: HashMap<IpAddr, (Data, Instant)>,
@@ -439,6 +734,7 @@ impl<Data: Packable> TcpMappedTTLSubscriber<Data> {
         listener.set_nonblocking(true)?;
         Ok(Self {
             listener,
+            pending: Vec::new(),
             data: HashMap::new(),
             ttl,
         })
@@ -450,10 +746,12 @@ impl<Data: Packable> Subscriber for TcpMappedTTLSubscriber<Data> {
 
     fn get(&mut self) -> &Self::Target {
         let mut buffer = vec![0u8; Data::len()];
-        while let Ok((mut stream, socket_addr)) = self.listener.accept() {
-            if stream.read(&mut buffer).is_ok() {
+        accept_connections(&self.listener, None, &mut self.pending, None);
+        for (mut stream, _) in self.pending.drain(..) {
+            let address = mapped_address(stream.peer_addr(), stream.local_addr());
+            if let (Some(address), Ok(_)) = (address, stream.read(&mut buffer)) {
                 let data = Data::unpack(&buffer).unwrap();
-                self.data.insert(socket_addr.ip(), (data, Instant::now()));
+                self.data.insert(address, (data, Instant::now()));
             }
             buffer.iter_mut().for_each(|v| *v = 0);
         }
@@ -463,6 +761,11 @@ impl<Data: Packable> Subscriber for TcpMappedTTLSubscriber<Data> {
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        accept_connections(&self.listener, None, &mut self.pending, None);
+        !self.pending.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -471,6 +774,7 @@ mod tests {
 
     use rand::random;
     use std::{
+        io::ErrorKind,
         net::{Ipv4Addr, SocketAddrV4},
         thread::sleep,
         time::Duration,
@@ -494,7 +798,10 @@ mod tests {
 
         fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
             if buffer.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
             } else {
                 Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
             }
@@ -502,7 +809,10 @@ mod tests {
 
         fn unpack(data: &[u8]) -> Result<Self, PackingError> {
             if data.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
             } else {
                 Ok(Self {
                     num: u64::from_le_bytes(data[..8].try_into().unwrap()),
@@ -531,6 +841,25 @@ mod tests {
         assert_eq!(subscriber.get().unwrap(), data);
     }
 
+    #[test]
+    fn test_take_tcp_subscriber() {
+        let mut publisher = TcpPublisher::new(
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6010))],
+            None,
+        );
+
+        let mut subscriber: TcpSubscriber<Data> =
+            TcpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6010)))
+                .unwrap();
+
+        let data = Data::new();
+        publisher.publish(data.clone()).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.take(), Some(data));
+        assert_eq!(subscriber.take(), None);
+    }
+
     #[test]
     fn test_publish_buffered_subscriber() {
         let mut publisher = TcpPublisher::new(
@@ -653,4 +982,114 @@ mod tests {
             data
         );
     }
+
+    #[test]
+    fn test_tcp_subscriber_has_update() {
+        let mut publisher = TcpPublisher::new(
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6007))],
+            None,
+        );
+
+        let mut subscriber: TcpSubscriber<Data> = TcpSubscriber::new_with_whitelist(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6007)),
+            vec![IpAddr::V4(Ipv4Addr::LOCALHOST)],
+        )
+        .unwrap();
+        assert!(!subscriber.has_update());
+
+        let data = Data::new();
+        publisher.publish(data).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert!(subscriber.has_update());
+        // Checking for an update does not consume the data
+        assert!(subscriber.has_update());
+        assert_eq!(subscriber.get().unwrap(), data);
+        assert!(!subscriber.has_update());
+    }
+
+    #[test]
+    fn test_mapped_address() {
+        let peer = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 6100));
+        let local = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6101));
+
+        // Data is mapped by peer address when the peer address is known
+        assert_eq!(mapped_address(Ok(peer), Ok(local)), Some(peer.ip()));
+
+        // And falls back to the local address when it isn't
+        assert_eq!(
+            mapped_address(Err(Error::from(ErrorKind::NotConnected)), Ok(local)),
+            Some(local.ip())
+        );
+        assert_eq!(
+            mapped_address(
+                Err(Error::from(ErrorKind::NotConnected)),
+                Err(Error::from(ErrorKind::NotConnected))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tcp_publish_error_source() {
+        // Nothing is listening on the address so the connection is refused
+        let mut publisher = TcpPublisher::new(
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6011))],
+            None,
+        );
+
+        let error: Box<dyn std::error::Error> =
+            Box::new(publisher.publish(Data::new()).unwrap_err());
+        assert!(error.to_string().starts_with("io error on 1 address(es)"));
+        let source = error.source().unwrap().downcast_ref::<Error>().unwrap();
+        assert_eq!(source.kind(), ErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn test_latched_tcp_publisher() {
+        let address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6012));
+        let mut publisher = TcpPublisher::new(vec![address], None);
+        publisher.set_latched(true);
+
+        // Nothing is listening when the data is published
+        let data = Data::new();
+        assert!(publisher.publish(data).is_err());
+
+        let mut subscriber: TcpSubscriber<Data> = TcpSubscriber::new(address).unwrap();
+        publisher.deliver_latched().unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(*subscriber.get(), Some(data));
+
+        // The latched data is only delivered once
+        publisher.deliver_latched().unwrap();
+        sleep(Duration::from_millis(50));
+        assert!(!subscriber.has_update());
+    }
+
+    #[test]
+    fn test_tcp_subscriber_max_accepts_per_get() {
+        let mut publisher = TcpPublisher::new(
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6013))],
+            None,
+        );
+
+        let mut subscriber: TcpSubscriber<Data> =
+            TcpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6013)))
+                .unwrap();
+        subscriber.set_backlog(16).unwrap();
+        subscriber.set_max_accepts_per_get(Some(2));
+
+        for num in 1..=5 {
+            publisher.publish(Data { num }).unwrap();
+        }
+        sleep(Duration::from_millis(50));
+
+        // Connections are read from in the order they were made, two per get
+        assert_eq!(subscriber.get().unwrap(), Data { num: 2 });
+        assert!(subscriber.has_update());
+        assert_eq!(subscriber.get().unwrap(), Data { num: 4 });
+        assert_eq!(subscriber.get().unwrap(), Data { num: 5 });
+        assert!(!subscriber.has_update());
+    }
 }
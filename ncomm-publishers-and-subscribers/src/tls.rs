@@ -0,0 +1,495 @@
+//!
+//! A TLS-Encrypted Tcp-Based Publisher and Subscriber
+//!
+//! The TLS Tcp Publisher sends data via a TLS session over a TCP Stream to a
+//! bound listener on the Subscriber end.  The Subscriber acts as the TLS server
+//! (presenting a certificate) and the Publisher verifies the Subscriber's
+//! certificate against a trust anchor before sending any data.
+//!
+//! The packed data is framed exactly as it is by the Tcp Publisher, only the
+//! transport is encrypted.
+//!
+//! Note: Publishing blocks until the TLS handshake with each Subscriber has
+//! completed, so the Subscriber must be polled from another thread or process.
+//!
+
+use std::{
+    io::{Error, ErrorKind, Read, Write},
+    marker::PhantomData,
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use rustls::{
+    crypto::ring::default_provider,
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName},
+    ClientConfig, ClientConnection, RootCertStore, ServerConfig, ServerConnection, StreamOwned,
+};
+
+use ncomm_core::{Publisher, Subscriber};
+use ncomm_utils::packing::Packable;
+
+use crate::tcp::TcpPublishError;
+
+/// The longest time a Subscriber keeps a connection open for it to complete the
+/// TLS handshake and send its data
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// An Error when creating a TLS Tcp Publisher or Subscriber
+#[derive(Debug)]
+pub enum TlsError {
+    /// std::io::Error occurred
+    IOError(Error),
+    /// The TLS configuration (i.e. a certificate or key) was invalid
+    ConfigError(rustls::Error),
+}
+
+impl std::fmt::Display for TlsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IOError(err) => write!(f, "io error: {}", err),
+            Self::ConfigError(err) => write!(f, "tls configuration error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(err) => Some(err),
+            Self::ConfigError(err) => Some(err),
+        }
+    }
+}
+
+/// Convert a TLS error into a std::io::Error
+fn tls_io_error(err: rustls::Error) -> Error {
+    Error::new(ErrorKind::InvalidData, err)
+}
+
+/// A TLS Tcp Publisher that publishes data via packing the data
+/// according to the data's Packable implementation and sending it over
+/// a TLS session with each address
+pub struct TlsTcpPublisher<Data: Packable> {
+    /// The list of addresses to publish to
+    pub addresses: Vec<SocketAddr>,
+    /// The name the subscribers' certificates must be valid for
+    server_name: ServerName<'static>,
+    /// The TLS configuration of the publisher
+    config: Arc<ClientConfig>,
+    /// The amount of time to block when sending data
+    write_timeout: Option<Duration>,
+    /// The buffer data is packed into before being sent (reused across publishes)
+    buffer: Vec<u8>,
+    /// A marker to bind the specific type of data to send to
+    /// the publisher
+    phantom: PhantomData<Data>,
+}
+
+impl<Data: Packable> TlsTcpPublisher<Data> {
+    /// Create a new TlsTcpPublisher that trusts subscribers presenting a certificate
+    /// for `server_name` signed by the `trust_anchor` (or the trust anchor itself
+    /// for self-signed certificates)
+    pub fn new(
+        send_addresses: Vec<SocketAddr>,
+        server_name: ServerName<'static>,
+        trust_anchor: CertificateDer<'static>,
+        write_timeout: Option<Duration>,
+    ) -> Result<Self, TlsError> {
+        let mut roots = RootCertStore::empty();
+        roots.add(trust_anchor).map_err(TlsError::ConfigError)?;
+        let config = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(TlsError::ConfigError)?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        Ok(Self {
+            addresses: send_addresses,
+            server_name,
+            config: Arc::new(config),
+            write_timeout,
+            buffer: Vec::with_capacity(Data::len()),
+            phantom: PhantomData,
+        })
+    }
+
+    /// Send the packed data in the buffer over a new TLS session with an address
+    fn send_to(&self, address: &SocketAddr) -> Result<(), Error> {
+        let socket = TcpStream::connect(address)?;
+        socket.set_write_timeout(self.write_timeout)?;
+        socket.set_read_timeout(self.write_timeout)?;
+        let connection = ClientConnection::new(self.config.clone(), self.server_name.clone())
+            .map_err(tls_io_error)?;
+
+        let mut stream = StreamOwned::new(connection, socket);
+        stream.write_all(&self.buffer)?;
+        stream.conn.send_close_notify();
+        stream.flush()
+    }
+}
+
+impl<Data: Packable> Publisher for TlsTcpPublisher<Data> {
+    type Data = Data;
+    type Error = TcpPublishError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        self.buffer.clear();
+        self.buffer.resize(Data::len(), 0);
+        data.pack(&mut self.buffer)
+            .map_err(TcpPublishError::PackingError)?;
+
+        let publish_errors: Vec<Error> = self
+            .addresses
+            .iter()
+            .filter_map(|address| self.send_to(address).err())
+            .collect();
+
+        if publish_errors.is_empty() {
+            Ok(())
+        } else {
+            Err(TcpPublishError::IOError(publish_errors))
+        }
+    }
+}
+
+/// A TLS session accepted by a subscriber whose data hasn't been fully received
+struct PendingSession {
+    /// The (nonblocking) connection to the publisher
+    socket: TcpStream,
+    /// The server side of the TLS session
+    connection: ServerConnection,
+    /// The buffer the packed data is read into
+    buffer: Vec<u8>,
+    /// The number of bytes of packed data read so far
+    received: usize,
+    /// The instant the connection was accepted
+    accepted: Instant,
+}
+
+impl PendingSession {
+    /// Drive the TLS session as far as possible without blocking, returning
+    /// whether all of the packed data has been received.
+    fn poll(&mut self) -> Result<bool, Error> {
+        loop {
+            while self.connection.wants_write() {
+                match self.connection.write_tls(&mut self.socket) {
+                    Ok(_) => (),
+                    Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                    Err(err) => return Err(err),
+                }
+            }
+
+            match self
+                .connection
+                .reader()
+                .read(&mut self.buffer[self.received..])
+            {
+                Ok(0) => return Err(Error::from(ErrorKind::UnexpectedEof)),
+                Ok(read) => {
+                    self.received += read;
+                    if self.received == self.buffer.len() {
+                        return Ok(true);
+                    }
+                    continue;
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => (),
+                Err(err) => return Err(err),
+            }
+
+            match self.connection.read_tls(&mut self.socket) {
+                Ok(0) => return Err(Error::from(ErrorKind::UnexpectedEof)),
+                Ok(_) => {
+                    self.connection
+                        .process_new_packets()
+                        .map_err(tls_io_error)?;
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(false),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// A TLS Tcp Subscriber that is set to nonblocking and listens for incoming
+/// TLS sessions, presenting its certificate to each publisher
+///
+/// Note: The TLS handshake is driven without blocking over as many calls to `get`
+/// (or `has_update`) as it takes so a slow publisher never stalls the subscriber.
+/// Sessions that haven't delivered their data within a second are dropped.
+pub struct TlsTcpSubscriber<Data: Packable> {
+    /// The Tcp Listener for incoming data
+    listener: TcpListener,
+    /// The TLS configuration of the subscriber
+    config: Arc<ServerConfig>,
+    /// The accepted sessions whose data hasn't been fully received
+    pending: Vec<PendingSession>,
+    /// The maximum number of connections accepted per call to `get`
+    max_accepts_per_get: Option<usize>,
+    /// The current data stored in the subscriber
+    data: Option<Data>,
+    /// Whether data has been received since the data was last read
+    updated: bool,
+}
+
+impl<Data: Packable> TlsTcpSubscriber<Data> {
+    /// Create a new TlsTcpSubscriber bound to a specific address that presents
+    /// a certificate chain (and the chain's private key) to publishers
+    pub fn new(
+        bind_address: SocketAddr,
+        cert_chain: Vec<CertificateDer<'static>>,
+        key: PrivateKeyDer<'static>,
+    ) -> Result<Self, TlsError> {
+        let config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(TlsError::ConfigError)?
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, key)
+            .map_err(TlsError::ConfigError)?;
+
+        let listener = TcpListener::bind(bind_address).map_err(TlsError::IOError)?;
+        listener.set_nonblocking(true).map_err(TlsError::IOError)?;
+        Ok(Self {
+            listener,
+            config: Arc::new(config),
+            pending: Vec::new(),
+            max_accepts_per_get: None,
+            data: None,
+            updated: false,
+        })
+    }
+
+    /// Set the maximum number of connections accepted per call to `get` (or
+    /// `has_update`).  By default, every waiting connection is accepted.
+    ///
+    /// Note: Connections beyond the limit are left in the listener's backlog until
+    /// the next call to `get` so a burst of connections can't monopolize a
+    /// single call to `get`.
+    pub fn set_max_accepts_per_get(&mut self, max_accepts_per_get: Option<usize>) {
+        self.max_accepts_per_get = max_accepts_per_get;
+    }
+
+    /// Accept a new TLS session over an incoming connection
+    fn accept(&self, socket: TcpStream) -> Result<PendingSession, Error> {
+        socket.set_nonblocking(true)?;
+        let connection = ServerConnection::new(self.config.clone()).map_err(tls_io_error)?;
+        Ok(PendingSession {
+            socket,
+            connection,
+            buffer: vec![0u8; Data::len()],
+            received: 0,
+            accepted: Instant::now(),
+        })
+    }
+
+    /// Accept incoming connections and drive every pending session, returning
+    /// whether any data was received
+    fn receive_all(&mut self) -> bool {
+        let max_accepts = self.max_accepts_per_get.unwrap_or(usize::MAX);
+        let mut accepted = 0;
+        while accepted < max_accepts {
+            let Ok((socket, _)) = self.listener.accept() else {
+                break;
+            };
+            accepted += 1;
+            if let Ok(session) = self.accept(socket) {
+                self.pending.push(session);
+            }
+        }
+
+        let mut received = false;
+        let mut idx = 0;
+        while idx < self.pending.len() {
+            let session = &mut self.pending[idx];
+            match session.poll() {
+                Ok(false) if session.accepted.elapsed() < READ_TIMEOUT => idx += 1,
+                Ok(true) => {
+                    if let Ok(data) = Data::unpack(&session.buffer) {
+                        self.data = Some(data);
+                        received = true;
+                    }
+                    self.pending.swap_remove(idx);
+                }
+                _ => {
+                    self.pending.swap_remove(idx);
+                }
+            }
+        }
+        received
+    }
+}
+
+impl<Data: Packable> Subscriber for TlsTcpSubscriber<Data> {
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        self.receive_all();
+        self.updated = false;
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        // Connections can't be peeked without completing the handshake so the
+        // data is received (and held until the next `get`)
+        if self.receive_all() {
+            self.updated = true;
+        }
+        self.updated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::random;
+    use rustls::pki_types::PrivatePkcs8KeyDer;
+    use std::{
+        net::{Ipv4Addr, SocketAddrV4},
+        thread,
+        time::Instant,
+    };
+
+    use ncomm_utils::packing::PackingError;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Data {
+        num: u64,
+    }
+
+    impl Data {
+        pub fn new() -> Self {
+            Self { num: random() }
+        }
+    }
+
+    impl Packable for Data {
+        fn len() -> usize {
+            8
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            if buffer.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
+            } else {
+                Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
+            }
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            if data.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
+            } else {
+                Ok(Self {
+                    num: u64::from_le_bytes(data[..8].try_into().unwrap()),
+                })
+            }
+        }
+    }
+
+    /// A self-signed certificate for localhost (and its private key)
+    fn localhost_identity() -> (CertificateDer<'static>, PrivateKeyDer<'static>) {
+        let cert = CertificateDer::from(include_bytes!("../test-data/localhost.cert.der").to_vec());
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(
+            include_bytes!("../test-data/localhost.key.der").to_vec(),
+        ));
+        (cert, key)
+    }
+
+    #[test]
+    fn test_publish_tls_tcp_subscriber() {
+        let (cert, key) = localhost_identity();
+        let address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6008));
+
+        let mut subscriber: TlsTcpSubscriber<Data> =
+            TlsTcpSubscriber::new(address, vec![cert.clone()], key).unwrap();
+        let mut publisher: TlsTcpPublisher<Data> = TlsTcpPublisher::new(
+            vec![address],
+            ServerName::try_from("localhost").unwrap(),
+            cert,
+            Some(Duration::from_secs(1)),
+        )
+        .unwrap();
+
+        let original_data = Data::new();
+        let handle = thread::spawn(move || publisher.publish(original_data));
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while subscriber.get().is_none() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(handle.join().unwrap().is_ok());
+        assert_eq!(subscriber.get().unwrap(), original_data);
+    }
+
+    #[test]
+    fn test_tls_tcp_subscriber_stalled_connection() {
+        let (cert, key) = localhost_identity();
+        let address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6014));
+
+        let mut subscriber: TlsTcpSubscriber<Data> =
+            TlsTcpSubscriber::new(address, vec![cert.clone()], key).unwrap();
+        subscriber.set_max_accepts_per_get(Some(1));
+        let mut publisher: TlsTcpPublisher<Data> = TlsTcpPublisher::new(
+            vec![address],
+            ServerName::try_from("localhost").unwrap(),
+            cert,
+            Some(Duration::from_secs(1)),
+        )
+        .unwrap();
+
+        // A connection that never starts the handshake doesn't stall the subscriber
+        let _stalled = TcpStream::connect(address).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        let start = Instant::now();
+        assert!(subscriber.get().is_none());
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        let original_data = Data::new();
+        let handle = thread::spawn(move || publisher.publish(original_data));
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while subscriber.get().is_none() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(handle.join().unwrap().is_ok());
+        assert_eq!(subscriber.get().unwrap(), original_data);
+    }
+
+    #[test]
+    fn test_publish_tls_tcp_subscriber_untrusted_certificate() {
+        let (cert, key) = localhost_identity();
+        let address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 6009));
+
+        let mut subscriber: TlsTcpSubscriber<Data> =
+            TlsTcpSubscriber::new(address, vec![cert.clone()], key).unwrap();
+        // The certificate isn't valid for the name the publisher expects
+        let mut publisher: TlsTcpPublisher<Data> = TlsTcpPublisher::new(
+            vec![address],
+            ServerName::try_from("robot.local").unwrap(),
+            cert,
+            Some(Duration::from_secs(1)),
+        )
+        .unwrap();
+
+        let handle = thread::spawn(move || publisher.publish(Data::new()));
+
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while !handle.is_finished() && Instant::now() < deadline {
+            subscriber.get();
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(matches!(
+            handle.join().unwrap(),
+            Err(TcpPublishError::IOError(_))
+        ));
+        assert!(subscriber.get().is_none());
+    }
+}
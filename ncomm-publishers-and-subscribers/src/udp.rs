@@ -7,17 +7,61 @@
 use std::{
     collections::HashMap,
     hash::Hash,
-    io::Error,
+    io::{Error, ErrorKind},
     marker::PhantomData,
     net::{SocketAddr, UdpSocket},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
-use ncomm_core::{Publisher, Subscriber};
+use socket2::SockRef;
+
+use ncomm_core::{AddressedPublisher, ConsumingSubscriber, Publisher, Subscriber};
 use ncomm_utils::packing::{Packable, PackingError};
 
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "hmac")]
+use ring::hmac;
+
+/// The length of the count prepended to a batch of data
+const BATCH_HEADER_LEN: usize = 2;
+
+/// The largest datagram a batch of data is packed into (the largest UDP payload
+/// that fits in a standard 1500 byte Ethernet MTU)
+const MAX_BATCH_DATAGRAM_SIZE: usize = 1472;
+
+/// Get the number of pieces of data that fit into a single batch datagram
+fn max_batch_len<Data: Packable>() -> usize {
+    ((MAX_BATCH_DATAGRAM_SIZE - BATCH_HEADER_LEN) / Data::len().max(1)).clamp(1, u16::MAX as usize)
+}
+
+/// Unpack a batch of data sent by `UdpPublisher::publish_batch` into a buffer,
+/// ignoring the datagram if it isn't a valid batch
+fn unpack_batch<Data: Packable>(datagram: &[u8], buffer: &mut Vec<Data>) {
+    if datagram.len() < BATCH_HEADER_LEN || Data::len() == 0 {
+        return;
+    }
+
+    let count = u16::from_le_bytes([datagram[0], datagram[1]]) as usize;
+    if datagram.len() != BATCH_HEADER_LEN + count * Data::len() {
+        return;
+    }
+
+    buffer.extend(
+        datagram[BATCH_HEADER_LEN..]
+            .chunks_exact(Data::len())
+            .filter_map(|packed_data| Data::unpack(packed_data).ok()),
+    );
+}
+
 /// A UDP Publisher that publishes data in a way defined by the Packable
 /// layout to a group of addresses
+///
+/// Note: By default, data (including batches of data) is sent before `publish`
+/// returns.  With a batch size (see `set_batch_size`), published data is buffered
+/// and sent in batches and any buffered data is sent when the publisher is flushed
+/// or dropped.
 pub struct UdpPublisher<Data: Packable> {
     // the UdpSocket bound for transmission
     tx: UdpSocket,
@@ -27,6 +71,16 @@ pub struct UdpPublisher<Data: Packable> {
     /// to publish to in a way that is specific to the implementation of
     /// ToSocketAddrs
     pub addresses: Vec<SocketAddr>,
+    // The buffer data is packed into before being sent (reused across publishes)
+    buffer: Vec<u8>,
+    /// The maximum number of pieces of data buffered before they are sent as a
+    /// batch (if published data is batched)
+    batch_size: Option<usize>,
+    /// The packed data published since the last batch was sent (prefixed by the
+    /// batch header)
+    batch: Vec<u8>,
+    /// The number of pieces of data in the batch
+    batch_len: usize,
     // A PhantomAddress to bind the specific type of data to send to the
     // publisher
     phantom: PhantomData<Data>,
@@ -40,9 +94,133 @@ impl<Data: Packable> UdpPublisher<Data> {
         Ok(Self {
             tx,
             addresses: send_addresses,
+            buffer: Vec::with_capacity(Data::len()),
+            batch_size: None,
+            batch: Vec::new(),
+            batch_len: 0,
             phantom: PhantomData,
         })
     }
+
+    /// Buffer published data, sending it in batches of (at most) `batch_size`
+    /// pieces of data (or as many pieces of data as fit within a standard MTU).
+    /// By default, published data isn't batched.
+    ///
+    /// Note: Any data already buffered is sent before the batch size is changed.
+    /// Batches can only be received by a UdpBufferedSubscriber and data published
+    /// to specific addresses (via `publish_to`) is never batched.
+    pub fn set_batch_size(&mut self, batch_size: Option<usize>) -> Result<(), UdpPublishError> {
+        self.flush()?;
+        self.batch_size = batch_size.map(|batch_size| batch_size.clamp(1, max_batch_len::<Data>()));
+        Ok(())
+    }
+
+    /// Send any buffered data to every address as a single batch.
+    pub fn flush(&mut self) -> Result<(), UdpPublishError> {
+        if self.batch_len == 0 {
+            return Ok(());
+        }
+
+        self.batch[..BATCH_HEADER_LEN].copy_from_slice(&(self.batch_len as u16).to_le_bytes());
+        let result = self.addresses.iter().try_for_each(|address| {
+            self.tx
+                .send_to(&self.batch, address)
+                .map(|_| ())
+                .map_err(UdpPublishError::IOError)
+        });
+        self.batch.clear();
+        self.batch_len = 0;
+        result
+    }
+
+    /// Pack a piece of data into the publisher's send buffer
+    fn pack_into_buffer(&mut self, data: Data) -> Result<(), UdpPublishError> {
+        self.buffer.clear();
+        self.buffer.resize(Data::len(), 0);
+        data.pack(&mut self.buffer)
+            .map_err(UdpPublishError::PackingError)
+    }
+
+    /// Publish a batch of data, packing as many pieces of data as fit within
+    /// a standard MTU into each datagram.
+    ///
+    /// Note: Each datagram is prefixed with the number of pieces of data it contains
+    /// so batches can only be received by a UdpBufferedSubscriber.
+    pub fn publish_batch(&mut self, data: &[Data]) -> Result<(), UdpPublishError>
+    where
+        Data: Clone,
+    {
+        let data_len = Data::len().max(1);
+        for batch in data.chunks(max_batch_len::<Data>()) {
+            self.buffer.clear();
+            self.buffer
+                .extend_from_slice(&(batch.len() as u16).to_le_bytes());
+            self.buffer
+                .resize(BATCH_HEADER_LEN + batch.len() * Data::len(), 0);
+            for (data, packed_data) in batch
+                .iter()
+                .zip(self.buffer[BATCH_HEADER_LEN..].chunks_mut(data_len))
+            {
+                data.clone()
+                    .pack(packed_data)
+                    .map_err(UdpPublishError::PackingError)?;
+            }
+
+            for address in self.addresses.iter() {
+                self.tx
+                    .send_to(&self.buffer, address)
+                    .map_err(UdpPublishError::IOError)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a new UdpPublisher that can send to broadcast addresses.
+    ///
+    /// Note: The broadcast addresses should be the broadcast address of a subnet
+    /// (i.e. `x.x.x.255` for a /24 subnet) or the limited broadcast address
+    /// `255.255.255.255`.  Subscribers receiving broadcast data should be bound to
+    /// the unspecified address (`0.0.0.0`) on the broadcast port.
+    pub fn new_broadcast(
+        bind_address: SocketAddr,
+        broadcast_addresses: Vec<SocketAddr>,
+    ) -> Result<Self, Error> {
+        let publisher = Self::new(bind_address, broadcast_addresses)?;
+        publisher.tx.set_broadcast(true)?;
+        Ok(publisher)
+    }
+
+    /// Set the IP time-to-live of the publisher's socket (i.e. the number of hops
+    /// datagrams may take)
+    pub fn with_ttl(self, ttl: u32) -> Result<Self, Error> {
+        self.tx.set_ttl(ttl)?;
+        Ok(self)
+    }
+
+    /// Set the size of the publisher's socket's receive buffer.
+    ///
+    /// Note: The OS may round (or cap) the size of the buffer.
+    pub fn with_recv_buffer_size(self, size: usize) -> Result<Self, Error> {
+        SockRef::from(&self.tx).set_recv_buffer_size(size)?;
+        Ok(self)
+    }
+
+    /// Set the size of the publisher's socket's send buffer.
+    ///
+    /// Note: The OS may round (or cap) the size of the buffer.
+    pub fn with_send_buffer_size(self, size: usize) -> Result<Self, Error> {
+        SockRef::from(&self.tx).set_send_buffer_size(size)?;
+        Ok(self)
+    }
+
+    /// Get the address the publisher's socket is bound to.
+    ///
+    /// Note: This is useful when the publisher is bound to port 0 (to have the OS
+    /// assign a port) as it reports the port that was assigned.
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        self.tx.local_addr()
+    }
 }
 
 /// An Error with publishing udp packets
@@ -52,6 +230,35 @@ pub enum UdpPublishError {
     IOError(Error),
     /// An error occurred wth packing the data
     PackingError(PackingError),
+    /// The data sent to an address was never acknowledged (only returned by
+    /// reliable publishers)
+    Unacknowledged(SocketAddr),
+
+    /// The address to publish to isn't one of the publisher's addresses
+    UnknownAddress(SocketAddr),
+}
+
+impl std::fmt::Display for UdpPublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IOError(err) => write!(f, "io error: {}", err),
+            Self::PackingError(err) => write!(f, "packing error: {}", err),
+            Self::Unacknowledged(address) => {
+                write!(f, "data sent to {} was never acknowledged", address)
+            }
+            Self::UnknownAddress(address) => write!(f, "unknown address {}", address),
+        }
+    }
+}
+
+impl std::error::Error for UdpPublishError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(err) => Some(err),
+            Self::PackingError(err) => Some(err),
+            _ => None,
+        }
+    }
 }
 
 impl<Data: Packable> Publisher for UdpPublisher<Data> {
@@ -59,13 +266,62 @@ impl<Data: Packable> Publisher for UdpPublisher<Data> {
     type Error = UdpPublishError;
 
     fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
-        let mut packed_data = vec![0u8; Data::len()];
-        data.pack(&mut packed_data)
-            .map_err(UdpPublishError::PackingError)?;
+        if let Some(batch_size) = self.batch_size {
+            if self.batch.is_empty() {
+                self.batch.resize(BATCH_HEADER_LEN, 0);
+            }
+            let start = self.batch.len();
+            self.batch.resize(start + Data::len(), 0);
+            if let Err(err) = data.pack(&mut self.batch[start..]) {
+                self.batch.truncate(start);
+                return Err(UdpPublishError::PackingError(err));
+            }
+            self.batch_len += 1;
+
+            if self.batch_len >= batch_size {
+                self.flush()?;
+            }
+            return Ok(());
+        }
+
+        self.pack_into_buffer(data)?;
 
         for address in self.addresses.iter() {
             self.tx
-                .send_to(&packed_data, address)
+                .send_to(&self.buffer, address)
+                .map_err(UdpPublishError::IOError)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<Data: Packable> Drop for UdpPublisher<Data> {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            #[cfg(feature = "log")]
+            log::error!("failed to send buffered data when dropping a udp publisher: {err}");
+            #[cfg(not(feature = "log"))]
+            let _ = err;
+        }
+    }
+}
+
+impl<Data: Packable> AddressedPublisher<SocketAddr> for UdpPublisher<Data> {
+    fn publish_to(
+        &mut self,
+        data: Self::Data,
+        addresses: &[SocketAddr],
+    ) -> Result<(), Self::Error> {
+        if let Some(address) = addresses.iter().find(|v| !self.addresses.contains(v)) {
+            return Err(UdpPublishError::UnknownAddress(*address));
+        }
+
+        self.pack_into_buffer(data)?;
+
+        for address in addresses.iter() {
+            self.tx
+                .send_to(&self.buffer, address)
                 .map_err(UdpPublishError::IOError)?;
         }
 
@@ -78,6 +334,8 @@ impl<Data: Packable> Publisher for UdpPublisher<Data> {
 pub struct UdpSubscriber<Data: Packable> {
     /// The receiving UdpSocket
     rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
     /// The current data stored in the subscriber
     data: Option<Data>,
 }
@@ -87,7 +345,43 @@ impl<Data: Packable> UdpSubscriber<Data> {
     pub fn new(bind_address: SocketAddr) -> Result<Self, Error> {
         let rx = UdpSocket::bind(bind_address)?;
         rx.set_nonblocking(true)?;
-        Ok(Self { rx, data: None })
+        Ok(Self {
+            rx,
+            // The buffer has room for one extra byte to detect oversized datagrams
+            recv_buffer: vec![0u8; Data::len() + 1],
+            data: None,
+        })
+    }
+
+    /// Set the IP time-to-live of the subscriber's socket (i.e. the number of hops
+    /// datagrams may take)
+    pub fn with_ttl(self, ttl: u32) -> Result<Self, Error> {
+        self.rx.set_ttl(ttl)?;
+        Ok(self)
+    }
+
+    /// Set the size of the subscriber's socket's receive buffer.
+    ///
+    /// Note: The OS may round (or cap) the size of the buffer.
+    pub fn with_recv_buffer_size(self, size: usize) -> Result<Self, Error> {
+        SockRef::from(&self.rx).set_recv_buffer_size(size)?;
+        Ok(self)
+    }
+
+    /// Set the size of the subscriber's socket's send buffer.
+    ///
+    /// Note: The OS may round (or cap) the size of the buffer.
+    pub fn with_send_buffer_size(self, size: usize) -> Result<Self, Error> {
+        SockRef::from(&self.rx).set_send_buffer_size(size)?;
+        Ok(self)
+    }
+
+    /// Get the address the subscriber's socket is bound to.
+    ///
+    /// Note: This is useful when the subscriber is bound to port 0 (to have the OS
+    /// assign a port) as it reports the port that was assigned.
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        self.rx.local_addr()
     }
 }
 
@@ -97,13 +391,16 @@ impl<Data: Packable> Subscriber for UdpSubscriber<Data> {
     fn get(&mut self) -> &Self::Target {
         let mut data = None;
 
-        let mut buffer = vec![0u8; Data::len()];
         loop {
-            let temp = match self.rx.recv_from(&mut buffer) {
-                Ok(_received) => Data::unpack(&buffer[..]),
+            let temp = match self.rx.recv_from(&mut self.recv_buffer) {
+                Ok((received, _)) if received == Data::len() => {
+                    Data::unpack(&self.recv_buffer[..received])
+                }
+                // Datagrams of the wrong size can't be valid data
+                Ok(_) => continue,
                 Err(_) => break,
             };
-            buffer.iter_mut().for_each(|v| *v = 0);
+            self.recv_buffer.iter_mut().for_each(|v| *v = 0);
             if let Ok(found_data) = temp {
                 data = Some(found_data);
             }
@@ -115,14 +412,48 @@ impl<Data: Packable> Subscriber for UdpSubscriber<Data> {
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
+}
+
+impl<Data: Packable> ConsumingSubscriber for UdpSubscriber<Data> {
+    type Data = Data;
+
+    fn take(&mut self) -> Option<Self::Data> {
+        self.get();
+        self.data.take()
+    }
+}
+
+/// Data that carries a sequence number that increases by one with every piece
+/// of data published, allowing subscribers to detect lost data
+pub trait Sequenced {
+    /// Get the sequence number of the data
+    fn sequence(&self) -> u64;
 }
 
 /// A Udp Subscriber that stores incoming data into a clearable buffer
+///
+/// Note: The buffer grows until it is cleared unless the subscriber is bounded
+/// (see `new_bounded`).
 pub struct UdpBufferedSubscriber<Data: Packable> {
     /// The UdpSocket to receive data through
     rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
     /// The data buffer
     buffer: Vec<Data>,
+    /// Gets the sequence number of data (only for sequence-aware subscribers)
+    sequence_of: Option<fn(&Data) -> u64>,
+    /// The highest sequence number received
+    highest_sequence: Option<u64>,
+    /// The maximum number of pieces of data kept in the buffer (only for bounded subscribers)
+    capacity: Option<usize>,
+    /// The number of pieces of data missing from the received sequence numbers
+    /// or dropped from the front of a full buffer
+    dropped: u64,
 }
 
 impl<Data: Packable> UdpBufferedSubscriber<Data> {
@@ -132,34 +463,186 @@ impl<Data: Packable> UdpBufferedSubscriber<Data> {
         rx.set_nonblocking(true)?;
         Ok(Self {
             rx,
+            // The buffer has room for one extra byte to detect oversized datagrams
+            recv_buffer: vec![0u8; MAX_BATCH_DATAGRAM_SIZE.max(BATCH_HEADER_LEN + Data::len()) + 1],
             buffer: Vec::new(),
+            sequence_of: None,
+            highest_sequence: None,
+            capacity: None,
+            dropped: 0,
         })
     }
 
+    /// Create a new bounded UdpBufferedSubscriber bound to a specific bind address
+    /// that keeps only the most recent `capacity` pieces of data.
+    ///
+    /// Note: When the buffer is full the oldest data is dropped to make room for
+    /// newer data, and the dropped data is counted (see `dropped_count`).
+    pub fn new_bounded(bind_address: SocketAddr, capacity: usize) -> Result<Self, Error> {
+        let mut subscriber = Self::new(bind_address)?;
+        subscriber.buffer.reserve_exact(capacity);
+        subscriber.capacity = Some(capacity);
+        Ok(subscriber)
+    }
+
+    /// Create a new sequence-aware UdpBufferedSubscriber bound to a specific bind
+    /// address that counts the pieces of data lost (see `dropped_count`).
+    pub fn new_sequenced(bind_address: SocketAddr) -> Result<Self, Error>
+    where
+        Data: Sequenced,
+    {
+        let mut subscriber = Self::new(bind_address)?;
+        subscriber.sequence_of = Some(Data::sequence);
+        Ok(subscriber)
+    }
+
     /// Clear the buffer contained by the UdpBufferedSubscriber
     pub fn clear(&mut self) {
         self.buffer.clear();
     }
+
+    /// Get the number of pieces of data that were skipped over in the sequence
+    /// numbers received or dropped from a full buffer (as of the most recent call
+    /// to `get`).
+    ///
+    /// Note: Only sequence-aware subscribers (created via `new_sequenced`) count
+    /// skipped sequence numbers and only bounded subscribers (created via
+    /// `new_bounded`) drop data from the buffer.  Data that arrives out of order
+    /// after a later sequence number is still counted as dropped.
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped
+    }
 }
 
 impl<Data: Packable> Subscriber for UdpBufferedSubscriber<Data> {
     type Target = Vec<Data>;
 
     fn get(&mut self) -> &Self::Target {
-        let mut buffer = vec![0u8; Data::len()];
+        let received_from = self.buffer.len();
         loop {
-            let temp = match self.rx.recv_from(&mut buffer) {
-                Ok(_received) => Data::unpack(&buffer[..]),
+            match self.rx.recv_from(&mut self.recv_buffer) {
+                Ok((received, _)) if received == Data::len() => {
+                    if let Ok(found_data) = Data::unpack(&self.recv_buffer[..received]) {
+                        self.buffer.push(found_data);
+                    }
+                }
+                // Datagrams of any other size can only be valid as a batch of data
+                Ok((received, _)) => unpack_batch(&self.recv_buffer[..received], &mut self.buffer),
                 Err(_) => break,
-            };
-            buffer.iter_mut().for_each(|v| *v = 0);
-            if let Ok(found_data) = temp {
-                self.buffer.push(found_data);
+            }
+        }
+
+        if let Some(sequence_of) = self.sequence_of {
+            for data in self.buffer[received_from..].iter() {
+                let sequence = sequence_of(data);
+                match self.highest_sequence {
+                    Some(highest) if sequence <= highest => (),
+                    Some(highest) => {
+                        self.dropped += sequence - highest - 1;
+                        self.highest_sequence = Some(sequence);
+                    }
+                    None => self.highest_sequence = Some(sequence),
+                }
+            }
+        }
+
+        if let Some(capacity) = self.capacity {
+            let overflow = self.buffer.len().saturating_sub(capacity);
+            if overflow > 0 {
+                self.buffer.drain(..overflow);
+                self.dropped += overflow as u64;
             }
         }
 
         &self.buffer
     }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
+}
+
+/// Data that carries the (wall-clock) time it was sent at so the one-way
+/// latency of the data can be measured by a UdpTimestampedSubscriber
+pub trait SenderTimestamped {
+    /// Get the time the data was sent
+    fn sent_at(&self) -> SystemTime;
+}
+
+/// A UDP Subscriber that updates its internal data representation with the
+/// most recent piece of data and the instant it was received.
+///
+/// Unlike the UdpTTLSubscriber, data received by the UdpTimestampedSubscriber
+/// never expires.
+///
+/// Note: Data is received (and timestamped) when the subscriber is dereferenced
+/// so the subscriber should be polled frequently for accurate timestamps.
+pub struct UdpTimestampedSubscriber<Data: Packable> {
+    /// The UdpSocket to receive data through
+    rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
+    /// The most recent data contained by the subscriber and the instant it
+    /// was received
+    data: Option<(Data, Instant)>,
+    /// The wall-clock time the most recent data was received
+    received_at: Option<SystemTime>,
+}
+
+impl<Data: Packable> UdpTimestampedSubscriber<Data> {
+    /// Create a new UdpTimestampedSubscriber bound to a specific bind address
+    pub fn new(bind_address: SocketAddr) -> Result<Self, Error> {
+        let rx = UdpSocket::bind(bind_address)?;
+        rx.set_nonblocking(true)?;
+        Ok(Self {
+            rx,
+            // The buffer has room for one extra byte to detect oversized datagrams
+            recv_buffer: vec![0u8; Data::len() + 1],
+            data: None,
+            received_at: None,
+        })
+    }
+
+    /// Get the one-way latency of the most recent data (the time between the
+    /// data being sent and it being received).
+    ///
+    /// Note: The latency is only meaningful when the clocks of the publisher
+    /// and subscriber are synchronized.  If the sender's clock is ahead of the
+    /// subscriber's clock (or no data has been received), None is returned.
+    pub fn one_way_latency(&self) -> Option<Duration>
+    where
+        Data: SenderTimestamped,
+    {
+        let (data, _) = self.data.as_ref()?;
+        self.received_at?.duration_since(data.sent_at()).ok()
+    }
+}
+
+impl<Data: Packable> Subscriber for UdpTimestampedSubscriber<Data> {
+    type Target = Option<(Data, Instant)>;
+
+    fn get(&mut self) -> &Self::Target {
+        loop {
+            match self.rx.recv_from(&mut self.recv_buffer) {
+                Ok((received, _)) if received == Data::len() => {
+                    let received_instant = Instant::now();
+                    if let Ok(found_data) = Data::unpack(&self.recv_buffer[..received]) {
+                        self.data = Some((found_data, received_instant));
+                        self.received_at = Some(SystemTime::now());
+                    }
+                }
+                // Datagrams of the wrong size can't be valid data
+                Ok(_) => continue,
+                Err(_) => break,
+            }
+        }
+
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
 }
 
 /// A UDP Subscriber that updates its internal data representation with the
@@ -167,7 +650,9 @@ impl<Data: Packable> Subscriber for UdpBufferedSubscriber<Data> {
 pub struct UdpTTLSubscriber<Data: Packable> {
     /// The UdpSocket to receive data through
     rx: UdpSocket,
-    /// The most recent data contained by the subscriber
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
+    /// The most recent data contained by the subscriber (kept after it expires)
     data: Option<(Data, Instant)>,
     /// The total time that data is alive for
     ttl: Duration,
@@ -180,10 +665,27 @@ impl<Data: Packable> UdpTTLSubscriber<Data> {
         rx.set_nonblocking(true)?;
         Ok(Self {
             rx,
+            // The buffer has room for one extra byte to detect oversized datagrams
+            recv_buffer: vec![0u8; Data::len() + 1],
             data: None,
             ttl,
         })
     }
+
+    /// Get the instant the most recent piece of data was received (as of the
+    /// most recent call to `get`), even if that data has since expired.
+    pub fn last_seen(&self) -> Option<Instant> {
+        self.data.as_ref().map(|(_, received)| *received)
+    }
+
+    /// Check whether the publisher is alive (i.e. whether data was received
+    /// within the time-to-live).
+    ///
+    /// Note: Only data received as of the most recent call to `get` is considered.
+    pub fn is_alive(&self) -> bool {
+        self.last_seen()
+            .is_some_and(|last_seen| last_seen.elapsed() <= self.ttl)
+    }
 }
 
 impl<Data: Packable> Subscriber for UdpTTLSubscriber<Data> {
@@ -192,13 +694,16 @@ impl<Data: Packable> Subscriber for UdpTTLSubscriber<Data> {
     fn get(&mut self) -> &Self::Target {
         let mut data = None;
 
-        let mut buffer = vec![0u8; Data::len()];
         loop {
-            let temp = match self.rx.recv_from(&mut buffer) {
-                Ok(_received) => Data::unpack(&buffer[..]),
+            let temp = match self.rx.recv_from(&mut self.recv_buffer) {
+                Ok((received, _)) if received == Data::len() => {
+                    Data::unpack(&self.recv_buffer[..received])
+                }
+                // Datagrams of the wrong size can't be valid data
+                Ok(_) => continue,
                 Err(_) => break,
             };
-            buffer.iter_mut().for_each(|v| *v = 0);
+            self.recv_buffer.iter_mut().for_each(|v| *v = 0);
 
             if let Ok(found_data) = temp {
                 data = Some(found_data);
@@ -209,14 +714,22 @@ impl<Data: Packable> Subscriber for UdpTTLSubscriber<Data> {
             self.data = Some((data, Instant::now()));
         }
 
-        if self.data.is_some()
-            && Instant::now().duration_since(self.data.as_ref().unwrap().1) > self.ttl
+        // Expired data is kept (but not returned) so the instant it was received
+        // is still known
+        if self
+            .data
+            .as_ref()
+            .is_some_and(|(_, received)| received.elapsed() > self.ttl)
         {
-            self.data = None;
+            return &None;
         }
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
 }
 
 /// A UDP Subscriber that maps incoming data into slots in a HashMap by a given
@@ -224,6 +737,8 @@ impl<Data: Packable> Subscriber for UdpTTLSubscriber<Data> {
 pub struct UdpMappedSubscriber<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> {
     /// The UdpSocket to receive data through
     rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
     /// A hashmap containing the most recent data for a set of keys
     data: HashMap<K, Data>,
     /// A hash method used to create keys for data obtained via the UdpSocket
@@ -237,6 +752,8 @@ impl<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> UdpMappedSubscriber<Data,
         rx.set_nonblocking(true)?;
         Ok(Self {
             rx,
+            // The buffer has room for one extra byte to detect oversized datagrams
+            recv_buffer: vec![0u8; Data::len() + 1],
             data: HashMap::new(),
             hash: map,
         })
@@ -249,13 +766,16 @@ impl<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> Subscriber
     type Target = HashMap<K, Data>;
 
     fn get(&mut self) -> &Self::Target {
-        let mut buffer = vec![0u8; Data::len()];
         loop {
-            let temp = match self.rx.recv_from(&mut buffer) {
-                Ok(_received) => Data::unpack(&buffer[..]),
+            let temp = match self.rx.recv_from(&mut self.recv_buffer) {
+                Ok((received, _)) if received == Data::len() => {
+                    Data::unpack(&self.recv_buffer[..received])
+                }
+                // Datagrams of the wrong size can't be valid data
+                Ok(_) => continue,
                 Err(_) => break,
             };
-            buffer.iter_mut().for_each(|v| *v = 0);
+            self.recv_buffer.iter_mut().for_each(|v| *v = 0);
             if let Ok(found_data) = temp {
                 let label = (self.hash)(&found_data);
                 self.data.insert(label, found_data);
@@ -264,6 +784,10 @@ impl<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> Subscriber
 
         &self.data
     }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
 }
 
 /// A UDP Subscriber that maps incoming data into slots in a HashMap by a given
@@ -271,6 +795,8 @@ impl<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> Subscriber
 pub struct UdpMappedTTLSubscriber<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> {
     /// The UdpSocket to receive data through
     rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
     /// A hashmap containing the most recent valid data for a set of keys
     data: HashMap<K, (Data, Instant)>,
     /// A hash method used to create keys for data obtained vai the UdpSocket
@@ -286,6 +812,8 @@ impl<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> UdpMappedTTLSubscriber<Dat
         rx.set_nonblocking(true)?;
         Ok(Self {
             rx,
+            // The buffer has room for one extra byte to detect oversized datagrams
+            recv_buffer: vec![0u8; Data::len() + 1],
             data: HashMap::new(),
             hash: map,
             ttl,
@@ -299,13 +827,16 @@ impl<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> Subscriber
     type Target = HashMap<K, (Data, Instant)>;
 
     fn get(&mut self) -> &Self::Target {
-        let mut buffer = vec![0u8; Data::len()];
         loop {
-            let temp = match self.rx.recv_from(&mut buffer) {
-                Ok(_received) => Data::unpack(&buffer[..]),
+            let temp = match self.rx.recv_from(&mut self.recv_buffer) {
+                Ok((received, _)) if received == Data::len() => {
+                    Data::unpack(&self.recv_buffer[..received])
+                }
+                // Datagrams of the wrong size can't be valid data
+                Ok(_) => continue,
                 Err(_) => break,
             };
-            buffer.iter_mut().for_each(|v| *v = 0);
+            self.recv_buffer.iter_mut().for_each(|v| *v = 0);
             if let Ok(found_data) = temp {
                 let label = (self.hash)(&found_data);
                 self.data.insert(label, (found_data, Instant::now()));
@@ -317,76 +848,785 @@ impl<Data: Packable, K: Eq + Hash, F: Fn(&Data) -> K> Subscriber
 
         &self.data
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    use rand::random;
-    use std::{
-        net::{Ipv4Addr, SocketAddrV4},
-        thread::sleep,
-        time::Duration,
-    };
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    struct Data {
-        num: u64,
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
     }
+}
 
-    impl Data {
-        pub fn new() -> Self {
-            Self { num: random() }
-        }
-    }
+/// The length of the sequence number prepended to data sent by a reliable publisher
+const SEQUENCE_LEN: usize = 8;
+
+/// A UDP Publisher that prepends each piece of data with a sequence number and
+/// waits for each address to acknowledge the data, retransmitting the data to
+/// any address whose acknowledgement is not received before the timeout.
+///
+/// Note: Publishing blocks until every address has acknowledged the data (or
+/// every retransmission has timed out) so the subscribers must be polled from
+/// another thread or process.
+///
+/// Addendum: The data is sent to every address before waiting on the
+/// acknowledgements, so a single publish blocks for at most `(retries + 1) * timeout`
+/// regardless of the number of addresses.
+pub struct ReliableUdpPublisher<Data: Packable> {
+    // the UdpSocket bound for transmission
+    tx: UdpSocket,
+    /// The addresses to send data along.
+    pub addresses: Vec<SocketAddr>,
+    /// The sequence number of the next piece of data to publish
+    next_sequence: u64,
+    /// The time to wait for an acknowledgement before retransmitting
+    timeout: Duration,
+    /// The number of times to retransmit data before giving up
+    retries: u32,
+    // A PhantomAddress to bind the specific type of data to send to the
+    // publisher
+    phantom: PhantomData<Data>,
+}
 
-    impl Packable for Data {
-        fn len() -> usize {
-            8
-        }
+impl<Data: Packable> ReliableUdpPublisher<Data> {
+    /// Create a new ReliableUdpPublisher that retransmits data up to `retries` times,
+    /// waiting `timeout` for an acknowledgement after each transmission
+    pub fn new(
+        bind_address: SocketAddr,
+        send_addresses: Vec<SocketAddr>,
+        timeout: Duration,
+        retries: u32,
+    ) -> Result<Self, Error> {
+        let tx = UdpSocket::bind(bind_address)?;
+        Ok(Self {
+            tx,
+            addresses: send_addresses,
+            next_sequence: 0,
+            timeout,
+            retries,
+            phantom: PhantomData,
+        })
+    }
 
-        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
-            if buffer.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
-            } else {
-                Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
+    /// Wait until the given deadline for acknowledgements of the sequence number,
+    /// removing each address that acknowledges the data from `pending`.
+    fn wait_for_acks(
+        &self,
+        sequence: u64,
+        pending: &mut Vec<SocketAddr>,
+        deadline: Instant,
+    ) -> Result<(), Error> {
+        let mut buffer = [0u8; SEQUENCE_LEN];
+        while !pending.is_empty() {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(());
             }
-        }
 
-        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
-            if data.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
-            } else {
-                Ok(Self {
-                    num: u64::from_le_bytes(data[..8].try_into().unwrap()),
-                })
+            self.tx.set_read_timeout(Some(deadline - now))?;
+            match self.tx.recv_from(&mut buffer) {
+                Ok((SEQUENCE_LEN, source)) if u64::from_le_bytes(buffer) == sequence => {
+                    pending.retain(|address| *address != source)
+                }
+                Ok(_) => (),
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(())
+                }
+                // Some platforms report an unreachable address when receiving
+                Err(err) if err.kind() == ErrorKind::ConnectionReset => (),
+                Err(err) => return Err(err),
             }
         }
+
+        Ok(())
     }
+}
 
-    #[test]
-    fn test_publish_udp_subscriber() {
-        let mut publisher = UdpPublisher::new(
-            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8000)),
-            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8001))],
-        )
-        .unwrap();
+impl<Data: Packable> Publisher for ReliableUdpPublisher<Data> {
+    type Data = Data;
+    type Error = UdpPublishError;
 
-        let mut subscriber: UdpSubscriber<Data> =
-            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8001)))
-                .unwrap();
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        let sequence = self.next_sequence;
+        self.next_sequence = self.next_sequence.wrapping_add(1);
 
-        let data = Data::new();
-        publisher.publish(data.clone()).unwrap();
+        let mut packed_data = vec![0u8; SEQUENCE_LEN + Data::len()];
+        packed_data[..SEQUENCE_LEN].copy_from_slice(&sequence.to_le_bytes());
+        data.pack(&mut packed_data[SEQUENCE_LEN..])
+            .map_err(UdpPublishError::PackingError)?;
 
-        sleep(Duration::from_millis(50));
-        assert_eq!(subscriber.get().unwrap(), data);
+        let mut pending = self.addresses.clone();
+        for _ in 0..=self.retries {
+            if pending.is_empty() {
+                break;
+            }
+
+            for address in pending.iter() {
+                self.tx
+                    .send_to(&packed_data, address)
+                    .map_err(UdpPublishError::IOError)?;
+            }
+            self.wait_for_acks(sequence, &mut pending, Instant::now() + self.timeout)
+                .map_err(UdpPublishError::IOError)?;
+        }
+
+        match pending.first() {
+            Some(address) => Err(UdpPublishError::Unacknowledged(*address)),
+            None => Ok(()),
+        }
     }
+}
 
-    #[test]
-    fn test_publish_buffered_subscriber() {
-        let mut publisher = UdpPublisher::new(
+/// A UDP Subscriber that acknowledges data sent by a ReliableUdpPublisher and
+/// ignores duplicated (i.e. retransmitted) data.
+///
+/// Note: Data is only acknowledged when the subscriber is dereferenced so the
+/// subscriber should be polled frequently.
+pub struct ReliableUdpSubscriber<Data: Packable> {
+    /// The receiving UdpSocket
+    rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
+    /// The current data stored in the subscriber
+    data: Option<Data>,
+    /// The most recent sequence number received from each publisher
+    sequences: HashMap<SocketAddr, u64>,
+}
+
+impl<Data: Packable> ReliableUdpSubscriber<Data> {
+    /// Create a new ReliableUdpSubscriber bound to a specific bind address
+    pub fn new(bind_address: SocketAddr) -> Result<Self, Error> {
+        let rx = UdpSocket::bind(bind_address)?;
+        rx.set_nonblocking(true)?;
+        Ok(Self {
+            rx,
+            recv_buffer: vec![0u8; SEQUENCE_LEN + Data::len()],
+            data: None,
+            sequences: HashMap::new(),
+        })
+    }
+}
+
+impl<Data: Packable> Subscriber for ReliableUdpSubscriber<Data> {
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        while let Ok((received, source)) = self.rx.recv_from(&mut self.recv_buffer) {
+            if received != self.recv_buffer.len() {
+                continue;
+            }
+
+            // Acknowledge every piece of data (even duplicates) because the
+            // previous acknowledgement may have been lost
+            let _ = self.rx.send_to(&self.recv_buffer[..SEQUENCE_LEN], source);
+
+            let sequence = u64::from_le_bytes(self.recv_buffer[..SEQUENCE_LEN].try_into().unwrap());
+            if self.sequences.get(&source) == Some(&sequence) {
+                continue;
+            }
+
+            if let Ok(data) = Data::unpack(&self.recv_buffer[SEQUENCE_LEN..]) {
+                self.sequences.insert(source, sequence);
+                self.data = Some(data);
+            }
+        }
+
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
+}
+
+/// The largest payload that can be sent in a single UDP datagram
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// The length of the header prepended to each fragment sent by a fragmented
+/// publisher (message id, fragment index, fragment count)
+const FRAGMENT_HEADER_LEN: usize = 12;
+
+/// A UDP Publisher that splits data larger than a given chunk size into a
+/// number of fragments that are reassembled by a UdpFragmentedSubscriber.
+///
+/// Note: Each fragment is prepended with a header containing the message id,
+/// the index of the fragment, and the total number of fragments in the message.
+pub struct UdpFragmentedPublisher<Data: Packable> {
+    // the UdpSocket bound for transmission
+    tx: UdpSocket,
+    /// The addresses to send data along.
+    pub addresses: Vec<SocketAddr>,
+    /// The largest number of bytes of data sent in a single fragment
+    chunk_size: usize,
+    /// The id of the next message to publish
+    next_message_id: u32,
+    // A PhantomAddress to bind the specific type of data to send to the
+    // publisher
+    phantom: PhantomData<Data>,
+}
+
+impl<Data: Packable> UdpFragmentedPublisher<Data> {
+    /// Create a new UdpFragmentedPublisher that sends at most `chunk_size` bytes
+    /// of data per fragment.
+    ///
+    /// Note: The chunk size must be greater than 0 and small enough for a
+    /// fragment to fit in a single UDP datagram.
+    pub fn new(
+        bind_address: SocketAddr,
+        send_addresses: Vec<SocketAddr>,
+        chunk_size: usize,
+    ) -> Result<Self, Error> {
+        if chunk_size == 0 || chunk_size > MAX_DATAGRAM_SIZE - FRAGMENT_HEADER_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid fragment chunk size",
+            ));
+        }
+
+        let tx = UdpSocket::bind(bind_address)?;
+        tx.set_nonblocking(true)?;
+        Ok(Self {
+            tx,
+            addresses: send_addresses,
+            chunk_size,
+            next_message_id: 0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<Data: Packable> Publisher for UdpFragmentedPublisher<Data> {
+    type Data = Data;
+    type Error = UdpPublishError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        let mut packed_data = vec![0u8; Data::len()];
+        data.pack(&mut packed_data)
+            .map_err(UdpPublishError::PackingError)?;
+
+        let message_id = self.next_message_id;
+        self.next_message_id = self.next_message_id.wrapping_add(1);
+
+        let fragment_count = packed_data.len().div_ceil(self.chunk_size).max(1) as u32;
+        let mut fragment = Vec::with_capacity(FRAGMENT_HEADER_LEN + self.chunk_size);
+        for index in 0..fragment_count {
+            let start = index as usize * self.chunk_size;
+            let end = (start + self.chunk_size).min(packed_data.len());
+
+            fragment.clear();
+            fragment.extend_from_slice(&message_id.to_le_bytes());
+            fragment.extend_from_slice(&index.to_le_bytes());
+            fragment.extend_from_slice(&fragment_count.to_le_bytes());
+            fragment.extend_from_slice(&packed_data[start..end]);
+
+            for address in self.addresses.iter() {
+                self.tx
+                    .send_to(&fragment, address)
+                    .map_err(UdpPublishError::IOError)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A partially received message in a UdpFragmentedSubscriber
+struct PartialMessage {
+    /// The fragments of the message (None if the fragment has not been received)
+    fragments: Vec<Option<Vec<u8>>>,
+    /// The number of fragments received
+    received: usize,
+    /// The number of bytes of data received
+    received_len: usize,
+    /// The time the first fragment of the message was received
+    first_received: Instant,
+}
+
+/// A UDP Subscriber that reassembles fragments sent by a UdpFragmentedPublisher
+/// into the most recent piece of data.
+///
+/// Note: Messages that are not completely received within the timeout are discarded.
+pub struct UdpFragmentedSubscriber<Data: Packable> {
+    /// The receiving UdpSocket
+    rx: UdpSocket,
+    /// The buffer fragments are received into
+    recv_buffer: Vec<u8>,
+    /// The current data stored in the subscriber
+    data: Option<Data>,
+    /// The partially received messages keyed by the sender and message id
+    partial: HashMap<(SocketAddr, u32), PartialMessage>,
+    /// The time to wait for all of the fragments of a message
+    timeout: Duration,
+}
+
+impl<Data: Packable> UdpFragmentedSubscriber<Data> {
+    /// Create a new UdpFragmentedSubscriber bound to a specific bind address
+    pub fn new(bind_address: SocketAddr, timeout: Duration) -> Result<Self, Error> {
+        let rx = UdpSocket::bind(bind_address)?;
+        rx.set_nonblocking(true)?;
+        Ok(Self {
+            rx,
+            recv_buffer: vec![0u8; MAX_DATAGRAM_SIZE],
+            data: None,
+            partial: HashMap::new(),
+            timeout,
+        })
+    }
+
+    /// Set the size of the socket's receive buffer.
+    ///
+    /// Note: Every fragment of a message is sent at once so the receive buffer should
+    /// be large enough to hold an entire message (the default is often only a few
+    /// hundred kilobytes).
+    pub fn set_receive_buffer_size(&self, size: usize) -> Result<(), Error> {
+        SockRef::from(&self.rx).set_recv_buffer_size(size)
+    }
+
+    /// Store a received fragment, returning the reassembled message if the
+    /// fragment completed its message.
+    ///
+    /// Note: The header of a fragment is untrusted so fragments claiming more
+    /// fragments (or more bytes of data) than packed Data can span are ignored, as
+    /// are completed messages whose length doesn't match the packed length of Data.
+    fn receive_fragment(&mut self, source: SocketAddr, fragment: &[u8]) -> Option<Vec<u8>> {
+        if fragment.len() < FRAGMENT_HEADER_LEN {
+            return None;
+        }
+
+        let message_id = u32::from_le_bytes(fragment[0..4].try_into().unwrap());
+        let index = u32::from_le_bytes(fragment[4..8].try_into().unwrap()) as usize;
+        let count = u32::from_le_bytes(fragment[8..12].try_into().unwrap()) as usize;
+        let payload = &fragment[FRAGMENT_HEADER_LEN..];
+        // Every fragment but the last carries at least one byte of data
+        if index >= count || count > Data::len().max(1) || payload.len() > Data::len() {
+            return None;
+        }
+
+        let message = self
+            .partial
+            .entry((source, message_id))
+            .or_insert_with(|| PartialMessage {
+                fragments: vec![None; count],
+                received: 0,
+                received_len: 0,
+                first_received: Instant::now(),
+            });
+        if message.fragments.len() != count || message.fragments[index].is_some() {
+            return None;
+        }
+        if message.received_len + payload.len() > Data::len() {
+            self.partial.remove(&(source, message_id));
+            return None;
+        }
+        message.fragments[index] = Some(payload.to_vec());
+        message.received += 1;
+        message.received_len += payload.len();
+
+        if message.received == count {
+            let message = self.partial.remove(&(source, message_id)).unwrap();
+            if message.received_len != Data::len() {
+                return None;
+            }
+            Some(message.fragments.into_iter().flatten().flatten().collect())
+        } else {
+            None
+        }
+    }
+}
+
+impl<Data: Packable> Subscriber for UdpFragmentedSubscriber<Data> {
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        // The buffer is moved out of the subscriber while fragments are stored
+        let mut buffer = std::mem::take(&mut self.recv_buffer);
+        while let Ok((received, source)) = self.rx.recv_from(&mut buffer) {
+            if let Some(message) = self.receive_fragment(source, &buffer[..received]) {
+                if let Ok(data) = Data::unpack(&message) {
+                    self.data = Some(data);
+                }
+            }
+        }
+        self.recv_buffer = buffer;
+
+        let now = Instant::now();
+        self.partial
+            .retain(|_, message| now.duration_since(message.first_received) <= self.timeout);
+
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
+}
+
+/// The length of the length prefix of serde-encoded data
+#[cfg(feature = "serde")]
+const LENGTH_PREFIX_LEN: usize = 4;
+
+/// An Error with publishing serde-encoded udp packets
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum UdpSerdePublishError {
+    /// std::io::Error occurred
+    IOError(Error),
+    /// An error occurred with serializing the data
+    SerializationError(bincode::Error),
+}
+
+#[cfg(feature = "serde")]
+impl std::fmt::Display for UdpSerdePublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IOError(err) => write!(f, "io error: {}", err),
+            Self::SerializationError(err) => write!(f, "serialization error: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for UdpSerdePublishError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(err) => Some(err),
+            Self::SerializationError(err) => Some(err),
+        }
+    }
+}
+
+/// A UDP Publisher that publishes any Serialize data by bincode-encoding
+/// the data with a length prefix to a group of addresses.
+///
+/// Note: Unlike the UdpPublisher, the data published does not need to have a
+/// fixed length so the data does not need to implement Packable.
+#[cfg(feature = "serde")]
+pub struct UdpSerdePublisher<Data: Serialize> {
+    // the UdpSocket bound for transmission
+    tx: UdpSocket,
+    /// The addresses to send data along.
+    pub addresses: Vec<SocketAddr>,
+    // A PhantomAddress to bind the specific type of data to send to the
+    // publisher
+    phantom: PhantomData<Data>,
+}
+
+#[cfg(feature = "serde")]
+impl<Data: Serialize> UdpSerdePublisher<Data> {
+    /// Create a new UdpSerdePublisher
+    pub fn new(bind_address: SocketAddr, send_addresses: Vec<SocketAddr>) -> Result<Self, Error> {
+        let tx = UdpSocket::bind(bind_address)?;
+        tx.set_nonblocking(true)?;
+        Ok(Self {
+            tx,
+            addresses: send_addresses,
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Data: Serialize> Publisher for UdpSerdePublisher<Data> {
+    type Data = Data;
+    type Error = UdpSerdePublishError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        let encoded =
+            bincode::serialize(&data).map_err(UdpSerdePublishError::SerializationError)?;
+        let mut packed_data = Vec::with_capacity(LENGTH_PREFIX_LEN + encoded.len());
+        packed_data.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+        packed_data.extend_from_slice(&encoded);
+
+        for address in self.addresses.iter() {
+            self.tx
+                .send_to(&packed_data, address)
+                .map_err(UdpSerdePublishError::IOError)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A UDP Subscriber that receives bincode-encoded length-prefixed data from
+/// a UdpSerdePublisher and updates its internal data whenever it is dereferenced
+#[cfg(feature = "serde")]
+pub struct UdpSerdeSubscriber<Data: DeserializeOwned> {
+    /// The receiving UdpSocket
+    rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
+    /// The current data stored in the subscriber
+    data: Option<Data>,
+}
+
+#[cfg(feature = "serde")]
+impl<Data: DeserializeOwned> UdpSerdeSubscriber<Data> {
+    /// Create a new UdpSerdeSubscriber bound to a specific bind address
+    pub fn new(bind_address: SocketAddr) -> Result<Self, Error> {
+        let rx = UdpSocket::bind(bind_address)?;
+        rx.set_nonblocking(true)?;
+        Ok(Self {
+            rx,
+            recv_buffer: vec![0u8; MAX_DATAGRAM_SIZE],
+            data: None,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Data: DeserializeOwned> Subscriber for UdpSerdeSubscriber<Data> {
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        while let Ok((received, _)) = self.rx.recv_from(&mut self.recv_buffer) {
+            if received < LENGTH_PREFIX_LEN {
+                continue;
+            }
+
+            let length =
+                u32::from_le_bytes(self.recv_buffer[..LENGTH_PREFIX_LEN].try_into().unwrap())
+                    as usize;
+            if LENGTH_PREFIX_LEN + length > received {
+                continue;
+            }
+
+            if let Ok(data) = bincode::deserialize(
+                &self.recv_buffer[LENGTH_PREFIX_LEN..LENGTH_PREFIX_LEN + length],
+            ) {
+                self.data = Some(data);
+            }
+        }
+
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
+}
+
+/// The length of the HMAC-SHA256 tag appended to data sent by an
+/// AuthenticatedUdpPublisher
+#[cfg(feature = "hmac")]
+pub const HMAC_TAG_LEN: usize = 32;
+
+/// A UDP Publisher that appends an HMAC-SHA256 tag (keyed by a secret shared with
+/// the subscribers) to the packed data so AuthenticatedUdpSubscribers can reject
+/// spoofed data.
+///
+/// Note: The tag authenticates the data but does not encrypt it.  Additionally, a
+/// correctly tagged datagram that is captured and resent is still accepted.
+#[cfg(feature = "hmac")]
+pub struct AuthenticatedUdpPublisher<Data: Packable> {
+    // the UdpSocket bound for transmission
+    tx: UdpSocket,
+    /// The addresses to send data along.
+    pub addresses: Vec<SocketAddr>,
+    // The key the data is tagged with
+    key: hmac::Key,
+    // The buffer data is packed into before being sent (reused across publishes)
+    buffer: Vec<u8>,
+    // A PhantomAddress to bind the specific type of data to send to the
+    // publisher
+    phantom: PhantomData<Data>,
+}
+
+#[cfg(feature = "hmac")]
+impl<Data: Packable> AuthenticatedUdpPublisher<Data> {
+    /// Create a new AuthenticatedUdpPublisher that tags data with a shared secret
+    pub fn new(
+        bind_address: SocketAddr,
+        send_addresses: Vec<SocketAddr>,
+        secret: &[u8],
+    ) -> Result<Self, Error> {
+        let tx = UdpSocket::bind(bind_address)?;
+        tx.set_nonblocking(true)?;
+        Ok(Self {
+            tx,
+            addresses: send_addresses,
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+            buffer: Vec::with_capacity(Data::len() + HMAC_TAG_LEN),
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "hmac")]
+impl<Data: Packable> Publisher for AuthenticatedUdpPublisher<Data> {
+    type Data = Data;
+    type Error = UdpPublishError;
+
+    fn publish(&mut self, data: Self::Data) -> Result<(), Self::Error> {
+        self.buffer.clear();
+        self.buffer.resize(Data::len(), 0);
+        data.pack(&mut self.buffer)
+            .map_err(UdpPublishError::PackingError)?;
+        let tag = hmac::sign(&self.key, &self.buffer);
+        self.buffer.extend_from_slice(tag.as_ref());
+
+        for address in self.addresses.iter() {
+            self.tx
+                .send_to(&self.buffer, address)
+                .map_err(UdpPublishError::IOError)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A UDP Subscriber that only accepts data whose HMAC-SHA256 tag verifies with
+/// the secret shared with the AuthenticatedUdpPublisher, dropping any other data.
+#[cfg(feature = "hmac")]
+pub struct AuthenticatedUdpSubscriber<Data: Packable> {
+    /// The receiving UdpSocket
+    rx: UdpSocket,
+    /// The buffer datagrams are received into
+    recv_buffer: Vec<u8>,
+    /// The key the data's tag is verified with
+    key: hmac::Key,
+    /// The current data stored in the subscriber
+    data: Option<Data>,
+}
+
+#[cfg(feature = "hmac")]
+impl<Data: Packable> AuthenticatedUdpSubscriber<Data> {
+    /// Create a new AuthenticatedUdpSubscriber that verifies data with a shared secret
+    pub fn new(bind_address: SocketAddr, secret: &[u8]) -> Result<Self, Error> {
+        let rx = UdpSocket::bind(bind_address)?;
+        rx.set_nonblocking(true)?;
+        Ok(Self {
+            rx,
+            // The buffer has room for one extra byte to detect oversized datagrams
+            recv_buffer: vec![0u8; Data::len() + HMAC_TAG_LEN + 1],
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+            data: None,
+        })
+    }
+}
+
+#[cfg(feature = "hmac")]
+impl<Data: Packable> Subscriber for AuthenticatedUdpSubscriber<Data> {
+    type Target = Option<Data>;
+
+    fn get(&mut self) -> &Self::Target {
+        while let Ok((received, _)) = self.rx.recv_from(&mut self.recv_buffer) {
+            // Datagrams of the wrong size can't be valid data
+            if received != Data::len() + HMAC_TAG_LEN {
+                continue;
+            }
+
+            let (packed_data, tag) = self.recv_buffer[..received].split_at(Data::len());
+            if hmac::verify(&self.key, packed_data, tag).is_err() {
+                continue;
+            }
+
+            if let Ok(data) = Data::unpack(packed_data) {
+                self.data = Some(data);
+            }
+        }
+
+        &self.data
+    }
+
+    fn has_update(&mut self) -> bool {
+        self.rx.peek_from(&mut self.recv_buffer).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::random;
+    use std::{
+        net::{Ipv4Addr, SocketAddrV4},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, sleep},
+        time::Duration,
+    };
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Data {
+        num: u64,
+    }
+
+    impl Data {
+        pub fn new() -> Self {
+            Self { num: random() }
+        }
+    }
+
+    impl Packable for Data {
+        fn len() -> usize {
+            8
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            if buffer.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
+            } else {
+                Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
+            }
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            if data.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
+            } else {
+                Ok(Self {
+                    num: u64::from_le_bytes(data[..8].try_into().unwrap()),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn test_publish_udp_subscriber() {
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8000)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8001))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpSubscriber<Data> =
+            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8001)))
+                .unwrap();
+
+        let data = Data::new();
+        publisher.publish(data.clone()).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().unwrap(), data);
+    }
+
+    #[test]
+    fn test_take_udp_subscriber() {
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8050)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8051))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpSubscriber<Data> =
+            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8051)))
+                .unwrap();
+
+        let data = Data::new();
+        publisher.publish(data.clone()).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.take(), Some(data));
+        assert_eq!(subscriber.take(), None);
+    }
+
+    #[test]
+    fn test_publish_buffered_subscriber() {
+        let mut publisher = UdpPublisher::new(
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8002)),
             vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8003))],
         )
@@ -437,6 +1677,35 @@ mod tests {
         assert_eq!(long_subscriber.get().unwrap().0, data);
     }
 
+    #[test]
+    fn test_ttl_subscriber_liveness() {
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8031)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8032))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpTTLSubscriber<Data> = UdpTTLSubscriber::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8032)),
+            Duration::from_millis(100),
+        )
+        .unwrap();
+        assert!(!subscriber.is_alive());
+        assert!(subscriber.last_seen().is_none());
+
+        publisher.publish(Data::new()).unwrap();
+        sleep(Duration::from_millis(20));
+        subscriber.get();
+        assert!(subscriber.is_alive());
+        let last_seen = subscriber.last_seen().unwrap();
+
+        // The publisher goes silent
+        sleep(Duration::from_millis(150));
+        assert!(subscriber.get().is_none());
+        assert!(!subscriber.is_alive());
+        assert_eq!(subscriber.last_seen(), Some(last_seen));
+    }
+
     #[test]
     fn tests_publish_mapped_subscriber() {
         let mut publisher = UdpPublisher::new(
@@ -492,4 +1761,684 @@ mod tests {
         assert_eq!(short_subscriber.get().get(&data.num), None);
         assert_eq!(long_subscriber.get().get(&data.num).unwrap().0, data);
     }
+
+    #[test]
+    fn test_udp_subscriber_has_update() {
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8012)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8013))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpSubscriber<Data> =
+            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8013)))
+                .unwrap();
+        assert!(!subscriber.has_update());
+
+        let data = Data::new();
+        publisher.publish(data).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert!(subscriber.has_update());
+        // Checking for an update does not consume the data
+        assert!(subscriber.has_update());
+        assert_eq!(subscriber.get().unwrap(), data);
+        assert!(!subscriber.has_update());
+    }
+
+    #[test]
+    fn test_reliable_publisher_redelivers_dropped_data() {
+        let publisher_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8016));
+        let subscriber_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8017));
+        let proxy_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8018));
+
+        let mut publisher = ReliableUdpPublisher::new(
+            publisher_address,
+            vec![proxy_address],
+            Duration::from_millis(50),
+            3,
+        )
+        .unwrap();
+        let mut subscriber: ReliableUdpSubscriber<Data> =
+            ReliableUdpSubscriber::new(subscriber_address).unwrap();
+
+        // The proxy drops the first datagram from the publisher and forwards
+        // everything else between the publisher and subscriber
+        let stop = Arc::new(AtomicBool::new(false));
+        let proxy = UdpSocket::bind(proxy_address).unwrap();
+        proxy
+            .set_read_timeout(Some(Duration::from_millis(5)))
+            .unwrap();
+        let proxy_stop = stop.clone();
+        let proxy_handle = thread::spawn(move || {
+            let mut published = 0;
+            let mut buffer = [0u8; 64];
+            while !proxy_stop.load(Ordering::Relaxed) {
+                if let Ok((received, source)) = proxy.recv_from(&mut buffer) {
+                    if source == publisher_address {
+                        published += 1;
+                        if published > 1 {
+                            proxy
+                                .send_to(&buffer[..received], subscriber_address)
+                                .unwrap();
+                        }
+                    } else {
+                        proxy
+                            .send_to(&buffer[..received], publisher_address)
+                            .unwrap();
+                    }
+                }
+            }
+            published
+        });
+
+        let subscriber_stop = stop.clone();
+        let subscriber_handle = thread::spawn(move || {
+            while !subscriber_stop.load(Ordering::Relaxed) {
+                subscriber.get();
+                sleep(Duration::from_millis(1));
+            }
+            subscriber
+        });
+
+        let data = Data::new();
+        publisher.publish(data).unwrap();
+
+        stop.store(true, Ordering::Relaxed);
+        let published = proxy_handle.join().unwrap();
+        let mut subscriber = subscriber_handle.join().unwrap();
+        assert_eq!(published, 2);
+        assert_eq!(subscriber.get().unwrap(), data);
+    }
+
+    #[test]
+    fn test_reliable_publisher_unacknowledged() {
+        let mut publisher = ReliableUdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8019)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8020))],
+            Duration::from_millis(10),
+            2,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            publisher.publish(Data::new()),
+            Err(UdpPublishError::Unacknowledged(_))
+        ));
+    }
+
+    #[test]
+    fn test_reliable_publisher_unacknowledged_addresses_share_timeout() {
+        let mut publisher = ReliableUdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8062)),
+            (8063..8067)
+                .map(|port| SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+                .collect(),
+            Duration::from_millis(50),
+            1,
+        )
+        .unwrap();
+
+        // Waiting on each address in turn would take at least 400ms
+        let start = Instant::now();
+        assert!(matches!(
+            publisher.publish(Data::new()),
+            Err(UdpPublishError::Unacknowledged(_))
+        ));
+        assert!(start.elapsed() < Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_reliable_subscriber_deduplicates() {
+        let sender =
+            UdpSocket::bind(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8021))).unwrap();
+        sender
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        let subscriber_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8022));
+        let mut subscriber: ReliableUdpSubscriber<Data> =
+            ReliableUdpSubscriber::new(subscriber_address).unwrap();
+
+        let first = Data::new();
+        let second = Data::new();
+        for data in [first, second] {
+            let mut buffer = vec![0u8; SEQUENCE_LEN + Data::len()];
+            buffer[..SEQUENCE_LEN].copy_from_slice(&7u64.to_le_bytes());
+            data.pack(&mut buffer[SEQUENCE_LEN..]).unwrap();
+            sender.send_to(&buffer, subscriber_address).unwrap();
+        }
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().unwrap(), first);
+
+        // Both copies of the data are acknowledged
+        let mut ack = [0u8; SEQUENCE_LEN];
+        for _ in 0..2 {
+            sender.recv_from(&mut ack).unwrap();
+            assert_eq!(u64::from_le_bytes(ack), 7);
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct LargeData {
+        bytes: Vec<u8>,
+    }
+
+    impl Packable for LargeData {
+        fn len() -> usize {
+            200_000
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            if buffer.len() < Self::len() {
+                Err(PackingError::InvalidBufferSize {
+                    expected: Self::len(),
+                    got: buffer.len(),
+                })
+            } else {
+                buffer[..Self::len()].copy_from_slice(&self.bytes);
+                Ok(())
+            }
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            if data.len() < Self::len() {
+                Err(PackingError::InvalidBufferSize {
+                    expected: Self::len(),
+                    got: data.len(),
+                })
+            } else {
+                Ok(Self {
+                    bytes: data[..Self::len()].to_vec(),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn test_publish_fragmented_subscriber() {
+        let mut publisher = UdpFragmentedPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8023)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8024))],
+            8_192,
+        )
+        .unwrap();
+
+        let mut subscriber: UdpFragmentedSubscriber<LargeData> = UdpFragmentedSubscriber::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8024)),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        subscriber.set_receive_buffer_size(1_000_000).unwrap();
+
+        let data = LargeData {
+            bytes: (0..LargeData::len()).map(|_| random()).collect(),
+        };
+        publisher.publish(data.clone()).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().as_ref().unwrap(), &data);
+        assert!(subscriber.partial.is_empty());
+    }
+
+    #[test]
+    fn test_fragmented_subscriber_discards_incomplete_messages() {
+        let sender =
+            UdpSocket::bind(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8025))).unwrap();
+        let subscriber_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8026));
+        let mut subscriber: UdpFragmentedSubscriber<Data> =
+            UdpFragmentedSubscriber::new(subscriber_address, Duration::from_millis(20)).unwrap();
+
+        // Send the first of two fragments
+        let mut fragment = Vec::new();
+        fragment.extend_from_slice(&0u32.to_le_bytes());
+        fragment.extend_from_slice(&0u32.to_le_bytes());
+        fragment.extend_from_slice(&2u32.to_le_bytes());
+        fragment.extend_from_slice(&[0u8; 4]);
+        sender.send_to(&fragment, subscriber_address).unwrap();
+
+        sleep(Duration::from_millis(10));
+        assert!(subscriber.get().is_none());
+        assert_eq!(subscriber.partial.len(), 1);
+
+        sleep(Duration::from_millis(30));
+        assert!(subscriber.get().is_none());
+        assert!(subscriber.partial.is_empty());
+    }
+
+    #[test]
+    fn test_fragmented_subscriber_rejects_invalid_fragments() {
+        let mut subscriber: UdpFragmentedSubscriber<Data> = UdpFragmentedSubscriber::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        let source = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1));
+        let fragment = |message_id: u32, index: u32, count: u32, payload: &[u8]| {
+            let mut fragment = Vec::new();
+            fragment.extend_from_slice(&message_id.to_le_bytes());
+            fragment.extend_from_slice(&index.to_le_bytes());
+            fragment.extend_from_slice(&count.to_le_bytes());
+            fragment.extend_from_slice(payload);
+            fragment
+        };
+
+        // More fragments than 8 bytes of data could ever be split into
+        assert!(subscriber
+            .receive_fragment(source, &fragment(0, 0, u32::MAX, &[0u8; 1]))
+            .is_none());
+        assert!(subscriber.partial.is_empty());
+
+        // A fragment count that doesn't match the message's existing fragments
+        assert!(subscriber
+            .receive_fragment(source, &fragment(1, 0, 2, &[0u8; 4]))
+            .is_none());
+        assert!(subscriber
+            .receive_fragment(source, &fragment(1, 1, 3, &[0u8; 4]))
+            .is_none());
+        assert_eq!(subscriber.partial[&(source, 1)].received, 1);
+
+        // A completed message shorter than the packed data
+        assert!(subscriber
+            .receive_fragment(source, &fragment(2, 0, 1, &[0u8; 4]))
+            .is_none());
+
+        // A message longer than the packed data
+        assert!(subscriber
+            .receive_fragment(source, &fragment(3, 0, 2, &[0u8; 6]))
+            .is_none());
+        assert!(subscriber
+            .receive_fragment(source, &fragment(3, 1, 2, &[0u8; 6]))
+            .is_none());
+        assert!(!subscriber.partial.contains_key(&(source, 3)));
+
+        assert_eq!(
+            subscriber.receive_fragment(source, &fragment(4, 0, 1, &[1u8; 8])),
+            Some(vec![1u8; 8])
+        );
+    }
+
+    #[test]
+    fn test_batched_publisher_flush_on_drop() {
+        let subscriber_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8061));
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8060)),
+            vec![subscriber_address],
+        )
+        .unwrap();
+        publisher.set_batch_size(Some(4)).unwrap();
+
+        let mut subscriber: UdpBufferedSubscriber<Data> =
+            UdpBufferedSubscriber::new(subscriber_address).unwrap();
+
+        // A full batch is sent as soon as it is full
+        let data: Vec<Data> = (0..6).map(|_| Data::new()).collect();
+        for data in data.iter() {
+            publisher.publish(*data).unwrap();
+        }
+        sleep(Duration::from_millis(50));
+        assert_eq!(*subscriber.get(), data[..4]);
+
+        // The rest of the data is sent when the publisher is dropped
+        drop(publisher);
+        sleep(Duration::from_millis(50));
+        assert_eq!(*subscriber.get(), data);
+    }
+
+    #[test]
+    fn test_publish_batch_buffered_subscriber() {
+        let subscriber_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8040));
+        let raw_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8041));
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8039)),
+            vec![subscriber_address, raw_address],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpBufferedSubscriber<Data> =
+            UdpBufferedSubscriber::new(subscriber_address).unwrap();
+        let raw_socket = UdpSocket::bind(raw_address).unwrap();
+        raw_socket
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+
+        let data: Vec<Data> = (0..10).map(|_| Data::new()).collect();
+        publisher.publish_batch(&data).unwrap();
+
+        // The whole batch is sent as a single datagram
+        let mut buffer = [0u8; 256];
+        let (received, _) = raw_socket.recv_from(&mut buffer).unwrap();
+        assert_eq!(received, BATCH_HEADER_LEN + 10 * Data::len());
+        raw_socket.set_nonblocking(true).unwrap();
+        assert!(raw_socket.recv_from(&mut buffer).is_err());
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(*subscriber.get(), data);
+
+        // Single pieces of data are still received alongside batches
+        let single = Data::new();
+        publisher.publish(single).unwrap();
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().len(), 11);
+        assert_eq!(subscriber.get()[10], single);
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct TimestampedData {
+        sent_at_us: u64,
+    }
+
+    impl Packable for TimestampedData {
+        fn len() -> usize {
+            8
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            Data {
+                num: self.sent_at_us,
+            }
+            .pack(buffer)
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            Ok(Self {
+                sent_at_us: Data::unpack(data)?.num,
+            })
+        }
+    }
+
+    impl SenderTimestamped for TimestampedData {
+        fn sent_at(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + Duration::from_micros(self.sent_at_us)
+        }
+    }
+
+    #[test]
+    fn test_publish_timestamped_subscriber() {
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8042)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8043))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpTimestampedSubscriber<TimestampedData> =
+            UdpTimestampedSubscriber::new(SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::LOCALHOST,
+                8043,
+            )))
+            .unwrap();
+        assert!(subscriber.get().is_none());
+        assert!(subscriber.one_way_latency().is_none());
+
+        let sent_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        let data = TimestampedData {
+            sent_at_us: sent_at.as_micros() as u64,
+        };
+        let published = Instant::now();
+        publisher.publish(data).unwrap();
+
+        let deadline = published + Duration::from_millis(500);
+        while subscriber.get().is_none() && Instant::now() < deadline {}
+
+        let (received_data, received) = subscriber.get().unwrap();
+        assert_eq!(received_data, data);
+        assert!(published <= received);
+        assert!(received - published < Duration::from_millis(50));
+        assert!(subscriber.one_way_latency().unwrap() < Duration::from_millis(50));
+    }
+
+    impl Sequenced for Data {
+        fn sequence(&self) -> u64 {
+            self.num
+        }
+    }
+
+    #[test]
+    fn test_publish_sequenced_buffered_subscriber() {
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8044)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8045))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpBufferedSubscriber<Data> = UdpBufferedSubscriber::new_sequenced(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8045)),
+        )
+        .unwrap();
+
+        for num in [0, 1, 3] {
+            publisher.publish(Data { num }).unwrap();
+        }
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().len(), 3);
+        assert_eq!(subscriber.dropped_count(), 1);
+    }
+
+    #[test]
+    fn test_publish_bounded_buffered_subscriber() {
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8058)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8059))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpBufferedSubscriber<Data> = UdpBufferedSubscriber::new_bounded(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8059)),
+            3,
+        )
+        .unwrap();
+
+        for num in 0..5 {
+            publisher.publish(Data { num }).unwrap();
+        }
+        sleep(Duration::from_millis(50));
+        assert_eq!(
+            subscriber.get(),
+            &vec![Data { num: 2 }, Data { num: 3 }, Data { num: 4 }]
+        );
+        assert_eq!(subscriber.dropped_count(), 2);
+
+        // The oldest data is dropped even if the buffer was filled by an earlier get
+        publisher.publish(Data { num: 5 }).unwrap();
+        sleep(Duration::from_millis(50));
+        assert_eq!(
+            subscriber.get(),
+            &vec![Data { num: 3 }, Data { num: 4 }, Data { num: 5 }]
+        );
+        assert_eq!(subscriber.dropped_count(), 3);
+    }
+
+    #[test]
+    fn test_publish_to_subscriber() {
+        let first_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8034));
+        let second_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8035));
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8033)),
+            vec![first_address, second_address],
+        )
+        .unwrap();
+
+        let mut first_subscriber: UdpSubscriber<Data> = UdpSubscriber::new(first_address).unwrap();
+        let mut second_subscriber: UdpSubscriber<Data> =
+            UdpSubscriber::new(second_address).unwrap();
+
+        let data = Data::new();
+        publisher.publish_to(data, &[second_address]).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert!(first_subscriber.get().is_none());
+        assert_eq!(second_subscriber.get().unwrap(), data);
+
+        let unknown_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8036));
+        assert!(matches!(
+            publisher.publish_to(data, &[first_address, unknown_address]),
+            Err(UdpPublishError::UnknownAddress(address)) if address == unknown_address
+        ));
+    }
+
+    #[test]
+    fn test_publish_broadcast_subscriber() {
+        let mut publisher = UdpPublisher::new_broadcast(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8029)),
+            vec![SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::new(127, 255, 255, 255),
+                8030,
+            ))],
+        )
+        .unwrap();
+        assert!(publisher.tx.broadcast().unwrap());
+
+        let mut subscriber: UdpSubscriber<Data> = UdpSubscriber::new(SocketAddr::V4(
+            SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 8030),
+        ))
+        .unwrap();
+
+        let data = Data::new();
+        publisher.publish(data).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().unwrap(), data);
+    }
+
+    #[test]
+    fn test_udp_subscriber_ignores_wrong_size_datagrams() {
+        let sender =
+            UdpSocket::bind(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8027))).unwrap();
+        let subscriber_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8028));
+        let mut subscriber: UdpSubscriber<Data> = UdpSubscriber::new(subscriber_address).unwrap();
+
+        // Send a truncated and an oversized datagram
+        sender.send_to(&[1u8; 4], subscriber_address).unwrap();
+        sender.send_to(&[1u8; 12], subscriber_address).unwrap();
+        sleep(Duration::from_millis(50));
+        assert!(subscriber.get().is_none());
+
+        let data = Data::new();
+        let mut buffer = vec![0u8; Data::len()];
+        data.pack(&mut buffer).unwrap();
+        sender.send_to(&buffer, subscriber_address).unwrap();
+        sender.send_to(&[1u8; 4], subscriber_address).unwrap();
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().unwrap(), data);
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    struct SerdeData {
+        name: String,
+        values: Vec<u32>,
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_publish_serde_subscriber() {
+        let mut publisher = UdpSerdePublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8014)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8015))],
+        )
+        .unwrap();
+
+        let mut subscriber: UdpSerdeSubscriber<SerdeData> =
+            UdpSerdeSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8015)))
+                .unwrap();
+
+        let data = SerdeData {
+            name: String::from("NComm"),
+            values: (0..100).map(|_| random()).collect(),
+        };
+        publisher.publish(data.clone()).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().as_ref().unwrap(), &data);
+    }
+
+    #[test]
+    #[cfg(feature = "hmac")]
+    fn test_publish_authenticated_subscriber() {
+        let subscriber_address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8048));
+        let secret = b"a secret shared by the robot";
+        let mut publisher = AuthenticatedUdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8046)),
+            vec![subscriber_address],
+            secret,
+        )
+        .unwrap();
+
+        let mut subscriber: AuthenticatedUdpSubscriber<Data> =
+            AuthenticatedUdpSubscriber::new(subscriber_address, secret).unwrap();
+
+        // A forged datagram with a bad tag is rejected
+        let forger =
+            UdpSocket::bind(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8047))).unwrap();
+        let mut forged = vec![0u8; Data::len() + HMAC_TAG_LEN];
+        Data::new().pack(&mut forged[..Data::len()]).unwrap();
+        forger.send_to(&forged, subscriber_address).unwrap();
+
+        // as is data tagged with the wrong secret
+        let mut wrong_secret_publisher = AuthenticatedUdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8049)),
+            vec![subscriber_address],
+            b"not the secret",
+        )
+        .unwrap();
+        wrong_secret_publisher.publish(Data::new()).unwrap();
+
+        sleep(Duration::from_millis(50));
+        assert!(subscriber.get().is_none());
+
+        // A correctly tagged datagram is accepted
+        let data = Data::new();
+        publisher.publish(data).unwrap();
+        sleep(Duration::from_millis(50));
+        assert_eq!(subscriber.get().unwrap(), data);
+    }
+
+    #[test]
+    fn test_udp_socket_options() {
+        let publisher: UdpPublisher<Data> = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8056)),
+            vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8057))],
+        )
+        .unwrap()
+        .with_ttl(5)
+        .unwrap()
+        .with_send_buffer_size(64 * 1024)
+        .unwrap();
+        assert_eq!(publisher.tx.ttl().unwrap(), 5);
+
+        let subscriber: UdpSubscriber<Data> =
+            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8057)))
+                .unwrap()
+                .with_ttl(12)
+                .unwrap()
+                .with_recv_buffer_size(64 * 1024)
+                .unwrap();
+        assert_eq!(subscriber.rx.ttl().unwrap(), 12);
+        // The OS may grow the buffer (i.e. Linux doubles it) but never shrinks it
+        assert!(SockRef::from(&subscriber.rx).recv_buffer_size().unwrap() >= 64 * 1024);
+    }
+
+    #[test]
+    fn test_udp_local_addr() {
+        let mut subscriber: UdpSubscriber<Data> =
+            UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))).unwrap();
+        let subscriber_address = subscriber.local_addr().unwrap();
+        assert_ne!(subscriber_address.port(), 0);
+
+        let mut publisher = UdpPublisher::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)),
+            vec![subscriber_address],
+        )
+        .unwrap();
+        let publisher_address = publisher.local_addr().unwrap();
+        assert_ne!(publisher_address.port(), 0);
+        assert_ne!(publisher_address, subscriber_address);
+
+        publisher.publish(Data { num: 11 }).unwrap();
+        sleep(Duration::from_millis(50));
+        assert_eq!(*subscriber.get(), Some(Data { num: 11 }));
+    }
 }
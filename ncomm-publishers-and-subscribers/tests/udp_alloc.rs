@@ -0,0 +1,179 @@
+//!
+//! UDP Allocation Tests
+//!
+//! These tests replace the global allocator with one that counts allocations so
+//! they live in their own test binary instead of affecting every other test.
+//!
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    thread::sleep,
+    time::Duration,
+};
+
+use rand::random;
+
+use ncomm_core::{Publisher, Subscriber};
+use ncomm_publishers_and_subscribers::udp::{
+    UdpBufferedSubscriber, UdpFragmentedSubscriber, UdpPublisher, UdpSubscriber,
+};
+use ncomm_utils::packing::{Packable, PackingError};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// An allocator that counts the allocations made by each thread
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Data {
+    num: u64,
+}
+
+impl Data {
+    pub fn new() -> Self {
+        Self { num: random() }
+    }
+}
+
+impl Packable for Data {
+    fn len() -> usize {
+        8
+    }
+
+    fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+        if buffer.len() < 8 {
+            Err(PackingError::InvalidBufferSize {
+                expected: 8,
+                got: buffer.len(),
+            })
+        } else {
+            buffer[..8].copy_from_slice(&self.num.to_le_bytes());
+            Ok(())
+        }
+    }
+
+    fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+        if data.len() < 8 {
+            Err(PackingError::InvalidBufferSize {
+                expected: 8,
+                got: data.len(),
+            })
+        } else {
+            Ok(Self {
+                num: u64::from_le_bytes(data[..8].try_into().unwrap()),
+            })
+        }
+    }
+}
+
+#[test]
+fn test_publish_does_not_allocate() {
+    let mut publisher = UdpPublisher::new(
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8037)),
+        vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8038))],
+    )
+    .unwrap();
+
+    let mut subscriber: UdpBufferedSubscriber<Data> =
+        UdpBufferedSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8038)))
+            .unwrap();
+
+    let data = Data::new();
+    publisher.publish(data).unwrap();
+
+    let allocations = ALLOCATIONS.with(|allocations| allocations.get());
+    for _ in 0..1000 {
+        publisher.publish(data).unwrap();
+    }
+    assert_eq!(
+        ALLOCATIONS.with(|allocations| allocations.get()),
+        allocations
+    );
+
+    sleep(Duration::from_millis(50));
+    assert_eq!(*subscriber.get().last().unwrap(), data);
+}
+
+#[test]
+fn test_subscriber_poll_does_not_allocate() {
+    let mut publisher = UdpPublisher::new(
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8067)),
+        vec![SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8068))],
+    )
+    .unwrap();
+
+    let mut subscriber: UdpSubscriber<Data> =
+        UdpSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8068))).unwrap();
+
+    let data = Data::new();
+    publisher.publish(data).unwrap();
+    sleep(Duration::from_millis(50));
+
+    let allocations = ALLOCATIONS.with(|allocations| allocations.get());
+    for _ in 0..1000 {
+        assert!(subscriber.has_update());
+    }
+    assert_eq!(subscriber.get().unwrap(), data);
+    assert!(!subscriber.has_update());
+    assert_eq!(
+        ALLOCATIONS.with(|allocations| allocations.get()),
+        allocations
+    );
+}
+
+#[test]
+fn test_fragmented_subscriber_poll_does_not_allocate() {
+    let mut subscriber: UdpFragmentedSubscriber<Data> = UdpFragmentedSubscriber::new(
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8069)),
+        Duration::from_millis(100),
+    )
+    .unwrap();
+
+    let allocations = ALLOCATIONS.with(|allocations| allocations.get());
+    for _ in 0..1000 {
+        assert!(!subscriber.has_update());
+        assert!(subscriber.get().is_none());
+    }
+    assert_eq!(
+        ALLOCATIONS.with(|allocations| allocations.get()),
+        allocations
+    );
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde_subscriber_poll_does_not_allocate() {
+    use ncomm_publishers_and_subscribers::udp::UdpSerdeSubscriber;
+
+    let mut subscriber: UdpSerdeSubscriber<u64> =
+        UdpSerdeSubscriber::new(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8070)))
+            .unwrap();
+
+    let allocations = ALLOCATIONS.with(|allocations| allocations.get());
+    for _ in 0..1000 {
+        assert!(!subscriber.has_update());
+        assert!(subscriber.get().is_none());
+    }
+    assert_eq!(
+        ALLOCATIONS.with(|allocations| allocations.get()),
+        allocations
+    );
+}
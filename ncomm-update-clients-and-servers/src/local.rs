@@ -30,7 +30,8 @@ impl<Req, Updt, Res> UpdateClient for LocalUpdateClient<Req, Updt, Res> {
     type Error = Infallible;
 
     fn send_request(&mut self, request: Self::Request) -> Result<(), Self::Error> {
-        self.tx.send(request).unwrap();
+        // The request is dropped if the server has removed this client
+        let _ = self.tx.send(request);
         Ok(())
     }
 
@@ -70,7 +71,9 @@ impl<Req, Updt, Res> UpdateClient for LocalUpdateClient<Req, Updt, Res> {
 /// A local update server that receives requests via a crossbeam channel, responds
 /// with updates via another channel, and finally sends a response via a final
 /// channel
-pub struct LocalUpdateServer<Req: Clone, Updt, Res, K: Hash + Eq + Clone> {
+///
+/// Clients are identified by a key of type K (a String by default).
+pub struct LocalUpdateServer<Req: Clone, Updt, Res, K: Hash + Eq + Clone = String> {
     /// A map between client identifiers and their channels
     #[allow(clippy::type_complexity)]
     client_map: HashMap<K, (Receiver<Req>, Sender<(Req, Updt)>, Sender<(Req, Res)>)>,
@@ -102,6 +105,14 @@ impl<Req: Clone, Updt, Res, K: Hash + Eq + Clone> LocalUpdateServer<Req, Updt, R
             tx: req_tx,
         }
     }
+
+    /// Remove a client from the server, returning whether the client existed.
+    ///
+    /// Requests from a removed client are dropped and it no longer receives
+    /// updates or responses.
+    pub fn remove_client(&mut self, key: &K) -> bool {
+        self.client_map.remove(key).is_some()
+    }
 }
 
 impl<Req: Clone, Updt, Res, K: Hash + Eq + Clone> UpdateServer
@@ -132,6 +143,10 @@ impl<Req: Clone, Updt, Res, K: Hash + Eq + Clone> UpdateServer
         requests
     }
 
+    fn get_clients(&self) -> Vec<Self::Key> {
+        self.client_map.keys().cloned().collect()
+    }
+
     fn send_update(
         &mut self,
         client_key: Self::Key,
@@ -272,4 +287,59 @@ mod tests {
             assert!(false, "Expected a response to be received");
         }
     }
+
+    #[test]
+    fn test_local_update_server_broadcast() {
+        let mut server = LocalUpdateServer::new();
+        let mut clients: Vec<LocalUpdateClient<Request, Update, Response>> = (0..3u8)
+            .map(|key| server.create_update_client(key))
+            .collect();
+        let mut client_keys = server.get_clients();
+        client_keys.sort();
+        assert_eq!(client_keys, vec![0, 1, 2]);
+
+        let request = Request::new();
+        let update = Update::new(request);
+        let response = Response::new(request);
+        assert!(server
+            .broadcast_update(&request, update)
+            .iter()
+            .all(Result::is_ok));
+        assert!(server
+            .broadcast_response(request, response)
+            .iter()
+            .all(Result::is_ok));
+
+        for client in clients.iter_mut() {
+            assert_eq!(client.poll_for_update().unwrap(), Some((request, update)));
+            assert_eq!(
+                client.poll_for_response().unwrap(),
+                Some((request, response))
+            );
+        }
+    }
+
+    #[test]
+    fn test_local_update_server_keys() {
+        let mut server: LocalUpdateServer<Request, Update, Response> = LocalUpdateServer::new();
+        let _named_client = server.create_update_client(String::from("client"));
+        assert_eq!(server.get_clients(), vec![String::from("client")]);
+
+        let mut server: LocalUpdateServer<Request, Update, Response, u32> =
+            LocalUpdateServer::new();
+        let mut numbered_client = server.create_update_client(42);
+        let request = Request::new();
+        numbered_client.send_request(request).unwrap();
+        let Ok(Some((key, request))) = server.poll_for_request() else {
+            panic!("Expected a request");
+        };
+        assert_eq!(key, 42);
+        server
+            .send_update(key, &request, Update::new(request))
+            .unwrap();
+        assert_eq!(
+            numbered_client.poll_for_update().unwrap(),
+            Some((request, Update::new(request)))
+        );
+    }
 }
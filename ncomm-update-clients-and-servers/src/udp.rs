@@ -27,24 +27,75 @@ pub enum UdpUpdateClientServerError<Data: Packable> {
     UnknownRequester((Data, SocketAddr)),
     /// The client you are sending data to is unknown
     UnknownClient,
+    /// The request you are sending an update or response for was never received
+    /// from the client (or has already been responded to)
+    UnknownRequest,
+    /// The client already has its maximum number of requests waiting for a response
+    TooManyInFlight,
 }
 
+impl<Data: Packable> std::fmt::Display for UdpUpdateClientServerError<Data> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IOError(err) => write!(f, "io error: {}", err),
+            Self::PackingError(err) => write!(f, "packing error: {}", err),
+            Self::UnknownRequester((_, address)) => {
+                write!(f, "request from unknown client {}", address)
+            }
+            Self::UnknownClient => write!(f, "unknown client"),
+            Self::UnknownRequest => write!(f, "unknown request"),
+            Self::TooManyInFlight => write!(f, "too many requests in flight"),
+        }
+    }
+}
+
+impl<Data: Packable + std::fmt::Debug> std::error::Error for UdpUpdateClientServerError<Data> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(err) => Some(err),
+            Self::PackingError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The length of the request id prepended to every request, update, and response
+const REQUEST_ID_LEN: usize = 4;
+
+/// The type tag prepended to every update sent by the server
+const UPDATE_TAG: u8 = 0;
+
+/// The type tag prepended to every response sent by the server
+const RESPONSE_TAG: u8 = 1;
+
 /// A Udp update client that sends request via a UdpSocket to a specific IP, receives
 /// periodic updates, and finally receives a response via a bound UdpSocket
 ///
-/// Note: If Update and Response Packets are the same length, there is a chance
-/// that when polling for updates a response will be received and processed.
-/// This is, obviously, suboptimal and I will fix this in a later version but
-/// for now I'd like to get version 1.0 out.
+/// Every request is prepended with a u32 request id (generated by the client) that
+/// the server echoes in each of its updates and responses so the updates and responses
+/// for concurrent requests can be told apart.
+///
+/// Updates and responses from the server are additionally prepended with a type tag
+/// so updates and responses can be told apart even when they are the same length.
+///
+/// The number of requests waiting for a response can be limited via
+/// `set_max_in_flight` so a client can't overwhelm its server.
 pub struct UdpUpdateClient<Req: Packable, Updt: Packable, Res: Packable> {
     /// The Udp Socket bound for transmitting requests and receiving responses
     socket: UdpSocket,
     /// The address to send data to
     address: SocketAddr,
+    /// The id of the next request sent by the client
+    next_id: u32,
+    /// The maximum number of requests that may be waiting for a response
+    max_in_flight: Option<usize>,
+    /// The ids of the requests waiting for a response (only tracked when the
+    /// number of requests in flight is limited)
+    in_flight: Vec<u32>,
     /// A buffer to keep any updates received when polling for responses
-    update_buffer: Vec<Result<(Req, Updt), UdpUpdateClientServerError<Req>>>,
+    update_buffer: Vec<(u32, Req, Updt)>,
     /// A buffer to keep any responses received when polling for updates
-    response_buffer: Vec<Result<(Req, Res), UdpUpdateClientServerError<Req>>>,
+    response_buffer: Vec<(u32, Req, Res)>,
     /// A PhantomData to bind the specific request, update, and response types to
     /// the update client
     _phantom: PhantomData<(Req, Updt, Res)>,
@@ -59,200 +110,213 @@ impl<Req: Packable, Updt: Packable, Res: Packable> UdpUpdateClient<Req, Updt, Re
         Ok(Self {
             socket,
             address: server_address,
+            next_id: 0,
+            max_in_flight: None,
+            in_flight: Vec::new(),
             update_buffer: Vec::new(),
             response_buffer: Vec::new(),
             _phantom: PhantomData,
         })
     }
-}
 
-impl<Req: Packable, Updt: Packable, Res: Packable> UpdateClient
-    for UdpUpdateClient<Req, Updt, Res>
-{
-    type Request = Req;
-    type Update = Updt;
-    type Response = Res;
-    type Error = UdpUpdateClientServerError<Req>;
+    /// Limit the number of requests that may be waiting for a response (or remove
+    /// the limit with None).
+    ///
+    /// Note: Once the limit is reached, requests are rejected with `TooManyInFlight`
+    /// until a response is polled for one of the requests in flight.
+    pub fn set_max_in_flight(&mut self, max_in_flight: Option<usize>) {
+        self.max_in_flight = max_in_flight;
+        if max_in_flight.is_none() {
+            self.in_flight.clear();
+        }
+    }
 
-    fn send_request(&mut self, request: Self::Request) -> Result<(), Self::Error> {
-        let mut buffer = vec![0u8; Req::len()];
+    /// Get the number of requests waiting for a response.
+    ///
+    /// Note: Requests are only tracked while the number of requests in flight is
+    /// limited.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Send a request to the server, returning the id of the request
+    pub fn send_request_with_id(
+        &mut self,
+        request: Req,
+    ) -> Result<u32, UdpUpdateClientServerError<Req>> {
+        if self
+            .max_in_flight
+            .is_some_and(|max_in_flight| self.in_flight.len() >= max_in_flight)
+        {
+            return Err(UdpUpdateClientServerError::TooManyInFlight);
+        }
+
+        let id = self.next_id;
+        let mut buffer = vec![0u8; REQUEST_ID_LEN + Req::len()];
+        buffer[..REQUEST_ID_LEN].copy_from_slice(&id.to_le_bytes());
         request
-            .pack(&mut buffer)
+            .pack(&mut buffer[REQUEST_ID_LEN..])
             .map_err(UdpUpdateClientServerError::PackingError)?;
 
         self.socket
             .send_to(&buffer, self.address)
             .map_err(UdpUpdateClientServerError::IOError)?;
-        Ok(())
+        self.next_id = self.next_id.wrapping_add(1);
+        if self.max_in_flight.is_some() {
+            self.in_flight.push(id);
+        }
+        Ok(id)
     }
 
-    fn poll_for_update(&mut self) -> Result<Option<(Self::Request, Self::Update)>, Self::Error> {
-        let mut buffer = vec![0u8; Req::len() + std::cmp::max(Updt::len(), Res::len())];
-        loop {
-            let (req, updt) = match self.socket.recv(&mut buffer) {
-                Ok(received) => {
-                    if received - Req::len() == Updt::len() {
-                        (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Updt::unpack(&buffer[Req::len()..]),
-                        )
-                    } else if received - Req::len() == Res::len() {
-                        let (req, res) = (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Res::unpack(&buffer[Req::len()..]),
-                        );
-
-                        if let (Ok(req), Ok(res)) = (req, res) {
-                            self.response_buffer.push(Ok((req, res)));
-                        }
-                        buffer.iter_mut().for_each(|v| *v = 0);
-                        continue;
-                    } else {
-                        break;
-                    }
-                }
-                Err(_) => break,
-            };
+    /// Check for an update, returning the id of the request the update is for
+    #[allow(clippy::type_complexity)]
+    pub fn poll_for_update_with_id(
+        &mut self,
+    ) -> Result<Option<(u32, Req, Updt)>, UdpUpdateClientServerError<Req>> {
+        self.receive();
+        if self.update_buffer.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(self.update_buffer.remove(0)))
+        }
+    }
 
-            if let (Ok(req), Ok(updt)) = (req, updt) {
-                return Ok(Some((req, updt)));
-            }
+    /// Check for updates, returning the id of the request each update is for
+    #[allow(clippy::type_complexity)]
+    pub fn poll_for_updates_with_id(
+        &mut self,
+    ) -> Vec<Result<(u32, Req, Updt), UdpUpdateClientServerError<Req>>> {
+        self.receive();
+        self.update_buffer.drain(..).map(Ok).collect()
+    }
+
+    /// Check for a response, returning the id of the request the response is for
+    #[allow(clippy::type_complexity)]
+    pub fn poll_for_response_with_id(
+        &mut self,
+    ) -> Result<Option<(u32, Req, Res)>, UdpUpdateClientServerError<Req>> {
+        self.receive();
+        if self.response_buffer.is_empty() {
+            Ok(None)
+        } else {
+            let response = self.response_buffer.remove(0);
+            self.complete(response.0);
+            Ok(Some(response))
         }
-        Ok(None)
     }
 
-    fn poll_for_updates(&mut self) -> Vec<Result<(Self::Request, Self::Update), Self::Error>> {
-        let mut updates = Vec::new();
-        updates.append(&mut self.update_buffer);
-
-        let mut buffer = vec![0u8; Req::len() + std::cmp::max(Updt::len(), Res::len())];
-        loop {
-            let (req, updt) = match self.socket.recv(&mut buffer) {
-                Ok(received) => {
-                    if received - Req::len() == Updt::len() {
-                        (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Updt::unpack(&buffer[Req::len()..]),
-                        )
-                    } else if received - Req::len() == Res::len() {
-                        let (req, res) = (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Res::unpack(&buffer[Req::len()..]),
-                        );
-
-                        if let (Ok(req), Ok(res)) = (req, res) {
-                            self.response_buffer.push(Ok((req, res)));
-                        }
-                        buffer.iter_mut().for_each(|v| *v = 0);
-                        continue;
-                    } else {
-                        (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Err(PackingError::InvalidBufferSize),
-                        )
+    /// Check for responses, returning the id of the request each response is for
+    #[allow(clippy::type_complexity)]
+    pub fn poll_for_responses_with_id(
+        &mut self,
+    ) -> Vec<Result<(u32, Req, Res), UdpUpdateClientServerError<Req>>> {
+        self.receive();
+        let responses: Vec<(u32, Req, Res)> = self.response_buffer.drain(..).collect();
+        for (id, _, _) in responses.iter() {
+            self.complete(*id);
+        }
+        responses.into_iter().map(Ok).collect()
+    }
+
+    /// Stop tracking a request once its response has been polled
+    fn complete(&mut self, id: u32) {
+        if let Some(idx) = self.in_flight.iter().position(|in_flight| *in_flight == id) {
+            self.in_flight.swap_remove(idx);
+        }
+    }
+
+    /// Receive every datagram waiting on the socket, sorting them into the update
+    /// and response buffers.
+    ///
+    /// Note: Incoming data will be in the form:
+    /// \[tag, id\[0\], ..., id\[3\], request\[0\], ..., request\[-1\], update_or_response\[0\], ...\]
+    fn receive(&mut self) {
+        let header_len = 1 + REQUEST_ID_LEN + Req::len();
+        let mut buffer = vec![0u8; header_len + std::cmp::max(Updt::len(), Res::len())];
+        while let Ok(received) = self.socket.recv(&mut buffer) {
+            if received < header_len {
+                continue;
+            }
+
+            let id = u32::from_le_bytes(buffer[1..1 + REQUEST_ID_LEN].try_into().unwrap());
+            let req = Req::unpack(&buffer[1 + REQUEST_ID_LEN..header_len]);
+            match buffer[0] {
+                UPDATE_TAG if received - header_len == Updt::len() => {
+                    if let (Ok(req), Ok(updt)) = (req, Updt::unpack(&buffer[header_len..received]))
+                    {
+                        self.update_buffer.push((id, req, updt));
                     }
                 }
-                Err(_) => break,
-            };
-            buffer.iter_mut().for_each(|v| *v = 0);
-
-            if let (Ok(req), Ok(updt)) = (req, updt) {
-                updates.push(Ok((req, updt)));
+                RESPONSE_TAG if received - header_len == Res::len() => {
+                    if let (Ok(req), Ok(res)) = (req, Res::unpack(&buffer[header_len..received])) {
+                        self.response_buffer.push((id, req, res));
+                    }
+                }
+                _ => (),
             }
             buffer.iter_mut().for_each(|v| *v = 0);
         }
+    }
+}
 
-        updates
+impl<Req: Packable, Updt: Packable, Res: Packable> UpdateClient
+    for UdpUpdateClient<Req, Updt, Res>
+{
+    type Request = Req;
+    type Update = Updt;
+    type Response = Res;
+    type Error = UdpUpdateClientServerError<Req>;
+
+    fn send_request(&mut self, request: Self::Request) -> Result<(), Self::Error> {
+        self.send_request_with_id(request).map(|_| ())
+    }
+
+    fn poll_for_update(&mut self) -> Result<Option<(Self::Request, Self::Update)>, Self::Error> {
+        self.poll_for_update_with_id()
+            .map(|update| update.map(|(_, req, updt)| (req, updt)))
+    }
+
+    fn poll_for_updates(&mut self) -> Vec<Result<(Self::Request, Self::Update), Self::Error>> {
+        self.poll_for_updates_with_id()
+            .into_iter()
+            .map(|update| update.map(|(_, req, updt)| (req, updt)))
+            .collect()
     }
 
     fn poll_for_response(
         &mut self,
     ) -> Result<Option<(Self::Request, Self::Response)>, Self::Error> {
-        let mut buffer = vec![0u8; Req::len() + std::cmp::max(Updt::len(), Res::len())];
-        loop {
-            let (req, res) = match self.socket.recv(&mut buffer) {
-                Ok(received) => {
-                    if received - Req::len() == Updt::len() {
-                        let (req, updt) = (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Updt::unpack(&buffer[Req::len()..]),
-                        );
-
-                        if let (Ok(req), Ok(updt)) = (req, updt) {
-                            self.update_buffer.push(Ok((req, updt)));
-                        }
-
-                        buffer.iter_mut().for_each(|v| *v = 0);
-                        continue;
-                    } else if received - Req::len() == Res::len() {
-                        (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Res::unpack(&buffer[Req::len()..]),
-                        )
-                    } else {
-                        break;
-                    }
-                }
-                Err(_) => break,
-            };
-
-            if let (Ok(req), Ok(res)) = (req, res) {
-                return Ok(Some((req, res)));
-            }
-        }
-        Ok(None)
+        self.poll_for_response_with_id()
+            .map(|response| response.map(|(_, req, res)| (req, res)))
     }
 
     fn poll_for_responses(&mut self) -> Vec<Result<(Self::Request, Self::Response), Self::Error>> {
-        let mut responses = Vec::new();
-        responses.append(&mut self.response_buffer);
-
-        let mut buffer = vec![0u8; Req::len() + std::cmp::max(Updt::len(), Res::len())];
-        loop {
-            let (req, res) = match self.socket.recv(&mut buffer) {
-                Ok(received) => {
-                    if received - Req::len() == Updt::len() {
-                        let (req, updt) = (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Updt::unpack(&buffer[Req::len()..]),
-                        );
-
-                        if let (Ok(req), Ok(updt)) = (req, updt) {
-                            self.update_buffer.push(Ok((req, updt)));
-                        }
-                        continue;
-                    } else if received - Req::len() == Res::len() {
-                        (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Res::unpack(&buffer[Req::len()..]),
-                        )
-                    } else {
-                        (
-                            Req::unpack(&buffer[..Req::len()]),
-                            Err(PackingError::InvalidBufferSize),
-                        )
-                    }
-                }
-                Err(_) => break,
-            };
-            buffer.iter_mut().for_each(|v| *v = 0);
-
-            if let (Ok(req), Ok(res)) = (req, res) {
-                responses.push(Ok((req, res)));
-            }
-        }
-
-        responses
+        self.poll_for_responses_with_id()
+            .into_iter()
+            .map(|response| response.map(|(_, req, res)| (req, res)))
+            .collect()
     }
 }
 
 /// A Udp Update server that receives requests via a Udp Socket and sends updates and
 /// responses via the same Udp Socket to a given client identifiable by K.
+///
+/// The request id the client prepends to each request is remembered until the
+/// request has been responded to and is echoed in every update and response sent
+/// for the request.
+///
+/// Note: Identical requests in flight from the same client can only be told apart
+/// via the `_with_id` methods.  Updates and responses sent via the UpdateServer
+/// methods go to the oldest pending request that matches.
 pub struct UdpUpdateServer<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone> {
     /// The socket bound to by the UdpUpdateServer
     socket: UdpSocket,
     /// A Map between client identifiers and their addresses
     client_addresses: Vec<(K, SocketAddr)>,
+    /// The requests that have not yet been responded to (in the order they were
+    /// received), stored as the address of the client, the id of the request, and
+    /// the packed request
+    pending: Vec<(SocketAddr, u32, Vec<u8>)>,
     /// Bind the specific request, update, and response type to the update server
     _phantom: PhantomData<(Req, Updt, Res)>,
 }
@@ -262,13 +326,7 @@ impl<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone>
 {
     /// Create a new Udp Update Server
     pub fn new(bind_address: SocketAddr) -> Result<Self, Error> {
-        let socket = UdpSocket::bind(bind_address)?;
-        socket.set_nonblocking(true)?;
-        Ok(Self {
-            socket,
-            client_addresses: Vec::new(),
-            _phantom: PhantomData,
-        })
+        Self::new_with(bind_address, Vec::new())
     }
 
     /// Create a new Udp Update Server with a list of clients and addresses
@@ -281,6 +339,7 @@ impl<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone>
         Ok(Self {
             socket,
             client_addresses: clients,
+            pending: Vec::new(),
             _phantom: PhantomData,
         })
     }
@@ -289,65 +348,177 @@ impl<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone>
     pub fn add_clients(&mut self, mut clients: Vec<(K, SocketAddr)>) {
         self.client_addresses.append(&mut clients);
     }
-}
 
-impl<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone> UpdateServer
-    for UdpUpdateServer<Req, Updt, Res, K>
-{
-    type Request = Req;
-    type Update = Updt;
-    type Response = Res;
-    type Key = K;
-    type Error = UdpUpdateClientServerError<Req>;
+    /// Remove a client (and its pending requests) from the server, returning
+    /// whether the client existed.
+    ///
+    /// Requests from a removed client are reported as coming from an unknown requester.
+    pub fn remove_client(&mut self, key: &K) -> bool {
+        let clients = self.client_addresses.len();
+        let mut removed = Vec::new();
+        self.client_addresses.retain(|(k, address)| {
+            if k == key {
+                removed.push(*address);
+            }
+            k != key
+        });
+        self.pending
+            .retain(|(address, _, _)| !removed.contains(address));
+        self.client_addresses.len() != clients
+    }
 
-    fn poll_for_request(&mut self) -> Result<Option<(Self::Key, Self::Request)>, Self::Error> {
-        let mut buffer = vec![0u8; Req::len()];
+    /// Check for a request, returning the id the client gave the request
+    #[allow(clippy::type_complexity)]
+    pub fn poll_for_request_with_id(
+        &mut self,
+    ) -> Result<Option<(K, u32, Req)>, UdpUpdateClientServerError<Req>> {
+        self.receive_request().transpose()
+    }
+
+    /// Check for requests, returning the id the client gave each request
+    #[allow(clippy::type_complexity)]
+    pub fn poll_for_requests_with_id(
+        &mut self,
+    ) -> Vec<Result<(K, u32, Req), UdpUpdateClientServerError<Req>>> {
+        let mut requests = Vec::new();
+        while let Some(request) = self.receive_request() {
+            requests.push(request);
+        }
+        requests
+    }
+
+    /// Send an update for the request with a given id to a client
+    pub fn send_update_with_id(
+        &mut self,
+        client_key: K,
+        id: u32,
+        request: &Req,
+        update: Updt,
+    ) -> Result<(), UdpUpdateClientServerError<Req>> {
+        self.send_to_client(client_key, Some(id), request, update, UPDATE_TAG)
+    }
+
+    /// Send a response for the request with a given id to a client
+    pub fn send_response_with_id(
+        &mut self,
+        client_key: K,
+        id: u32,
+        request: Req,
+        response: Res,
+    ) -> Result<(), UdpUpdateClientServerError<Req>> {
+        self.send_to_client(client_key, Some(id), &request, response, RESPONSE_TAG)
+    }
+
+    /// Receive a single request (and its id) from the socket, returning None if there
+    /// are no requests waiting to be received.
+    ///
+    /// Note: A request whose id is already pending for the client (i.e. a resent
+    /// request) replaces the pending request.
+    #[allow(clippy::type_complexity)]
+    fn receive_request(
+        &mut self,
+    ) -> Option<Result<(K, u32, Req), UdpUpdateClientServerError<Req>>> {
+        let mut buffer = vec![0u8; REQUEST_ID_LEN + Req::len()];
         let address = match self.socket.recv_from(&mut buffer) {
-            Ok((_requset_size, address)) => address,
-            Err(_) => return Ok(None),
+            Ok((_request_size, address)) => address,
+            Err(_) => return None,
         };
 
-        match Req::unpack(&buffer[..]) {
+        let id = u32::from_le_bytes(buffer[..REQUEST_ID_LEN].try_into().unwrap());
+        match Req::unpack(&buffer[REQUEST_ID_LEN..]) {
             Ok(data) => {
                 if let Some((k, _)) = self.client_addresses.iter().find(|v| v.1 == address) {
-                    Ok(Some((k.clone(), data)))
+                    let request_bytes = buffer[REQUEST_ID_LEN..].to_vec();
+                    match self
+                        .pending
+                        .iter_mut()
+                        .find(|(a, i, _)| *a == address && *i == id)
+                    {
+                        Some(pending) => pending.2 = request_bytes,
+                        None => self.pending.push((address, id, request_bytes)),
+                    }
+                    Some(Ok((k.clone(), id, data)))
                 } else {
-                    Err(UdpUpdateClientServerError::UnknownRequester((
+                    Some(Err(UdpUpdateClientServerError::UnknownRequester((
                         data, address,
-                    )))
+                    ))))
                 }
             }
-            Err(err) => Err(UdpUpdateClientServerError::PackingError(err)),
+            Err(err) => Some(Err(UdpUpdateClientServerError::PackingError(err))),
         }
     }
 
-    fn poll_for_requests(&mut self) -> Vec<Result<(Self::Key, Self::Request), Self::Error>> {
-        let mut requests = Vec::new();
+    /// Pack the type tag, request id, request, and update or response into a buffer and
+    /// send it to the client.  Responses remove the request from the pending requests.
+    ///
+    /// Without an id, the data is sent for the oldest pending request from the client
+    /// that matches the request.
+    fn send_to_client<Data: Packable>(
+        &mut self,
+        client_key: K,
+        id: Option<u32>,
+        request: &Req,
+        data: Data,
+        tag: u8,
+    ) -> Result<(), UdpUpdateClientServerError<Req>> {
+        let Some(&(_, address)) = self.client_addresses.iter().find(|v| v.0 == client_key) else {
+            return Err(UdpUpdateClientServerError::UnknownClient);
+        };
 
-        let mut buffer = vec![0u8; Req::len()];
-        loop {
-            let address = match self.socket.recv_from(&mut buffer) {
-                Ok((_request_size, address)) => address,
-                Err(_) => break,
-            };
-
-            match Req::unpack(&buffer[..]) {
-                Ok(data) => {
-                    if let Some((k, _)) = self.client_addresses.iter().find(|v| v.1 == address) {
-                        requests.push(Ok((k.clone(), data)));
-                    } else {
-                        requests.push(Err(UdpUpdateClientServerError::UnknownRequester((
-                            data, address,
-                        ))));
-                    }
-                }
-                Err(err) => requests.push(Err(UdpUpdateClientServerError::PackingError(err))),
-            }
+        let header_len = 1 + REQUEST_ID_LEN + Req::len();
+        let mut buffer = vec![0u8; header_len + Data::len()];
+        buffer[0] = tag;
+        request
+            .clone()
+            .pack(&mut buffer[1 + REQUEST_ID_LEN..header_len])
+            .map_err(UdpUpdateClientServerError::PackingError)?;
+        data.pack(&mut buffer[header_len..])
+            .map_err(UdpUpdateClientServerError::PackingError)?;
 
-            buffer.iter_mut().for_each(|v| *v = 0);
+        let request_bytes = &buffer[1 + REQUEST_ID_LEN..header_len];
+        let Some(idx) = self.pending.iter().position(|(a, i, req)| {
+            *a == address && id.map_or(req == request_bytes, |id| *i == id)
+        }) else {
+            return Err(UdpUpdateClientServerError::UnknownRequest);
+        };
+        buffer[1..1 + REQUEST_ID_LEN].copy_from_slice(&self.pending[idx].1.to_le_bytes());
+
+        self.socket
+            .send_to(&buffer, address)
+            .map_err(UdpUpdateClientServerError::IOError)?;
+        if tag == RESPONSE_TAG {
+            self.pending.remove(idx);
         }
+        Ok(())
+    }
+}
 
-        requests
+impl<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone> UpdateServer
+    for UdpUpdateServer<Req, Updt, Res, K>
+{
+    type Request = Req;
+    type Update = Updt;
+    type Response = Res;
+    type Key = K;
+    type Error = UdpUpdateClientServerError<Req>;
+
+    fn poll_for_request(&mut self) -> Result<Option<(Self::Key, Self::Request)>, Self::Error> {
+        self.poll_for_request_with_id()
+            .map(|request| request.map(|(k, _, req)| (k, req)))
+    }
+
+    fn poll_for_requests(&mut self) -> Vec<Result<(Self::Key, Self::Request), Self::Error>> {
+        self.poll_for_requests_with_id()
+            .into_iter()
+            .map(|request| request.map(|(k, _, req)| (k, req)))
+            .collect()
+    }
+
+    fn get_clients(&self) -> Vec<Self::Key> {
+        self.client_addresses
+            .iter()
+            .map(|(k, _)| k.clone())
+            .collect()
     }
 
     fn send_update(
@@ -356,24 +527,7 @@ impl<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone> Update
         request: &Self::Request,
         update: Self::Update,
     ) -> Result<(), Self::Error> {
-        if let Some((_, address)) = self.client_addresses.iter().find(|v| v.0 == client_key) {
-            let mut buffer = vec![0u8; Req::len() + Updt::len()];
-
-            request
-                .clone()
-                .pack(&mut buffer[0..Req::len()])
-                .map_err(UdpUpdateClientServerError::PackingError)?;
-            update
-                .pack(&mut buffer[Req::len()..])
-                .map_err(UdpUpdateClientServerError::PackingError)?;
-
-            self.socket
-                .send_to(&buffer, address)
-                .map_err(UdpUpdateClientServerError::IOError)?;
-            Ok(())
-        } else {
-            Err(UdpUpdateClientServerError::UnknownClient)
-        }
+        self.send_to_client(client_key, None, request, update, UPDATE_TAG)
     }
 
     fn send_response(
@@ -382,24 +536,7 @@ impl<Req: Packable + Clone, Updt: Packable, Res: Packable, K: Eq + Clone> Update
         request: Self::Request,
         response: Self::Response,
     ) -> Result<(), Self::Error> {
-        if let Some((_, address)) = self.client_addresses.iter().find(|v| v.0 == client_key) {
-            let mut buffer = vec![0u8; Req::len() + Res::len()];
-
-            request
-                .clone()
-                .pack(&mut buffer[0..Req::len()])
-                .map_err(UdpUpdateClientServerError::PackingError)?;
-            response
-                .pack(&mut buffer[Req::len()..])
-                .map_err(UdpUpdateClientServerError::PackingError)?;
-
-            self.socket
-                .send_to(&buffer, address)
-                .map_err(UdpUpdateClientServerError::IOError)?;
-            Ok(())
-        } else {
-            Err(UdpUpdateClientServerError::UnknownClient)
-        }
+        self.send_to_client(client_key, None, &request, response, RESPONSE_TAG)
     }
 }
 
@@ -433,7 +570,10 @@ mod tests {
 
         fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
             if buffer.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
             } else {
                 Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
             }
@@ -441,7 +581,10 @@ mod tests {
 
         fn unpack(data: &[u8]) -> Result<Self, PackingError> {
             if data.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
             } else {
                 Ok(Self {
                     num: u64::from_le_bytes(data[..8].try_into().unwrap()),
@@ -470,7 +613,10 @@ mod tests {
 
         fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
             if buffer.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
             } else {
                 Ok(buffer[..16].copy_from_slice(&self.num.to_le_bytes()))
             }
@@ -478,7 +624,10 @@ mod tests {
 
         fn unpack(data: &[u8]) -> Result<Self, PackingError> {
             if data.len() < 16 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 16,
+                    got: data.len(),
+                })
             } else {
                 Ok(Self {
                     num: u128::from_le_bytes(data[..16].try_into().unwrap()),
@@ -507,7 +656,10 @@ mod tests {
 
         fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
             if buffer.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
             } else {
                 Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
             }
@@ -515,7 +667,10 @@ mod tests {
 
         fn unpack(data: &[u8]) -> Result<Self, PackingError> {
             if data.len() < 8 {
-                Err(PackingError::InvalidBufferSize)
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
             } else {
                 Ok(Self {
                     num: u64::from_le_bytes(data[..8].try_into().unwrap()),
@@ -672,4 +827,330 @@ mod tests {
             assert!(false, "Expected a response");
         }
     }
+
+    #[test]
+    fn test_udp_update_client_server_interleaved_requests() {
+        let mut server: UdpUpdateServer<Request, Update, Response, i32> =
+            UdpUpdateServer::new_with(
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7014)),
+                vec![(
+                    0,
+                    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7015)),
+                )],
+            )
+            .unwrap();
+
+        let mut client: UdpUpdateClient<Request, Update, Response> = UdpUpdateClient::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7015)),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7014)),
+        )
+        .unwrap();
+
+        let first_request = Request::new();
+        let second_request = Request::new();
+        let first_id = client.send_request_with_id(first_request).unwrap();
+        let second_id = client.send_request_with_id(second_request).unwrap();
+        assert_ne!(first_id, second_id);
+
+        sleep(Duration::from_millis(50));
+
+        let requests: Vec<(i32, Request)> = server
+            .poll_for_requests()
+            .into_iter()
+            .map(|request| request.unwrap())
+            .collect();
+        assert_eq!(requests.len(), 2);
+
+        // Answer the requests in the reverse order they were received
+        for (client_key, request) in requests.into_iter().rev() {
+            server
+                .send_update(client_key, &request, Update::new(request))
+                .unwrap();
+            server
+                .send_response(client_key, request, Response::new(request))
+                .unwrap();
+        }
+
+        sleep(Duration::from_millis(50));
+
+        let updates = client.poll_for_updates_with_id();
+        assert_eq!(updates.len(), 2);
+        for update in updates {
+            let (id, request, update) = update.unwrap();
+            if id == first_id {
+                assert_eq!(request, first_request);
+                assert_eq!(update, Update::new(first_request));
+            } else {
+                assert_eq!(id, second_id);
+                assert_eq!(request, second_request);
+                assert_eq!(update, Update::new(second_request));
+            }
+        }
+
+        let responses = client.poll_for_responses_with_id();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].as_ref().unwrap().0, second_id);
+        assert_eq!(responses[1].as_ref().unwrap().0, first_id);
+        for response in responses {
+            let (id, request, response) = response.unwrap();
+            if id == first_id {
+                assert_eq!(request, first_request);
+                assert_eq!(response, Response::new(first_request));
+            } else {
+                assert_eq!(request, second_request);
+                assert_eq!(response, Response::new(second_request));
+            }
+        }
+
+        // Requests that have been responded to can no longer be updated
+        assert!(matches!(
+            server.send_update(0, &first_request, Update::new(first_request)),
+            Err(UdpUpdateClientServerError::UnknownRequest)
+        ));
+    }
+
+    #[test]
+    fn test_udp_update_client_server_identical_requests() {
+        let mut server: UdpUpdateServer<Request, Update, Response, i32> =
+            UdpUpdateServer::new_with(
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7025)),
+                vec![(
+                    0,
+                    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7026)),
+                )],
+            )
+            .unwrap();
+
+        let mut client: UdpUpdateClient<Request, Update, Response> = UdpUpdateClient::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7026)),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7025)),
+        )
+        .unwrap();
+
+        let request = Request::new();
+        let first_id = client.send_request_with_id(request).unwrap();
+        let second_id = client.send_request_with_id(request).unwrap();
+
+        sleep(Duration::from_millis(50));
+
+        let requests: Vec<(i32, u32, Request)> = server
+            .poll_for_requests_with_id()
+            .into_iter()
+            .map(|request| request.unwrap())
+            .collect();
+        assert_eq!(
+            requests,
+            vec![(0, first_id, request), (0, second_id, request)]
+        );
+
+        // Respond to the second request only
+        let response = Response { num: 42 };
+        server
+            .send_update_with_id(0, second_id, &request, Update { num: 41 })
+            .unwrap();
+        server
+            .send_response_with_id(0, second_id, request, response)
+            .unwrap();
+
+        sleep(Duration::from_millis(50));
+
+        let update = client.poll_for_update_with_id().unwrap().unwrap();
+        assert_eq!(update, (second_id, request, Update { num: 41 }));
+        let response = client.poll_for_response_with_id().unwrap().unwrap();
+        assert_eq!(response, (second_id, request, Response { num: 42 }));
+
+        // The first request is still pending but the second has been responded to
+        assert!(matches!(
+            server.send_update_with_id(0, second_id, &request, Update::new(request)),
+            Err(UdpUpdateClientServerError::UnknownRequest)
+        ));
+        server
+            .send_update_with_id(0, first_id, &request, Update::new(request))
+            .unwrap();
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct SmallUpdate {
+        num: u64,
+    }
+
+    impl Packable for SmallUpdate {
+        fn len() -> usize {
+            8
+        }
+
+        fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
+            if buffer.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: buffer.len(),
+                })
+            } else {
+                Ok(buffer[..8].copy_from_slice(&self.num.to_le_bytes()))
+            }
+        }
+
+        fn unpack(data: &[u8]) -> Result<Self, PackingError> {
+            if data.len() < 8 {
+                Err(PackingError::InvalidBufferSize {
+                    expected: 8,
+                    got: data.len(),
+                })
+            } else {
+                Ok(Self {
+                    num: u64::from_le_bytes(data[..8].try_into().unwrap()),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn test_udp_update_client_server_same_length_update_and_response() {
+        let mut server: UdpUpdateServer<Request, SmallUpdate, Response, i32> =
+            UdpUpdateServer::new_with(
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7016)),
+                vec![(
+                    0,
+                    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7017)),
+                )],
+            )
+            .unwrap();
+
+        let mut client: UdpUpdateClient<Request, SmallUpdate, Response> = UdpUpdateClient::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7017)),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7016)),
+        )
+        .unwrap();
+
+        let request = Request::new();
+        let update = SmallUpdate {
+            num: request.num.wrapping_mul(3),
+        };
+        client.send_request(request).unwrap();
+
+        sleep(Duration::from_millis(50));
+
+        let (client_key, received) = server.poll_for_request().unwrap().unwrap();
+        assert_eq!(received, request);
+        server
+            .send_response(client_key, request, Response::new(request))
+            .unwrap();
+
+        // The response is sent first so a length-based check would report it as an update
+        client.send_request(request).unwrap();
+        sleep(Duration::from_millis(50));
+        let (client_key, _) = server.poll_for_request().unwrap().unwrap();
+        server.send_update(client_key, &request, update).unwrap();
+
+        sleep(Duration::from_millis(50));
+
+        let updates = client.poll_for_updates();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].as_ref().unwrap(), &(request, update));
+
+        let responses = client.poll_for_responses();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(
+            responses[0].as_ref().unwrap(),
+            &(request, Response::new(request))
+        );
+    }
+
+    #[test]
+    fn test_udp_update_server_remove_client() {
+        let mut server: UdpUpdateServer<Request, Update, Response, i32> =
+            UdpUpdateServer::new_with(
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7018)),
+                vec![(
+                    0,
+                    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7019)),
+                )],
+            )
+            .unwrap();
+
+        let mut client: UdpUpdateClient<Request, Update, Response> = UdpUpdateClient::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7019)),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7018)),
+        )
+        .unwrap();
+
+        let request = Request::new();
+        client.send_request(request).unwrap();
+        sleep(Duration::from_millis(50));
+        let Ok(Some((key, request))) = server.poll_for_request() else {
+            panic!("Expected a request");
+        };
+
+        assert!(server.remove_client(&key));
+        assert!(!server.remove_client(&key));
+        assert!(server.get_clients().is_empty());
+
+        // The removed client no longer receives responses
+        assert!(matches!(
+            server.send_response(key, request, Response::new(request)),
+            Err(UdpUpdateClientServerError::UnknownClient)
+        ));
+        sleep(Duration::from_millis(50));
+        assert!(client.poll_for_response().unwrap().is_none());
+
+        client.send_request(request).unwrap();
+        sleep(Duration::from_millis(50));
+        assert!(matches!(
+            server.poll_for_request(),
+            Err(UdpUpdateClientServerError::UnknownRequester(_))
+        ));
+    }
+
+    #[test]
+    fn test_udp_update_client_max_in_flight() {
+        let mut server: UdpUpdateServer<Request, Update, Response, i32> =
+            UdpUpdateServer::new_with(
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7023)),
+                vec![(
+                    0,
+                    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7024)),
+                )],
+            )
+            .unwrap();
+
+        let mut client: UdpUpdateClient<Request, Update, Response> = UdpUpdateClient::new(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7024)),
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 7023)),
+        )
+        .unwrap();
+        client.set_max_in_flight(Some(2));
+
+        client.send_request(Request::new()).unwrap();
+        client.send_request(Request::new()).unwrap();
+        assert!(matches!(
+            client.send_request(Request::new()),
+            Err(UdpUpdateClientServerError::TooManyInFlight)
+        ));
+        assert_eq!(client.in_flight(), 2);
+
+        sleep(Duration::from_millis(50));
+
+        // An update doesn't complete a request
+        let Ok(Some((key, request))) = server.poll_for_request() else {
+            panic!("Expected a request");
+        };
+        server
+            .send_update(key, &request, Update::new(request))
+            .unwrap();
+        sleep(Duration::from_millis(50));
+        assert!(client.poll_for_update().unwrap().is_some());
+        assert!(matches!(
+            client.send_request(Request::new()),
+            Err(UdpUpdateClientServerError::TooManyInFlight)
+        ));
+
+        server
+            .send_response(key, request, Response::new(request))
+            .unwrap();
+        sleep(Duration::from_millis(50));
+        assert!(client.poll_for_response().unwrap().is_some());
+        assert_eq!(client.in_flight(), 1);
+        client.send_request(Request::new()).unwrap();
+        assert_eq!(client.in_flight(), 2);
+    }
 }
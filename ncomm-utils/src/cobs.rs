@@ -0,0 +1,211 @@
+//!
+//! Consistent Overhead Byte Stuffing (COBS)
+//!
+//! COBS encodes data so that it contains no zero bytes, allowing a zero byte
+//! to unambiguously mark the end of a message sent over a stream (i.e. a
+//! serial line or TCP stream).
+//!
+//! Encoding adds at most 1 byte of overhead for every 254 bytes of data (plus
+//! the zero delimiter).
+//!
+
+use crate::packing::PackingError;
+
+/// The byte that marks the end of an encoded message
+pub const DELIMITER: u8 = 0x00;
+
+/// Get the maximum length of the encoded form (including the trailing zero
+/// delimiter) of a message that is `len` bytes long.
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / 254 + 2
+}
+
+/// Encode the bytes of `src` into `dst` followed by a zero delimiter, returning
+/// the number of bytes written to `dst`.
+///
+/// Note: `dst` must be at least `max_encoded_len(src.len())` bytes long.
+pub fn encode(src: &[u8], dst: &mut [u8]) -> Result<usize, PackingError> {
+    if dst.len() < max_encoded_len(src.len()) {
+        return Err(PackingError::InvalidBufferSize {
+            expected: max_encoded_len(src.len()),
+            got: dst.len(),
+        });
+    }
+
+    let mut code_idx = 0;
+    let mut code = 1u8;
+    let mut out = 1;
+    for byte in src.iter() {
+        if *byte == 0 {
+            dst[code_idx] = code;
+            code_idx = out;
+            out += 1;
+            code = 1;
+        } else {
+            dst[out] = *byte;
+            out += 1;
+            code += 1;
+            if code == 0xFF {
+                dst[code_idx] = code;
+                code_idx = out;
+                out += 1;
+                code = 1;
+            }
+        }
+    }
+    dst[code_idx] = code;
+    dst[out] = DELIMITER;
+
+    Ok(out + 1)
+}
+
+/// Decode a single encoded message from `src` into `dst`, returning the number
+/// of bytes written to `dst`.
+///
+/// Decoding stops at the first zero delimiter (or the end of `src` if it has
+/// no delimiter).  An error is returned if the message is truncated or `dst`
+/// is too small to fit the decoded message.
+pub fn decode(src: &[u8], dst: &mut [u8]) -> Result<usize, PackingError> {
+    let mut idx = 0;
+    let mut out = 0;
+    while idx < src.len() && src[idx] != DELIMITER {
+        let code = src[idx] as usize;
+        let start = idx + 1;
+        let end = idx + code;
+        if end > src.len() {
+            return Err(PackingError::InvalidBufferSize {
+                expected: end,
+                got: src.len(),
+            });
+        }
+        // A delimiter inside of a block means the block was truncated
+        if let Some(delimiter) = src[start..end].iter().position(|v| *v == DELIMITER) {
+            return Err(PackingError::InvalidBufferSize {
+                expected: end,
+                got: start + delimiter,
+            });
+        }
+        if out + (end - start) > dst.len() {
+            return Err(PackingError::InvalidBufferSize {
+                expected: out + (end - start),
+                got: dst.len(),
+            });
+        }
+
+        dst[out..out + (end - start)].copy_from_slice(&src[start..end]);
+        out += end - start;
+        idx = end;
+
+        if code != 0xFF && idx < src.len() && src[idx] != DELIMITER {
+            if out >= dst.len() {
+                return Err(PackingError::InvalidBufferSize {
+                    expected: out + 1,
+                    got: dst.len(),
+                });
+            }
+            dst[out] = 0;
+            out += 1;
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(data: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![0u8; max_encoded_len(data.len())];
+        let encoded_len = encode(data, &mut encoded).unwrap();
+        assert_eq!(encoded[encoded_len - 1], DELIMITER);
+        assert!(!encoded[..encoded_len - 1].contains(&DELIMITER));
+
+        let mut decoded = vec![0u8; data.len()];
+        let decoded_len = decode(&encoded[..encoded_len], &mut decoded).unwrap();
+        assert_eq!(&decoded[..decoded_len], data);
+
+        encoded[..encoded_len].to_vec()
+    }
+
+    #[test]
+    fn test_encode_known_values() {
+        assert_eq!(round_trip(&[]), vec![0x01, 0x00]);
+        assert_eq!(round_trip(&[0x00]), vec![0x01, 0x01, 0x00]);
+        assert_eq!(round_trip(&[0x00, 0x00]), vec![0x01, 0x01, 0x01, 0x00]);
+        assert_eq!(
+            round_trip(&[0x11, 0x22, 0x00, 0x33]),
+            vec![0x03, 0x11, 0x22, 0x02, 0x33, 0x00]
+        );
+        assert_eq!(
+            round_trip(&[0x11, 0x00, 0x00, 0x00]),
+            vec![0x02, 0x11, 0x01, 0x01, 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_round_trip_zeros() {
+        round_trip(&[0u8; 300]);
+        round_trip(&[0, 1, 0, 2, 0, 3, 0]);
+    }
+
+    #[test]
+    fn test_round_trip_worst_case() {
+        let data: Vec<u8> = (1..=254).collect();
+        let encoded = round_trip(&data);
+        assert_eq!(encoded.len(), max_encoded_len(254));
+        assert_eq!(encoded[0], 0xFF);
+
+        let data: Vec<u8> = (0..1_000).map(|v| (v % 255 + 1) as u8).collect();
+        round_trip(&data);
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut encoded = [0u8; 4];
+        assert_eq!(
+            encode(&[1, 2, 3], &mut encoded),
+            Err(PackingError::InvalidBufferSize {
+                expected: 5,
+                got: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut decoded = [0u8; 8];
+        // Truncated message
+        assert_eq!(
+            decode(&[0x05, 0x11, 0x22], &mut decoded),
+            Err(PackingError::InvalidBufferSize {
+                expected: 5,
+                got: 3
+            })
+        );
+        // Delimiter inside of a block
+        assert_eq!(
+            decode(&[0x03, 0x11, 0x00, 0x22], &mut decoded),
+            Err(PackingError::InvalidBufferSize {
+                expected: 3,
+                got: 2
+            })
+        );
+        // Decoded message does not fit in the destination
+        let mut decoded = [0u8; 1];
+        assert_eq!(
+            decode(&[0x03, 0x11, 0x22, 0x00], &mut decoded),
+            Err(PackingError::InvalidBufferSize {
+                expected: 2,
+                got: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_stops_at_delimiter() {
+        let mut decoded = [0u8; 8];
+        let decoded_len = decode(&[0x02, 0x11, 0x00, 0x02, 0x22, 0x00], &mut decoded).unwrap();
+        assert_eq!(&decoded[..decoded_len], &[0x11]);
+    }
+}
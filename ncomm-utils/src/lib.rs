@@ -11,4 +11,5 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+pub mod cobs;
 pub mod packing;
@@ -9,9 +9,40 @@
 pub enum PackingError {
     /// The buffer to pack or unpack data from cannot be used as
     /// the data will not fit in the buffer.
-    InvalidBufferSize,
+    InvalidBufferSize {
+        /// The number of bytes the data needs
+        expected: usize,
+        /// The number of bytes that were available
+        got: usize,
+    },
+    /// The discriminant (i.e. the tag of an enum) of the data being unpacked
+    /// is unknown.
+    InvalidDiscriminant(u8),
+    /// The checksum of the data being unpacked does not match the data.
+    ChecksumMismatch,
+    /// The text being unpacked is not valid UTF-8.
+    Utf8Error,
 }
 
+impl core::fmt::Display for PackingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidBufferSize { expected, got } => write!(
+                f,
+                "invalid buffer size (expected {} bytes, got {})",
+                expected, got
+            ),
+            Self::InvalidDiscriminant(discriminant) => {
+                write!(f, "invalid discriminant {}", discriminant)
+            }
+            Self::ChecksumMismatch => write!(f, "checksum mismatch"),
+            Self::Utf8Error => write!(f, "invalid utf-8"),
+        }
+    }
+}
+
+impl core::error::Error for PackingError {}
+
 /// Trait implemented by data to be sent over network boundaries.
 pub trait Packable: Sized {
     /// Get the minimum necessary length of a buffer to pack this data
@@ -39,7 +70,10 @@ macro_rules! packable_primitive {
 
             fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
                 if buffer.len() < Self::len() {
-                    return Err(PackingError::InvalidBufferSize);
+                    return Err(PackingError::InvalidBufferSize {
+                        expected: Self::len(),
+                        got: buffer.len(),
+                    });
                 }
 
                 buffer[..Self::len()].copy_from_slice(&self.to_le_bytes()[..]);
@@ -48,7 +82,10 @@ macro_rules! packable_primitive {
 
             fn unpack(data: &[u8]) -> Result<Self, PackingError> {
                 if data.len() < Self::len() {
-                    return Err(PackingError::InvalidBufferSize);
+                    return Err(PackingError::InvalidBufferSize {
+                        expected: Self::len(),
+                        got: data.len(),
+                    });
                 }
 
                 Ok(Self::from_le_bytes(data[..Self::len()].try_into().unwrap()))
@@ -67,7 +104,10 @@ macro_rules! packable_primitive {
 
             fn pack(self, buffer: &mut [u8]) -> Result<(), PackingError> {
                 if buffer.len() < Self::len() {
-                    return Err(PackingError::InvalidBufferSize);
+                    return Err(PackingError::InvalidBufferSize {
+                        expected: Self::len(),
+                        got: buffer.len(),
+                    });
                 }
 
                 buffer[..Self::len()].copy_from_slice(&self.to_be_bytes()[..]);
@@ -76,7 +116,10 @@ macro_rules! packable_primitive {
 
             fn unpack(data: &[u8]) -> Result<Self, PackingError> {
                 if data.len() < Self::len() {
-                    return Err(PackingError::InvalidBufferSize);
+                    return Err(PackingError::InvalidBufferSize {
+                        expected: Self::len(),
+                        got: data.len(),
+                    });
                 }
 
                 Ok(Self::from_be_bytes(data[..Self::len()].try_into().unwrap()))
@@ -100,6 +143,184 @@ packable_primitive!(isize, 8);
 packable_primitive!(f32, 4);
 packable_primitive!(f64, 8);
 
+/// The byte order multi-byte values are packed in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// Most significant byte first (i.e. network byte order)
+    #[cfg_attr(not(feature = "little-endian"), default)]
+    Big,
+    /// Least significant byte first
+    #[cfg_attr(feature = "little-endian", default)]
+    Little,
+}
+
+impl Endianness {
+    /// The byte order primitives are packed in by their Packable implementations
+    /// (little-endian with the `little-endian` feature and big-endian otherwise)
+    #[cfg(feature = "little-endian")]
+    pub const PACKABLE: Self = Self::Little;
+    /// The byte order primitives are packed in by their Packable implementations
+    /// (little-endian with the `little-endian` feature and big-endian otherwise)
+    #[cfg(not(feature = "little-endian"))]
+    pub const PACKABLE: Self = Self::Big;
+}
+
+macro_rules! endian_helpers {
+    (
+        $primitive: ident,
+        $pack: ident,
+        $pack_be: ident,
+        $pack_le: ident,
+        $unpack: ident,
+        $unpack_be: ident,
+        $unpack_le: ident
+    ) => {
+        #[doc = concat!("Pack a `", stringify!($primitive), "` into a buffer in big-endian byte order")]
+        pub fn $pack_be(value: $primitive, buffer: &mut [u8]) -> Result<(), PackingError> {
+            $pack(value, buffer, Endianness::Big)
+        }
+
+        #[doc = concat!("Pack a `", stringify!($primitive), "` into a buffer in little-endian byte order")]
+        pub fn $pack_le(value: $primitive, buffer: &mut [u8]) -> Result<(), PackingError> {
+            $pack(value, buffer, Endianness::Little)
+        }
+
+        #[doc = concat!("Pack a `", stringify!($primitive), "` into a buffer in a given byte order")]
+        pub fn $pack(
+            value: $primitive,
+            buffer: &mut [u8],
+            endianness: Endianness,
+        ) -> Result<(), PackingError> {
+            const LEN: usize = core::mem::size_of::<$primitive>();
+            if buffer.len() < LEN {
+                return Err(PackingError::InvalidBufferSize { expected: LEN, got: buffer.len() });
+            }
+
+            let bytes = match endianness {
+                Endianness::Big => value.to_be_bytes(),
+                Endianness::Little => value.to_le_bytes(),
+            };
+            buffer[..LEN].copy_from_slice(&bytes);
+            Ok(())
+        }
+
+        #[doc = concat!("Unpack a `", stringify!($primitive), "` from big-endian bytes")]
+        pub fn $unpack_be(data: &[u8]) -> Result<$primitive, PackingError> {
+            $unpack(data, Endianness::Big)
+        }
+
+        #[doc = concat!("Unpack a `", stringify!($primitive), "` from little-endian bytes")]
+        pub fn $unpack_le(data: &[u8]) -> Result<$primitive, PackingError> {
+            $unpack(data, Endianness::Little)
+        }
+
+        #[doc = concat!("Unpack a `", stringify!($primitive), "` from bytes in a given byte order")]
+        pub fn $unpack(data: &[u8], endianness: Endianness) -> Result<$primitive, PackingError> {
+            const LEN: usize = core::mem::size_of::<$primitive>();
+            if data.len() < LEN {
+                return Err(PackingError::InvalidBufferSize { expected: LEN, got: data.len() });
+            }
+
+            let bytes = data[..LEN].try_into().unwrap();
+            Ok(match endianness {
+                Endianness::Big => $primitive::from_be_bytes(bytes),
+                Endianness::Little => $primitive::from_le_bytes(bytes),
+            })
+        }
+    };
+}
+
+endian_helpers!(
+    u16,
+    pack_u16,
+    pack_u16_be,
+    pack_u16_le,
+    unpack_u16,
+    unpack_u16_be,
+    unpack_u16_le
+);
+endian_helpers!(
+    u32,
+    pack_u32,
+    pack_u32_be,
+    pack_u32_le,
+    unpack_u32,
+    unpack_u32_be,
+    unpack_u32_le
+);
+endian_helpers!(
+    u64,
+    pack_u64,
+    pack_u64_be,
+    pack_u64_le,
+    unpack_u64,
+    unpack_u64_be,
+    unpack_u64_le
+);
+endian_helpers!(
+    u128,
+    pack_u128,
+    pack_u128_be,
+    pack_u128_le,
+    unpack_u128,
+    unpack_u128_be,
+    unpack_u128_le
+);
+endian_helpers!(
+    i16,
+    pack_i16,
+    pack_i16_be,
+    pack_i16_le,
+    unpack_i16,
+    unpack_i16_be,
+    unpack_i16_le
+);
+endian_helpers!(
+    i32,
+    pack_i32,
+    pack_i32_be,
+    pack_i32_le,
+    unpack_i32,
+    unpack_i32_be,
+    unpack_i32_le
+);
+endian_helpers!(
+    i64,
+    pack_i64,
+    pack_i64_be,
+    pack_i64_le,
+    unpack_i64,
+    unpack_i64_be,
+    unpack_i64_le
+);
+endian_helpers!(
+    i128,
+    pack_i128,
+    pack_i128_be,
+    pack_i128_le,
+    unpack_i128,
+    unpack_i128_be,
+    unpack_i128_le
+);
+endian_helpers!(
+    f32,
+    pack_f32,
+    pack_f32_be,
+    pack_f32_le,
+    unpack_f32,
+    unpack_f32_be,
+    unpack_f32_le
+);
+endian_helpers!(
+    f64,
+    pack_f64,
+    pack_f64_be,
+    pack_f64_le,
+    unpack_f64,
+    unpack_f64_be,
+    unpack_f64_le
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +351,68 @@ mod tests {
     test_primitive_packing!(isize, 16, 129isize, test_isize_packing);
     test_primitive_packing!(f32, 4, 2.01f32, test_f32_packing);
     test_primitive_packing!(f64, 8, 2.01f64, test_f64_packing);
+
+    #[test]
+    fn test_endian_helpers() {
+        let mut buffer = [0u8; 4];
+        pack_u32_be(0x0102_0304, &mut buffer).unwrap();
+        assert_eq!(buffer, [1, 2, 3, 4]);
+        assert_eq!(unpack_u32_be(&buffer), Ok(0x0102_0304));
+
+        pack_u32_le(0x0102_0304, &mut buffer).unwrap();
+        assert_eq!(buffer, [4, 3, 2, 1]);
+        assert_eq!(unpack_u32_le(&buffer), Ok(0x0102_0304));
+
+        assert_eq!(
+            pack_u64_be(1, &mut buffer),
+            Err(PackingError::InvalidBufferSize {
+                expected: 8,
+                got: 4
+            })
+        );
+        assert_eq!(
+            unpack_u64_le(&buffer),
+            Err(PackingError::InvalidBufferSize {
+                expected: 8,
+                got: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_endian_helpers_round_trip() {
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let mut buffer = [0u8; 16];
+            pack_i16(-129, &mut buffer, endianness).unwrap();
+            assert_eq!(unpack_i16(&buffer, endianness), Ok(-129));
+            pack_u128(u128::MAX - 1, &mut buffer, endianness).unwrap();
+            assert_eq!(unpack_u128(&buffer, endianness), Ok(u128::MAX - 1));
+            pack_f64(2.01, &mut buffer, endianness).unwrap();
+            assert_eq!(unpack_f64(&buffer, endianness), Ok(2.01));
+        }
+
+        // Primitives are packed in the same byte order as the default endianness
+        let mut buffer = [0u8; 2];
+        let mut expected = [0u8; 2];
+        0x0102u16.pack(&mut buffer).unwrap();
+        pack_u16(0x0102, &mut expected, Endianness::default()).unwrap();
+        assert_eq!(buffer, expected);
+        assert_eq!(Endianness::default(), Endianness::PACKABLE);
+    }
+
+    #[test]
+    fn test_truncated_unpack_error() {
+        let error = u32::unpack(&[1, 2]).unwrap_err();
+        assert_eq!(
+            error,
+            PackingError::InvalidBufferSize {
+                expected: 4,
+                got: 2
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "invalid buffer size (expected 4 bytes, got 2)"
+        );
+    }
 }
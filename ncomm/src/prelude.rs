@@ -2,5 +2,21 @@
 //! Re-export of NComm traits to make working with
 //! NComm a bit easier
 //!
+//! The prelude contains the items most applications need, so the minimal
+//! examples only require a single glob import:
+//!
+//! ```rust
+//! use ncomm::prelude::*;
+//!
+//! let (_, interrupt) = crossbeam::channel::unbounded();
+//! let mut executor: SimpleExecutor<u8> = SimpleExecutor::new(interrupt);
+//! executor.update_for_ms(10);
+//! assert!(executor.node_ids().is_empty());
+//! ```
+//!
 
 pub use ncomm_core::*;
+pub use ncomm_utils::packing::Packable;
+
+#[cfg(feature = "std")]
+pub use ncomm_executors::{SimpleExecutor, ThreadPoolExecutor, ThreadedExecutor};